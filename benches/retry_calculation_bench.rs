@@ -65,15 +65,29 @@ fn bench_should_retry_with_error(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'test'".to_string(),
             timeout_seconds: None,
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
@@ -92,8 +106,13 @@ fn bench_should_retry_with_error(c: &mut Criterion) {
             output: None,
             error: Some("Error occurred".to_string()),
             exit_code: Some(1),
+            failed_assertion: None,
         }),
         triggered_by: rapidcron::types::TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -120,15 +139,29 @@ fn bench_should_retry_exceeded(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'test'".to_string(),
             timeout_seconds: None,
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(2),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
@@ -147,8 +180,13 @@ fn bench_should_retry_exceeded(c: &mut Criterion) {
             output: None,
             error: Some("Error occurred".to_string()),
             exit_code: Some(1),
+            failed_assertion: None,
         }),
         triggered_by: rapidcron::types::TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -173,15 +211,29 @@ fn bench_calculate_retry_delay_fixed(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'test'".to_string(),
             timeout_seconds: None,
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
@@ -200,8 +252,13 @@ fn bench_calculate_retry_delay_fixed(c: &mut Criterion) {
             output: None,
             error: Some("Error occurred".to_string()),
             exit_code: Some(1),
+            failed_assertion: None,
         }),
         triggered_by: rapidcron::types::TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -227,15 +284,29 @@ fn bench_calculate_retry_delay_exponential(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'test'".to_string(),
             timeout_seconds: None,
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(5),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
@@ -254,8 +325,13 @@ fn bench_calculate_retry_delay_exponential(c: &mut Criterion) {
             output: None,
             error: Some("Error occurred".to_string()),
             exit_code: Some(1),
+            failed_assertion: None,
         }),
         triggered_by: rapidcron::types::TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -287,15 +363,29 @@ fn bench_calculate_retry_delay_linear(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'test'".to_string(),
             timeout_seconds: None,
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(5),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
@@ -314,8 +404,13 @@ fn bench_calculate_retry_delay_linear(c: &mut Criterion) {
             output: None,
             error: Some("Error occurred".to_string()),
             exit_code: Some(1),
+            failed_assertion: None,
         }),
         triggered_by: rapidcron::types::TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -356,8 +451,13 @@ fn bench_batch_retry_calculation(c: &mut Criterion) {
                 output: None,
                 error: Some("Error occurred".to_string()),
                 exit_code: Some(1),
+                failed_assertion: None,
             }),
             triggered_by: rapidcron::types::TriggeredBy::Scheduler,
+            priority_score: None,
+            sla_flags: Vec::new(),
+            trigger_payload: None,
+            parameters: None,
             created_at: Utc::now(),
         })
         .collect();
@@ -7,13 +7,32 @@ fn bench_create_task_request_command(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: Some("Test task".to_string()),
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'Hello World'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     c.bench_function("create_task_command", |b| {
@@ -26,13 +45,32 @@ fn bench_create_task_request_http(c: &mut Criterion) {
         name: "http-task".to_string(),
         description: Some("HTTP task".to_string()),
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("http".to_string()),
         schedule: "0 * * * * *".to_string(),
         enabled: true,
         command: None,
         url: Some("http://example.com/api".to_string()),
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     c.bench_function("create_task_http", |b| {
@@ -49,13 +87,32 @@ fn bench_create_task_request_with_dependencies(c: &mut Criterion) {
             "507f1f77bcf86cd799439012".to_string(),
             "507f1f77bcf86cd799439013".to_string(),
         ],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/10 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'test'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(60),
         max_retries: Some(5),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     c.bench_function("create_task_with_dependencies", |b| {
@@ -68,13 +125,32 @@ fn bench_create_task_request_complex_schedule(c: &mut Criterion) {
         name: "complex-task".to_string(),
         description: Some("Complex schedule task".to_string()),
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0,15,30,45 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'complex'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     c.bench_function("create_task_complex_schedule", |b| {
@@ -88,13 +164,32 @@ fn bench_create_task_request_batch(c: &mut Criterion) {
             name: format!("task-{}", i),
             description: Some(format!("Test task {}", i)),
             dependency_ids: vec![],
+            tags: vec![],
             task_type: Some("command".to_string()),
             schedule: "0/5 * * * * *".to_string(),
             enabled: true,
             command: Some(format!("echo 'Task {}'", i)),
             url: None,
+            interpreter: None,
+            script: None,
+            wasm: None,
+            grpc: None,
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
+            assertions: None,
             timeout_seconds: Some(30),
             max_retries: Some(3),
+            priority: None,
+            deadline_seconds: None,
+            start_sla_seconds: None,
+            run_sla_seconds: None,
+            max_concurrent_instances: None,
+            rate_limit: None,
+            parameters: vec![],
         })
         .collect();
 
@@ -116,15 +211,29 @@ fn bench_task_serialization(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: Some("Test task".to_string()),
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'Hello World'".to_string(),
             timeout_seconds: Some(30),
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
@@ -144,15 +253,29 @@ fn bench_task_deserialization(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: Some("Test task".to_string()),
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'Hello World'".to_string(),
             timeout_seconds: Some(30),
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
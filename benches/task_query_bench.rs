@@ -39,6 +39,10 @@ fn bench_task_instance_serialization(c: &mut Criterion) {
         retry_count: 0,
         result: None,
         triggered_by: rapidcron::types::TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -59,6 +63,10 @@ fn bench_task_instance_deserialization(c: &mut Criterion) {
         retry_count: 0,
         result: None,
         triggered_by: rapidcron::types::TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -77,15 +85,29 @@ fn bench_task_serialization(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: Some("Test task".to_string()),
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'Hello World'".to_string(),
             timeout_seconds: Some(30),
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
@@ -102,15 +124,29 @@ fn bench_task_deserialization(c: &mut Criterion) {
         name: "test-task".to_string(),
         description: Some("Test task".to_string()),
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'Hello World'".to_string(),
             timeout_seconds: Some(30),
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
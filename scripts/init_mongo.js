@@ -8,6 +8,10 @@ db.tasks.deleteMany({});
 db.task_instances.deleteMany({});
 db.execution_logs.deleteMany({});
 db.dispatch_logs.deleteMany({});
+db.backfill_jobs.deleteMany({});
+db.sla_events.deleteMany({});
+db.pauses.deleteMany({});
+db.webhooks.deleteMany({});
 print("✓ 旧数据已清理");
 
 function ensureCollection(name) {
@@ -31,6 +35,7 @@ db.tasks.createIndex(
   }
 );
 db.tasks.createIndex({ "dependency_ids": 1 });
+db.tasks.createIndex({ "tags": 1 });
 db.tasks.createIndex(
   { enabled: 1 },
   {
@@ -202,4 +207,44 @@ db.dispatch_logs.createIndex({ scan_time: -1 });
 db.dispatch_logs.createIndex({ scan_window_start: -1, scan_window_end: -1 });
 db.dispatch_logs.createIndex({ error_message: 1, scan_time: -1 });
 
+// ======================
+// 5. backfill_jobs 集合
+// ======================
+
+ensureCollection("backfill_jobs");
+
+// 创建索引
+db.backfill_jobs.createIndex({ task_id: 1, created_at: -1 });
+db.backfill_jobs.createIndex({ status: 1 });
+
+// ======================
+// 6. sla_events 集合
+// ======================
+
+ensureCollection("sla_events");
+
+// 创建索引
+db.sla_events.createIndex({ detected_at: -1 });
+db.sla_events.createIndex({ task_id: 1, detected_at: -1 });
+
+// ======================
+// 7. pauses 集合
+// ======================
+
+ensureCollection("pauses");
+
+// 创建索引
+db.pauses.createIndex({ starts_at: -1 });
+db.pauses.createIndex({ resumed_at: 1, ends_at: 1 });
+
+// ======================
+// 8. webhooks 集合
+// ======================
+
+ensureCollection("webhooks");
+
+// 创建索引
+db.webhooks.createIndex({ token: 1 }, { unique: true });
+db.webhooks.createIndex({ task_id: 1 }, { unique: true });
+
 print("✅ Database initialized with collections, indexes, and sample data.");
\ No newline at end of file
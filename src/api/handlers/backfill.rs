@@ -0,0 +1,210 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use mongodb::bson::doc;
+
+use crate::{
+    error::Error,
+    scheduler::{
+        backfill::{BackfillRunner, pending_slots},
+        cron_parser::CronParser,
+    },
+    types::{
+        ApiResponse, BackfillJob, BackfillRequest, BackfillStatus, PaginatedResponse,
+        parse_object_id,
+    },
+};
+
+use super::super::models::api_state::ApiState;
+
+/// 回填作业列表查询参数
+#[derive(Debug, serde::Deserialize)]
+pub struct BackfillListQuery {
+    pub task_id: Option<String>,
+    pub status: Option<String>,
+    pub page: Option<String>,
+    pub page_size: Option<String>,
+}
+
+/// 为任务创建回填作业
+pub async fn create_backfill(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Json(req): Json<BackfillRequest>,
+) -> Result<Json<ApiResponse<BackfillJob>>, Error> {
+    let object_id = parse_object_id(&id).map_err(Error::Validation)?;
+
+    let task_queue = state
+        .task_queue
+        .clone()
+        .ok_or_else(|| Error::Execution("任务队列未配置，无法回填".to_string()))?;
+
+    let task = state
+        .db
+        .get_task(object_id)
+        .await?
+        .filter(|task| task.deleted_at.is_none())
+        .ok_or_else(|| Error::Execution("任务不存在".to_string()))?;
+    if !task.enabled {
+        return Err(Error::Validation("任务未启用".to_string()));
+    }
+
+    let now = chrono::Utc::now();
+    let range_start = chrono::DateTime::from_timestamp(req.from, 0)
+        .ok_or_else(|| Error::Validation("无效的开始时间".to_string()))?;
+    let range_end = chrono::DateTime::from_timestamp(req.to, 0)
+        .ok_or_else(|| Error::Validation("无效的结束时间".to_string()))?;
+    if range_start >= range_end {
+        return Err(Error::Validation("开始时间必须早于结束时间".to_string()));
+    }
+    if range_end > now {
+        return Err(Error::Validation(
+            "回填结束时间不能晚于当前时间".to_string(),
+        ));
+    }
+
+    let config = &state.backfill;
+    let rate_per_sec = req.rate_per_sec.unwrap_or(config.default_rate_per_sec);
+    if rate_per_sec == 0 || rate_per_sec > config.max_rate_per_sec {
+        return Err(Error::Validation(format!(
+            "发布速率必须在1到{}之间",
+            config.max_rate_per_sec
+        )));
+    }
+
+    let parser = CronParser::new(&task.schedule)?;
+    let slots = parser.triggers_between(range_start, range_end, config.max_slots + 1);
+    if slots.len() > config.max_slots {
+        return Err(Error::Validation(format!(
+            "回填区间内的调度时间超过上限 {}",
+            config.max_slots
+        )));
+    }
+
+    let existing = state
+        .db
+        .find_task_instances(
+            Some(doc! {
+                "task_id": object_id,
+                "scheduled_time": { "$gte": range_start, "$lt": range_end },
+            }),
+            None,
+        )
+        .await?;
+    let total_slots = slots.len();
+    let (pending, skipped) = pending_slots(slots, &existing);
+
+    let mut job = BackfillJob {
+        id: None,
+        task_id: object_id,
+        task_name: task.name.clone(),
+        range_start,
+        range_end,
+        status: BackfillStatus::Running,
+        rate_per_sec,
+        total_slots: total_slots as i32,
+        skipped_slots: skipped as i32,
+        dispatched_instances: 0,
+        error_message: None,
+        created_at: now,
+        finished_at: None,
+    };
+    if pending.is_empty() {
+        job.status = BackfillStatus::Completed;
+        job.finished_at = Some(now);
+    }
+
+    let job_id = state.db.create_backfill_job(&job).await?;
+    job.id = Some(job_id);
+
+    if !pending.is_empty() {
+        let runner = BackfillRunner::new(state.db.clone(), task_queue, job_id, task, rate_per_sec);
+        tokio::spawn(runner.run(pending));
+    }
+
+    Ok(Json(ApiResponse::success(job)))
+}
+
+/// 获取回填作业列表
+pub async fn list_backfills(
+    State(state): State<ApiState>,
+    Query(query): Query<BackfillListQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<BackfillJob>>>, Error> {
+    let mut filter = doc! {};
+
+    if let Some(task_id) = query.task_id {
+        let object_id = parse_object_id(&task_id).map_err(Error::Validation)?;
+        filter.insert("task_id", object_id);
+    }
+
+    if let Some(status) = query.status {
+        let status_value = match status.as_str() {
+            "running" => "running",
+            "completed" => "completed",
+            "cancelled" => "cancelled",
+            "failed" => "failed",
+            _ => return Err(Error::Validation("无效的回填状态".to_string())),
+        };
+        filter.insert("status", status_value);
+    }
+
+    let page = query
+        .page
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(1);
+    let page_size = query
+        .page_size
+        .and_then(|ps| ps.parse::<usize>().ok())
+        .unwrap_or(20);
+
+    let jobs = state.db.find_backfill_jobs(Some(filter)).await?;
+
+    Ok(Json(ApiResponse::success(PaginatedResponse::from_items(
+        jobs, page, page_size,
+    ))))
+}
+
+/// 获取回填作业详情（含进度）
+pub async fn get_backfill(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<BackfillJob>>, Error> {
+    let object_id = parse_object_id(&id).map_err(Error::Validation)?;
+
+    let job = state
+        .db
+        .get_backfill_job(object_id)
+        .await?
+        .ok_or_else(|| Error::Execution("回填作业不存在".to_string()))?;
+
+    Ok(Json(ApiResponse::success(job)))
+}
+
+/// 取消回填作业，已发布的实例不受影响
+pub async fn cancel_backfill(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<BackfillJob>>, Error> {
+    let object_id = parse_object_id(&id).map_err(Error::Validation)?;
+
+    let cancelled = state
+        .db
+        .update_running_backfill_job(
+            object_id,
+            doc! { "$set": { "status": "cancelled", "finished_at": chrono::Utc::now() } },
+        )
+        .await?;
+
+    let job = state
+        .db
+        .get_backfill_job(object_id)
+        .await?
+        .ok_or_else(|| Error::Execution("回填作业不存在".to_string()))?;
+
+    if !cancelled {
+        return Err(Error::Validation("回填作业已结束，无法取消".to_string()));
+    }
+
+    Ok(Json(ApiResponse::success(job)))
+}
@@ -17,6 +17,9 @@ struct ExecutorNodeInfo {
     cpu_usage: f64,
     memory_usage: f64,
     memory_total: u64,
+    /// 执行器正在执行的实例数，旧版本执行器不返回该字段
+    #[serde(default)]
+    active_tasks: Option<u64>,
 }
 
 /// API 状态（扩展以支持集群信息）
@@ -82,6 +85,7 @@ pub async fn get_cluster_info(
             memory_total: 0,
             active_tasks: running_instances,
             metadata: service.metadata.clone(),
+            labels: service.labels.clone(),
         };
 
         if service.metadata.as_deref() == Some("executor") {
@@ -92,6 +96,9 @@ pub async fn get_cluster_info(
                 node.cpu_usage = node_info.cpu_usage;
                 node.memory_usage = node_info.memory_usage;
                 node.memory_total = node_info.memory_total;
+                if let Some(active_tasks) = node_info.active_tasks {
+                    node.active_tasks = active_tasks;
+                }
             }
         } else if service.metadata.as_deref() == Some("dispatcher") {
             let mut system = System::new_all();
@@ -6,7 +6,7 @@ use mongodb::bson::{Bson, doc};
 
 use crate::{
     error::Error,
-    types::{ApiResponse, DispatchLog, PaginatedResponse, parse_object_id},
+    types::{ApiResponse, DispatchLog, DispatchTaskDetail, PaginatedResponse, parse_object_id},
 };
 
 use super::super::models::api_state::ApiState;
@@ -21,7 +21,17 @@ pub struct DispatchLogListQuery {
     pub page_size: Option<String>,
 }
 
-/// 获取分发日志列表
+/// 分发日志任务明细查询参数
+#[derive(Debug, serde::Deserialize)]
+pub struct DispatchTaskDetailQuery {
+    pub task_id: Option<String>,
+    /// 只返回处理失败的任务
+    pub failed: Option<bool>,
+    pub page: Option<String>,
+    pub page_size: Option<String>,
+}
+
+/// 获取分发日志列表（不含任务明细）
 pub async fn list_dispatch_logs(
     State(state): State<ApiState>,
     Query(query): Query<DispatchLogListQuery>,
@@ -67,7 +77,11 @@ pub async fn list_dispatch_logs(
         .and_then(|ps| ps.parse::<usize>().ok())
         .unwrap_or(20);
 
-    let logs = state.db.find_dispatch_logs(Some(filter), None).await?;
+    let mut logs = state.db.find_dispatch_logs(Some(filter), None).await?;
+    for log in &mut logs {
+        log.task_details.clear();
+        log.deferred_slots.clear();
+    }
 
     Ok(Json(ApiResponse::success(PaginatedResponse::from_items(
         logs, page, page_size,
@@ -89,3 +103,45 @@ pub async fn get_dispatch_log(
 
     Ok(Json(ApiResponse::success(log)))
 }
+
+/// 获取一次扫描的按任务分发明细
+pub async fn get_dispatch_log_tasks(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<DispatchTaskDetailQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<DispatchTaskDetail>>>, Error> {
+    let object_id = parse_object_id(&id).map_err(Error::Validation)?;
+    let task_id = query
+        .task_id
+        .map(|task_id| parse_object_id(&task_id).map_err(Error::Validation))
+        .transpose()?;
+
+    let log = state
+        .db
+        .get_dispatch_log(object_id)
+        .await?
+        .ok_or_else(|| Error::Execution("分发日志不存在".to_string()))?;
+
+    let details: Vec<DispatchTaskDetail> = log
+        .task_details
+        .into_iter()
+        .filter(|detail| task_id.is_none_or(|task_id| detail.task_id == task_id))
+        .filter(|detail| match query.failed {
+            Some(failed) => detail.error.is_some() == failed,
+            None => true,
+        })
+        .collect();
+
+    let page = query
+        .page
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(1);
+    let page_size = query
+        .page_size
+        .and_then(|ps| ps.parse::<usize>().ok())
+        .unwrap_or(20);
+
+    Ok(Json(ApiResponse::success(PaginatedResponse::from_items(
+        details, page, page_size,
+    ))))
+}
@@ -48,6 +48,8 @@ pub async fn list_execution_logs(
             "success" => "success",
             "failed" => "failed",
             "cancelled" => "cancelled",
+            "timed_out" => "timed_out",
+            "interrupted" => "interrupted",
             _ => return Err(Error::Validation("无效的任务状态".to_string())),
         };
         filter.insert("status", task_status);
@@ -57,6 +59,8 @@ pub async fn list_execution_logs(
         let triggered_by_value = match triggered_by.as_str() {
             "scheduler" => "scheduler",
             "manual" => "manual",
+            "backfill" => "backfill",
+            "webhook" => "webhook",
             _ => return Err(Error::Validation("无效的触发方式".to_string())),
         };
         filter.insert("triggered_by", triggered_by_value);
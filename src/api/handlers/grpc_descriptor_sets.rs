@@ -0,0 +1,109 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use base64::Engine;
+use mongodb::bson;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::Error,
+    executor::grpc::parse_descriptor_set,
+    types::{
+        ApiResponse, CreateGrpcDescriptorSetRequest, GrpcDescriptorSet, GrpcDescriptorSetResponse,
+        MAX_GRPC_DESCRIPTOR_SET_BYTES, validate_grpc_descriptor_set_name,
+    },
+};
+
+use super::super::models::api_state::ApiState;
+
+/// 上传描述符集，描述符集须能完整解析（包含全部依赖）且至少定义一个服务
+pub async fn create_grpc_descriptor_set(
+    State(state): State<ApiState>,
+    Json(req): Json<CreateGrpcDescriptorSetRequest>,
+) -> Result<Json<ApiResponse<GrpcDescriptorSetResponse>>, Error> {
+    validate_grpc_descriptor_set_name(&req.name).map_err(Error::Validation)?;
+    if req
+        .description
+        .as_deref()
+        .is_some_and(|description| description.len() > 500)
+    {
+        return Err(Error::Validation(
+            "描述符集描述长度不能超过500个字符".to_string(),
+        ));
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(req.descriptor_set_base64.trim())
+        .map_err(|e| {
+            Error::Validation(format!("descriptor_set_base64 不是有效的 base64: {}", e))
+        })?;
+    if bytes.len() > MAX_GRPC_DESCRIPTOR_SET_BYTES {
+        return Err(Error::Validation(format!(
+            "描述符集不能超过{}字节",
+            MAX_GRPC_DESCRIPTOR_SET_BYTES
+        )));
+    }
+    let (_, services) = parse_descriptor_set(&bytes).map_err(Error::Validation)?;
+
+    if state
+        .db
+        .get_grpc_descriptor_set_by_name(&req.name)
+        .await?
+        .is_some()
+    {
+        return Err(Error::Validation(format!("描述符集已存在: {}", req.name)));
+    }
+
+    let now = chrono::Utc::now();
+    let mut set = GrpcDescriptorSet {
+        id: None,
+        name: req.name,
+        description: req.description,
+        sha256: hex::encode(Sha256::digest(&bytes)),
+        size_bytes: bytes.len() as i64,
+        services,
+        data: bson::Binary {
+            subtype: bson::spec::BinarySubtype::Generic,
+            bytes,
+        },
+        created_at: now,
+        updated_at: now,
+    };
+    set.id = Some(state.db.create_grpc_descriptor_set(&set).await?);
+
+    Ok(Json(ApiResponse::success(set.into())))
+}
+
+/// 获取描述符集列表（不包含描述符集内容）
+pub async fn list_grpc_descriptor_sets(
+    State(state): State<ApiState>,
+) -> Result<Json<ApiResponse<Vec<GrpcDescriptorSetResponse>>>, Error> {
+    let sets = state.db.find_grpc_descriptor_sets().await?;
+    Ok(Json(ApiResponse::success(sets)))
+}
+
+/// 获取描述符集详情（不包含描述符集内容）
+pub async fn get_grpc_descriptor_set(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<GrpcDescriptorSetResponse>>, Error> {
+    let set = state
+        .db
+        .get_grpc_descriptor_set_by_name(&name)
+        .await?
+        .ok_or_else(|| Error::Execution("描述符集不存在".to_string()))?;
+
+    Ok(Json(ApiResponse::success(set.into())))
+}
+
+/// 删除描述符集，引用它的任务之后执行时会失败
+pub async fn delete_grpc_descriptor_set(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<()>>, Error> {
+    if !state.db.delete_grpc_descriptor_set_by_name(&name).await? {
+        return Err(Error::Execution("描述符集不存在".to_string()));
+    }
+
+    Ok(Json(ApiResponse::success(())))
+}
@@ -3,3 +3,10 @@ pub mod tasks;
 pub mod auth;
 pub mod dispatch;
 pub mod execution;
+pub mod backfill;
+pub mod sla;
+pub mod pauses;
+pub mod secrets;
+pub mod webhooks;
+pub mod wasm_modules;
+pub mod grpc_descriptor_sets;
\ No newline at end of file
@@ -0,0 +1,181 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use mongodb::bson::doc;
+
+use crate::{
+    error::Error,
+    scheduler::pause::cancel_queued_instances,
+    types::{
+        ApiResponse, CreatePauseRequest, PaginatedResponse, Pause, PauseResponse, PauseScope,
+        parse_object_id,
+    },
+};
+
+use super::super::models::api_state::ApiState;
+
+/// 暂停列表查询参数
+#[derive(Debug, serde::Deserialize)]
+pub struct PauseListQuery {
+    /// 仅返回当前生效（或未生效）的暂停
+    pub active: Option<bool>,
+    pub page: Option<String>,
+    pub page_size: Option<String>,
+}
+
+fn to_response(pause: Pause, now: &chrono::DateTime<chrono::Utc>) -> PauseResponse {
+    let active = pause.is_active_at(now);
+    PauseResponse { pause, active }
+}
+
+/// 创建暂停，可指定开始时间与自动结束时间
+pub async fn create_pause(
+    State(state): State<ApiState>,
+    Json(req): Json<CreatePauseRequest>,
+) -> Result<Json<ApiResponse<PauseResponse>>, Error> {
+    match &req.scope {
+        PauseScope::Global => {}
+        PauseScope::Tag { tag } => {
+            if tag.trim().is_empty() {
+                return Err(Error::Validation("暂停标签不能为空".to_string()));
+            }
+        }
+        PauseScope::Tasks { task_ids } => {
+            if task_ids.is_empty() {
+                return Err(Error::Validation("暂停的任务列表不能为空".to_string()));
+            }
+        }
+    }
+
+    let now = chrono::Utc::now();
+    let starts_at = match req.starts_at {
+        Some(starts_at) => chrono::DateTime::from_timestamp(starts_at, 0)
+            .ok_or_else(|| Error::Validation("无效的开始时间".to_string()))?,
+        None => now,
+    };
+    let ends_at = req
+        .ends_at
+        .map(|ends_at| {
+            chrono::DateTime::from_timestamp(ends_at, 0)
+                .ok_or_else(|| Error::Validation("无效的结束时间".to_string()))
+        })
+        .transpose()?;
+    if let Some(ends_at) = ends_at {
+        if ends_at <= starts_at {
+            return Err(Error::Validation("结束时间必须晚于开始时间".to_string()));
+        }
+        if ends_at <= now {
+            return Err(Error::Validation("结束时间必须晚于当前时间".to_string()));
+        }
+    }
+
+    let mut pause = Pause {
+        id: None,
+        scope: req.scope,
+        reason: req.reason,
+        queued_action: req.queued_action,
+        starts_at,
+        ends_at,
+        resumed_at: None,
+        created_at: now,
+    };
+    let pause_id = state.db.create_pause(&pause).await?;
+    pause.id = Some(pause_id);
+
+    // 立即生效的暂停马上处理排队中的实例，不必等待下一次扫描
+    if pause.is_active_at(&now) {
+        cancel_queued_instances(&state.db, &pause, now).await?;
+    }
+
+    Ok(Json(ApiResponse::success(to_response(pause, &now))))
+}
+
+/// 获取暂停列表
+pub async fn list_pauses(
+    State(state): State<ApiState>,
+    Query(query): Query<PauseListQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<PauseResponse>>>, Error> {
+    let now = chrono::Utc::now();
+
+    let pauses = match query.active {
+        Some(true) => state.db.find_active_pauses(now).await?,
+        Some(false) => state
+            .db
+            .find_pauses(None)
+            .await?
+            .into_iter()
+            .filter(|pause| !pause.is_active_at(&now))
+            .collect(),
+        None => state.db.find_pauses(None).await?,
+    };
+
+    let page = query
+        .page
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(1);
+    let page_size = query
+        .page_size
+        .and_then(|ps| ps.parse::<usize>().ok())
+        .unwrap_or(20);
+
+    let pauses = pauses
+        .into_iter()
+        .map(|pause| to_response(pause, &now))
+        .collect();
+
+    Ok(Json(ApiResponse::success(PaginatedResponse::from_items(
+        pauses, page, page_size,
+    ))))
+}
+
+/// 获取暂停详情
+pub async fn get_pause(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<PauseResponse>>, Error> {
+    let object_id = parse_object_id(&id).map_err(Error::Validation)?;
+
+    let pause = state
+        .db
+        .get_pause(object_id)
+        .await?
+        .ok_or_else(|| Error::Execution("暂停不存在".to_string()))?;
+
+    Ok(Json(ApiResponse::success(to_response(
+        pause,
+        &chrono::Utc::now(),
+    ))))
+}
+
+/// 手动恢复暂停，未开始的暂停也会一并撤销
+pub async fn resume_pause(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<PauseResponse>>, Error> {
+    let object_id = parse_object_id(&id).map_err(Error::Validation)?;
+    let now = chrono::Utc::now();
+
+    let pause = state
+        .db
+        .get_pause(object_id)
+        .await?
+        .ok_or_else(|| Error::Execution("暂停不存在".to_string()))?;
+
+    if pause.resumed_at.is_some() || pause.ends_at.is_some_and(|ends_at| ends_at <= now) {
+        return Err(Error::Validation("暂停已结束，无需恢复".to_string()));
+    }
+
+    state
+        .db
+        .update_pause(object_id, doc! { "$set": { "resumed_at": now } })
+        .await?;
+
+    let pause = state
+        .db
+        .get_pause(object_id)
+        .await?
+        .ok_or_else(|| Error::Execution("暂停不存在".to_string()))?;
+
+    Ok(Json(ApiResponse::success(to_response(pause, &now))))
+}
@@ -0,0 +1,134 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use mongodb::bson::doc;
+
+use crate::{
+    error::Error,
+    secrets::{SecretCipher, validate_secret_name, validate_secret_value},
+    types::{ApiResponse, CreateSecretRequest, Secret, SecretResponse, UpdateSecretRequest},
+};
+
+use super::super::models::api_state::ApiState;
+
+fn cipher(state: &ApiState) -> Result<&SecretCipher, Error> {
+    state.secret_cipher.as_deref().ok_or_else(|| {
+        Error::Validation("未配置主密钥（secrets.master_key 或 RAPIDCRON_MASTER_KEY）".to_string())
+    })
+}
+
+fn validate_description(description: Option<&str>) -> Result<(), Error> {
+    if description.is_some_and(|description| description.len() > 500) {
+        return Err(Error::Validation(
+            "密钥描述长度不能超过500个字符".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 创建密钥，响应中不包含密钥值
+pub async fn create_secret(
+    State(state): State<ApiState>,
+    Json(req): Json<CreateSecretRequest>,
+) -> Result<Json<ApiResponse<SecretResponse>>, Error> {
+    validate_secret_name(&req.name).map_err(Error::Validation)?;
+    validate_secret_value(&req.value).map_err(Error::Validation)?;
+    validate_description(req.description.as_deref())?;
+    let encrypted = cipher(&state)?
+        .encrypt(&req.name, &req.value)
+        .map_err(Error::Execution)?;
+
+    if state.db.get_secret_by_name(&req.name).await?.is_some() {
+        return Err(Error::Validation(format!("密钥已存在: {}", req.name)));
+    }
+
+    let now = chrono::Utc::now();
+    let mut secret = Secret {
+        id: None,
+        name: req.name,
+        description: req.description,
+        nonce: encrypted.nonce,
+        ciphertext: encrypted.ciphertext,
+        created_at: now,
+        updated_at: now,
+    };
+    secret.id = Some(state.db.create_secret(&secret).await?);
+
+    Ok(Json(ApiResponse::success(secret.into())))
+}
+
+/// 获取密钥列表（不包含密钥值）
+pub async fn list_secrets(
+    State(state): State<ApiState>,
+) -> Result<Json<ApiResponse<Vec<SecretResponse>>>, Error> {
+    let secrets = state.db.find_secrets(None).await?;
+    Ok(Json(ApiResponse::success(
+        secrets.into_iter().map(SecretResponse::from).collect(),
+    )))
+}
+
+/// 获取密钥详情（不包含密钥值）
+pub async fn get_secret(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<SecretResponse>>, Error> {
+    let secret = state
+        .db
+        .get_secret_by_name(&name)
+        .await?
+        .ok_or_else(|| Error::Execution("密钥不存在".to_string()))?;
+
+    Ok(Json(ApiResponse::success(secret.into())))
+}
+
+/// 更新密钥值或描述，新值对之后执行的实例生效
+pub async fn update_secret(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<UpdateSecretRequest>,
+) -> Result<Json<ApiResponse<SecretResponse>>, Error> {
+    validate_description(req.description.as_deref())?;
+
+    let mut update = doc! {
+        "$set": {
+            "updated_at": chrono::Utc::now()
+        }
+    };
+    let set = update.get_document_mut("$set").unwrap();
+    if let Some(value) = &req.value {
+        validate_secret_value(value).map_err(Error::Validation)?;
+        let encrypted = cipher(&state)?
+            .encrypt(&name, value)
+            .map_err(Error::Execution)?;
+        set.insert("nonce", encrypted.nonce);
+        set.insert("ciphertext", encrypted.ciphertext);
+    }
+    if let Some(description) = req.description {
+        set.insert("description", description);
+    }
+
+    if !state.db.update_secret_by_name(&name, update).await? {
+        return Err(Error::Execution("密钥不存在".to_string()));
+    }
+
+    let secret = state
+        .db
+        .get_secret_by_name(&name)
+        .await?
+        .ok_or_else(|| Error::Execution("密钥不存在".to_string()))?;
+
+    Ok(Json(ApiResponse::success(secret.into())))
+}
+
+/// 删除密钥，引用它的任务之后执行时会失败
+pub async fn delete_secret(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<()>>, Error> {
+    if !state.db.delete_secret_by_name(&name).await? {
+        return Err(Error::Execution("密钥不存在".to_string()));
+    }
+
+    Ok(Json(ApiResponse::success(())))
+}
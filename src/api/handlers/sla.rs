@@ -0,0 +1,71 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use mongodb::bson::doc;
+
+use crate::{
+    error::Error,
+    types::{ApiResponse, PaginatedResponse, SlaEvent, parse_object_id},
+};
+
+use super::super::models::api_state::ApiState;
+
+/// SLA 事件列表查询参数
+#[derive(Debug, serde::Deserialize)]
+pub struct SlaEventListQuery {
+    pub task_id: Option<String>,
+    pub kind: Option<String>,
+    pub start_time: Option<String>,
+    pub page: Option<String>,
+    pub page_size: Option<String>,
+}
+
+/// 获取 SLA 事件列表
+pub async fn list_sla_events(
+    State(state): State<ApiState>,
+    Query(query): Query<SlaEventListQuery>,
+) -> Result<Json<ApiResponse<PaginatedResponse<SlaEvent>>>, Error> {
+    let mut filter = doc! {};
+
+    if let Some(task_id) = query.task_id {
+        let object_id = parse_object_id(&task_id).map_err(Error::Validation)?;
+        filter.insert("task_id", object_id);
+    }
+
+    if let Some(kind) = query.kind {
+        let kind_value = match kind.as_str() {
+            "start_late" => "start_late",
+            "run_overrun" => "run_overrun",
+            "expired" => "expired",
+            _ => return Err(Error::Validation("无效的 SLA 事件类型".to_string())),
+        };
+        filter.insert("kind", kind_value);
+    }
+
+    if let Some(start_time_str) = query.start_time {
+        if let Ok(start_time) = chrono::DateTime::parse_from_rfc3339(&start_time_str) {
+            filter.insert(
+                "detected_at",
+                doc! { "$gte": start_time.with_timezone(&chrono::Utc) },
+            );
+        } else {
+            return Err(Error::Validation("无效的开始时间格式".to_string()));
+        }
+    }
+
+    let page = query
+        .page
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(1);
+    let page_size = query
+        .page_size
+        .and_then(|ps| ps.parse::<usize>().ok())
+        .unwrap_or(20);
+
+    let events = state.db.find_sla_events(Some(filter)).await?;
+
+    Ok(Json(ApiResponse::success(PaginatedResponse::from_items(
+        events, page, page_size,
+    ))))
+}
@@ -2,15 +2,22 @@ use axum::{
     Json,
     extract::{Path, Query, State},
 };
+use chrono::Local;
 use mongodb::bson::{doc, oid::ObjectId};
 use std::str::FromStr;
 
 use crate::{
     error::Error,
+    executor::{assertion::validate_assertions, template::validate_payload},
+    scheduler::cron_parser::CronParser,
     types::{
-        ApiResponse, CreateTaskRequest, PaginatedResponse, StatsResponse, Task, TaskInstance,
+        ApiResponse, CreateTaskRequest, PaginatedResponse, SchedulePreviewRequest,
+        SchedulePreviewResponse, SlaEventKind, StatsResponse, Task, TaskInstance, TaskParameter,
         TaskPayload, TaskStatus, TaskType, TriggerTaskRequest, TriggeredBy, UpdateTaskRequest,
-        parse_object_id, parse_object_ids,
+        parse_object_id, parse_object_ids, resolve_parameters, validate_command_options,
+        validate_deadline_seconds, validate_max_concurrent_instances, validate_parameters,
+        validate_priority, validate_rate_limit, validate_resource_limits, validate_sla_seconds,
+        validate_tags,
     },
 };
 
@@ -22,6 +29,7 @@ pub struct TaskListQuery {
     pub enabled: Option<bool>,
     pub name: Option<String>,
     pub task_type: Option<String>,
+    pub tag: Option<String>,
     pub page: Option<String>,
     pub page_size: Option<String>,
 }
@@ -60,6 +68,10 @@ pub async fn list_tasks(
         filter.insert("type", task_type);
     }
 
+    if let Some(tag) = query.tag {
+        filter.insert("tags", tag);
+    }
+
     let page = query
         .page
         .and_then(|p| p.parse::<usize>().ok())
@@ -114,12 +126,42 @@ pub async fn create_task(
 pub async fn update_task(
     State(state): State<ApiState>,
     Path(id): Path<String>,
-    Json(req): Json<UpdateTaskRequest>,
+    Json(mut req): Json<UpdateTaskRequest>,
 ) -> Result<Json<ApiResponse<Task>>, Error> {
     let object_id = parse_object_id(&id).map_err(Error::Validation)?;
 
     let mut update = doc! { "$set": { "updated_at": chrono::Utc::now() } };
 
+    // 参数与载荷合并后整体校验，模板须引用合并后的参数
+    if req.parameters.is_some() || updates_payload(&req) {
+        let task = state
+            .db
+            .get_task(object_id)
+            .await?
+            .ok_or_else(|| Error::Execution("任务不存在".to_string()))?;
+        let (parameters, payload) = merge_parameters_and_payload(task, &mut req)?;
+        if let Some(parameters) = parameters {
+            let parameters = mongodb::bson::to_bson(&parameters)
+                .map_err(|e| Error::Execution(format!("序列化任务参数失败: {}", e)))?;
+            update
+                .get_mut("$set")
+                .unwrap()
+                .as_document_mut()
+                .unwrap()
+                .insert("parameters", parameters);
+        }
+        if let Some(payload) = payload {
+            let payload = mongodb::bson::Bson::try_from(payload)
+                .map_err(|e| Error::Execution(format!("序列化任务载荷失败: {}", e)))?;
+            update
+                .get_mut("$set")
+                .unwrap()
+                .as_document_mut()
+                .unwrap()
+                .insert("payload", payload);
+        }
+    }
+
     if let Some(name) = req.name {
         update
             .get_mut("$set")
@@ -172,6 +214,66 @@ pub async fn update_task(
             .unwrap()
             .insert("max_retries", max_retries);
     }
+    if let Some(priority) = req.priority {
+        validate_priority(priority).map_err(Error::Validation)?;
+        update
+            .get_mut("$set")
+            .unwrap()
+            .as_document_mut()
+            .unwrap()
+            .insert("priority", priority);
+    }
+    if let Some(deadline_seconds) = req.deadline_seconds {
+        validate_deadline_seconds(deadline_seconds).map_err(Error::Validation)?;
+        update
+            .get_mut("$set")
+            .unwrap()
+            .as_document_mut()
+            .unwrap()
+            .insert("deadline_seconds", deadline_seconds);
+    }
+    if let Some(start_sla_seconds) = req.start_sla_seconds {
+        validate_sla_seconds(start_sla_seconds).map_err(Error::Validation)?;
+        update
+            .get_mut("$set")
+            .unwrap()
+            .as_document_mut()
+            .unwrap()
+            .insert("start_sla_seconds", start_sla_seconds);
+    }
+    if let Some(run_sla_seconds) = req.run_sla_seconds {
+        validate_sla_seconds(run_sla_seconds).map_err(Error::Validation)?;
+        update
+            .get_mut("$set")
+            .unwrap()
+            .as_document_mut()
+            .unwrap()
+            .insert("run_sla_seconds", run_sla_seconds);
+    }
+    if let Some(max_concurrent_instances) = req.max_concurrent_instances {
+        validate_max_concurrent_instances(max_concurrent_instances).map_err(Error::Validation)?;
+        update
+            .get_mut("$set")
+            .unwrap()
+            .as_document_mut()
+            .unwrap()
+            .insert("max_concurrent_instances", max_concurrent_instances);
+    }
+    if let Some(rate_limit) = req.rate_limit {
+        validate_rate_limit(&rate_limit).map_err(Error::Validation)?;
+        update
+            .get_mut("$set")
+            .unwrap()
+            .as_document_mut()
+            .unwrap()
+            .insert(
+                "rate_limit",
+                doc! {
+                    "max_runs": rate_limit.max_runs,
+                    "window_seconds": rate_limit.window_seconds,
+                },
+            );
+    }
     if let Some(dependency_ids) = req.dependency_ids {
         let ids = parse_object_ids(&dependency_ids);
         update
@@ -181,6 +283,15 @@ pub async fn update_task(
             .unwrap()
             .insert("dependency_ids", ids);
     }
+    if let Some(tags) = req.tags {
+        validate_tags(&tags).map_err(Error::Validation)?;
+        update
+            .get_mut("$set")
+            .unwrap()
+            .as_document_mut()
+            .unwrap()
+            .insert("tags", tags);
+    }
 
     state.db.update_task(object_id, update).await?;
 
@@ -193,6 +304,114 @@ pub async fn update_task(
     Ok(Json(ApiResponse::success(updated_task)))
 }
 
+/// 请求是否修改任务载荷
+fn updates_payload(req: &UpdateTaskRequest) -> bool {
+    updates_command_options(req) || req.assertions.is_some()
+}
+
+fn updates_command_options(req: &UpdateTaskRequest) -> bool {
+    req.env.is_some()
+        || req.working_dir.is_some()
+        || req.run_as_user.is_some()
+        || req.stdin.is_some()
+        || req.limits.is_some()
+        || req.sandbox.is_some()
+}
+
+/// 将请求中的参数与载荷修改合并到任务上，再按合并后的参数校验合并后的载荷
+///
+/// 返回需要写入的参数与载荷，请求未修改的部分为 `None`
+fn merge_parameters_and_payload(
+    task: Task,
+    req: &mut UpdateTaskRequest,
+) -> Result<(Option<Vec<TaskParameter>>, Option<TaskPayload>), Error> {
+    let mut parameters = task.parameters;
+    let mut payload = task.payload;
+
+    let updates_parameters = req.parameters.is_some();
+    if let Some(new_parameters) = req.parameters.take() {
+        validate_parameters(&new_parameters).map_err(Error::Validation)?;
+        parameters = new_parameters;
+    }
+
+    let updates_payload = updates_payload(req);
+    if updates_command_options(req) {
+        validate_command_options(
+            req.env.as_ref(),
+            req.working_dir.as_deref(),
+            req.run_as_user.as_deref(),
+            req.stdin.as_deref(),
+        )
+        .map_err(Error::Validation)?;
+        if let Some(limits) = &req.limits {
+            validate_resource_limits(limits).map_err(Error::Validation)?;
+        }
+        let (TaskPayload::Command {
+            env,
+            working_dir,
+            run_as_user,
+            stdin,
+            limits,
+            sandbox,
+            ..
+        }
+        | TaskPayload::Script {
+            env,
+            working_dir,
+            run_as_user,
+            stdin,
+            limits,
+            sandbox,
+            ..
+        }) = &mut payload
+        else {
+            return Err(Error::Validation(
+                "env、working_dir、run_as_user、stdin、limits 与 sandbox 仅适用于命令与脚本任务"
+                    .to_string(),
+            ));
+        };
+        if req.env.is_some() {
+            *env = req.env.take();
+        }
+        if req.working_dir.is_some() {
+            *working_dir = req.working_dir.take();
+        }
+        if req.run_as_user.is_some() {
+            *run_as_user = req.run_as_user.take();
+        }
+        if req.stdin.is_some() {
+            *stdin = req.stdin.take();
+        }
+        if req.limits.is_some() {
+            *limits = req.limits.take();
+        }
+        if req.sandbox.is_some() {
+            *sandbox = req.sandbox.take();
+        }
+    }
+
+    if let Some(assertions) = req.assertions.take() {
+        validate_assertions(&assertions).map_err(Error::Validation)?;
+        let TaskPayload::Http {
+            assertions: current,
+            ..
+        } = &mut payload
+        else {
+            return Err(Error::Validation(
+                "assertions 仅适用于 HTTP 任务".to_string(),
+            ));
+        };
+        *current = Some(assertions);
+    }
+
+    validate_payload(&payload, &parameters).map_err(Error::Validation)?;
+
+    Ok((
+        updates_parameters.then_some(parameters),
+        updates_payload.then_some(payload),
+    ))
+}
+
 /// 删除任务
 pub async fn delete_task(
     State(state): State<ApiState>,
@@ -282,9 +501,38 @@ pub async fn trigger_task(
         .map(|ts| chrono::DateTime::from_timestamp(ts, 0).unwrap_or(now))
         .unwrap_or(now);
 
+    resolve_parameters(&task.parameters, req.parameters.as_ref()).map_err(Error::Validation)?;
+
+    let created_instance = enqueue_triggered_instance(
+        &state,
+        &task,
+        scheduled_time,
+        TriggeredBy::Manual,
+        None,
+        req.parameters,
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::success(created_instance)))
+}
+
+/// 为手动或外部触发创建任务实例并发布到队列
+pub(super) async fn enqueue_triggered_instance(
+    state: &ApiState,
+    task: &Task,
+    scheduled_time: chrono::DateTime<chrono::Utc>,
+    triggered_by: TriggeredBy,
+    trigger_payload: Option<serde_json::Value>,
+    parameters: Option<serde_json::Map<String, serde_json::Value>>,
+) -> Result<TaskInstance, Error> {
+    let task_id = task
+        .id
+        .ok_or_else(|| Error::Validation("任务 ID 不能为空".to_string()))?;
+
+    let now = chrono::Utc::now();
     let instance = TaskInstance {
         id: None,
-        task_id: object_id,
+        task_id,
         scheduled_time,
         status: TaskStatus::Pending,
         executor_id: None,
@@ -292,7 +540,11 @@ pub async fn trigger_task(
         end_time: None,
         retry_count: 0,
         result: None,
-        triggered_by: TriggeredBy::Manual,
+        triggered_by: triggered_by.clone(),
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload,
+        parameters,
         created_at: now,
     };
 
@@ -302,26 +554,86 @@ pub async fn trigger_task(
     if let Some(task_queue) = &state.task_queue {
         let task_msg = crate::executor::TaskMessage {
             instance_id,
-            task_id: object_id,
+            task_id,
             task_name: task.name.clone(),
             scheduled_time: scheduled_time.timestamp(),
             retry_count: 0,
-            triggered_by: TriggeredBy::Manual,
+            triggered_by,
+            priority: task.effective_priority() as u8,
         };
 
         task_queue
-            .publish_task(task_msg)
+            .publish_task(task_msg, now)
             .await
             .map_err(|e| Error::Execution(format!("发布任务到队列失败: {}", e)))?;
     }
 
-    let created_instance = state
+    state
         .db
         .get_task_instance(instance_id)
         .await?
-        .ok_or_else(|| Error::Execution("任务实例创建失败".to_string()))?;
+        .ok_or_else(|| Error::Execution("任务实例创建失败".to_string()))
+}
 
-    Ok(Json(ApiResponse::success(created_instance)))
+/// 预览调度时间
+pub async fn preview_schedule(
+    State(state): State<ApiState>,
+    Json(req): Json<SchedulePreviewRequest>,
+) -> Result<Json<ApiResponse<SchedulePreviewResponse>>, Error> {
+    let schedule = match (req.schedule, req.task_id) {
+        (Some(schedule), _) => schedule,
+        (None, Some(task_id)) => {
+            let object_id = parse_object_id(&task_id).map_err(Error::Validation)?;
+            state
+                .db
+                .get_task(object_id)
+                .await?
+                .ok_or_else(|| Error::Execution("任务不存在".to_string()))?
+                .schedule
+        }
+        (None, None) => {
+            return Err(Error::Validation(
+                "必须提供 Cron 表达式或任务 ID".to_string(),
+            ));
+        }
+    };
+
+    let count = req.count.unwrap_or(10);
+    if count == 0 || count > 100 {
+        return Err(Error::Validation("预览数量必须在1到100之间".to_string()));
+    }
+
+    let parser = CronParser::new(&schedule)?;
+    let now = chrono::Utc::now();
+
+    let (timezone, next_triggers) = match req.timezone {
+        Some(name) => {
+            let tz = chrono_tz::Tz::from_str(&name)
+                .map_err(|_| Error::Validation(format!("无效的时区: {}", name)))?;
+            let triggers = parser
+                .next_triggers(&now.with_timezone(&tz), count)
+                .iter()
+                .map(|t| t.to_rfc3339())
+                .collect();
+            (name, triggers)
+        }
+        None => {
+            let triggers = parser
+                .next_triggers(&now.with_timezone(&Local), count)
+                .iter()
+                .map(|t| t.to_rfc3339())
+                .collect();
+            (Local::now().offset().to_string(), triggers)
+        }
+    };
+
+    Ok(Json(ApiResponse::success(SchedulePreviewResponse {
+        description: parser.describe(),
+        warnings: parser.warnings(),
+        schedule,
+        timezone,
+        next_triggers,
+    })))
 }
 
 /// 获取任务实例列表
@@ -344,6 +656,9 @@ pub async fn list_instances(
             "success" => "success",
             "failed" => "failed",
             "cancelled" => "cancelled",
+            "expired" => "expired",
+            "timed_out" => "timed_out",
+            "interrupted" => "interrupted",
             _ => return Err(Error::Validation("无效的任务状态".to_string())),
         };
         filter.insert("status", task_status);
@@ -424,6 +739,31 @@ pub async fn get_stats(
         .filter(|i| i.status == TaskStatus::Failed)
         .count();
 
+    let expired_instances = all_instances
+        .iter()
+        .filter(|i| i.status == TaskStatus::Expired)
+        .count();
+
+    let timed_out_instances = all_instances
+        .iter()
+        .filter(|i| i.status == TaskStatus::TimedOut)
+        .count();
+
+    let interrupted_instances = all_instances
+        .iter()
+        .filter(|i| i.status == TaskStatus::Interrupted)
+        .count();
+
+    let late_instances = all_instances
+        .iter()
+        .filter(|i| i.sla_flags.contains(&SlaEventKind::StartLate))
+        .count();
+
+    let overrun_instances = all_instances
+        .iter()
+        .filter(|i| i.sla_flags.contains(&SlaEventKind::RunOverrun))
+        .count();
+
     let stats = StatsResponse {
         total_tasks: all_tasks.len() as u64,
         enabled_tasks: enabled_tasks.len() as u64,
@@ -432,6 +772,11 @@ pub async fn get_stats(
         running_instances: running_instances as u64,
         success_instances: success_instances as u64,
         failed_instances: failed_instances as u64,
+        expired_instances: expired_instances as u64,
+        timed_out_instances: timed_out_instances as u64,
+        interrupted_instances: interrupted_instances as u64,
+        late_instances: late_instances as u64,
+        overrun_instances: overrun_instances as u64,
     };
 
     Ok(Json(ApiResponse::success(stats)))
@@ -562,6 +907,7 @@ pub async fn create_test_tasks(
             name: name.to_string(),
             description: Some(description.to_string()),
             dependency_ids: Vec::new(),
+            tags: Vec::new(),
             task_type: task_type.clone(),
             schedule: schedule.to_string(),
             enabled,
@@ -572,14 +918,29 @@ pub async fn create_test_tasks(
                     headers: None,
                     body: None,
                     timeout_seconds: Some(timeout_seconds),
+                    assertions: None,
                 },
-                TaskType::Command => TaskPayload::Command {
+                // 演示任务只包含 HTTP 与命令任务
+                _ => TaskPayload::Command {
                     command: command.unwrap_or_default().to_string(),
                     timeout_seconds: Some(timeout_seconds),
+                    env: None,
+                    working_dir: None,
+                    run_as_user: None,
+                    stdin: None,
+                    limits: None,
+                    sandbox: None,
                 },
             },
             timeout_seconds: Some(timeout_seconds),
             max_retries: Some(max_retries),
+            priority: None,
+            deadline_seconds: None,
+            start_sla_seconds: None,
+            run_sla_seconds: None,
+            max_concurrent_instances: None,
+            rate_limit: None,
+            parameters: Vec::new(),
             created_at: now,
             updated_at: now,
             deleted_at: None,
@@ -599,3 +960,65 @@ pub async fn create_test_tasks(
         existed,
     })))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_task() -> Task {
+        let mut task = Task::for_test("export");
+        task.payload = TaskPayload::Command {
+            command: "export --region {{ params.region }}".to_string(),
+            timeout_seconds: None,
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
+        };
+        task.parameters = serde_json::from_value(serde_json::json!([
+            { "name": "region", "default": "cn" }
+        ]))
+        .unwrap();
+        task
+    }
+
+    fn make_request(body: serde_json::Value) -> UpdateTaskRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_merge_validates_payload_against_merged_parameters() {
+        // 新参数与引用它的新载荷一起提交时按合并结果校验
+        let mut req = make_request(serde_json::json!({
+            "parameters": [
+                { "name": "region", "default": "cn" },
+                { "name": "zone", "default": "a" }
+            ],
+            "env": { "ZONE": "{{ params.zone }}" }
+        }));
+        let (parameters, payload) = merge_parameters_and_payload(make_task(), &mut req).unwrap();
+        assert_eq!(parameters.unwrap().len(), 2);
+        let Some(TaskPayload::Command { env, .. }) = payload else {
+            panic!("应返回合并后的命令载荷");
+        };
+        assert_eq!(env.unwrap()["ZONE"], "{{ params.zone }}");
+
+        // 删除仍被现有载荷引用的参数
+        let mut req = make_request(serde_json::json!({ "parameters": [] }));
+        assert!(merge_parameters_and_payload(make_task(), &mut req).is_err());
+
+        // 载荷引用未声明的参数
+        let mut req = make_request(serde_json::json!({ "stdin": "{{ params.zone }}" }));
+        assert!(merge_parameters_and_payload(make_task(), &mut req).is_err());
+
+        // 只修改参数时不写回载荷
+        let mut req = make_request(serde_json::json!({
+            "parameters": [{ "name": "region", "default": "eu" }]
+        }));
+        let (parameters, payload) = merge_parameters_and_payload(make_task(), &mut req).unwrap();
+        assert!(parameters.is_some());
+        assert!(payload.is_none());
+    }
+}
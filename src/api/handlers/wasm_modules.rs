@@ -0,0 +1,106 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use base64::Engine;
+use mongodb::bson;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::Error,
+    executor::wasm::validate_module,
+    types::{
+        ApiResponse, CreateWasmModuleRequest, MAX_WASM_MODULE_BYTES, WasmModule,
+        WasmModuleResponse, validate_wasm_module_name,
+    },
+};
+
+use super::super::models::api_state::ApiState;
+
+/// 上传 wasm 模块，模块需能被执行器编译并导出 `_start`
+pub async fn create_wasm_module(
+    State(state): State<ApiState>,
+    Json(req): Json<CreateWasmModuleRequest>,
+) -> Result<Json<ApiResponse<WasmModuleResponse>>, Error> {
+    validate_wasm_module_name(&req.name).map_err(Error::Validation)?;
+    if req
+        .description
+        .as_deref()
+        .is_some_and(|description| description.len() > 500)
+    {
+        return Err(Error::Validation(
+            "模块描述长度不能超过500个字符".to_string(),
+        ));
+    }
+    // base64 编码后约为原始大小的 4/3，先粗略限制，避免解码超大请求
+    if req.module_base64.len() > MAX_WASM_MODULE_BYTES / 3 * 4 + 4 {
+        return Err(Error::Validation(format!(
+            "模块不能超过{}字节",
+            MAX_WASM_MODULE_BYTES
+        )));
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(req.module_base64.trim())
+        .map_err(|e| Error::Validation(format!("module_base64 不是有效的 base64: {}", e)))?;
+    // 编译较耗时，放到阻塞线程执行
+    let bytes = tokio::task::spawn_blocking(move || validate_module(&bytes).map(|()| bytes))
+        .await
+        .map_err(|e| Error::Execution(format!("校验模块失败: {}", e)))?
+        .map_err(Error::Validation)?;
+
+    if state.db.get_wasm_module_by_name(&req.name).await?.is_some() {
+        return Err(Error::Validation(format!("模块已存在: {}", req.name)));
+    }
+
+    let now = chrono::Utc::now();
+    let mut module = WasmModule {
+        id: None,
+        name: req.name,
+        description: req.description,
+        sha256: hex::encode(Sha256::digest(&bytes)),
+        size_bytes: bytes.len() as i64,
+        data: bson::Binary {
+            subtype: bson::spec::BinarySubtype::Generic,
+            bytes,
+        },
+        created_at: now,
+        updated_at: now,
+    };
+    module.id = Some(state.db.create_wasm_module(&module).await?);
+
+    Ok(Json(ApiResponse::success(module.into())))
+}
+
+/// 获取模块列表（不包含模块内容）
+pub async fn list_wasm_modules(
+    State(state): State<ApiState>,
+) -> Result<Json<ApiResponse<Vec<WasmModuleResponse>>>, Error> {
+    let modules = state.db.find_wasm_modules().await?;
+    Ok(Json(ApiResponse::success(modules)))
+}
+
+/// 获取模块详情（不包含模块内容）
+pub async fn get_wasm_module(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<WasmModuleResponse>>, Error> {
+    let module = state
+        .db
+        .get_wasm_module_by_name(&name)
+        .await?
+        .ok_or_else(|| Error::Execution("模块不存在".to_string()))?;
+
+    Ok(Json(ApiResponse::success(module.into())))
+}
+
+/// 删除模块，引用它的任务之后执行时会失败
+pub async fn delete_wasm_module(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<()>>, Error> {
+    if !state.db.delete_wasm_module_by_name(&name).await? {
+        return Err(Error::Execution("模块不存在".to_string()));
+    }
+
+    Ok(Json(ApiResponse::success(())))
+}
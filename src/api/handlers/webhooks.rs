@@ -0,0 +1,215 @@
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Path, State},
+    http::HeaderMap,
+};
+use hmac::{Hmac, Mac};
+use mongodb::bson::doc;
+use rand::RngCore;
+use sha2::Sha256;
+
+use crate::{
+    error::Error,
+    types::{
+        ApiResponse, CreateWebhookRequest, TaskInstance, TriggeredBy, Webhook, WebhookResponse,
+        check_untrusted_overrides, parse_object_id, resolve_parameters,
+    },
+};
+
+use super::super::models::api_state::ApiState;
+use super::tasks::enqueue_triggered_instance;
+
+/// 签名请求头，格式与 GitHub 一致：`sha256=<hex>`
+pub const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+
+/// 生成 32 字节的随机十六进制串，用作令牌与签名密钥
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// 校验签名请求头，比较在常数时间内完成
+pub fn verify_signature(secret: &str, body: &[u8], signature: Option<&str>) -> Result<(), String> {
+    let signature = signature.ok_or_else(|| format!("缺少签名请求头 {}", SIGNATURE_HEADER))?;
+    let expected = signature
+        .strip_prefix("sha256=")
+        .and_then(|hex_digest| hex::decode(hex_digest).ok())
+        .ok_or_else(|| "签名格式无效".to_string())?;
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC 可以接受任意长度的密钥");
+    mac.update(body);
+    mac.verify_slice(&expected)
+        .map_err(|_| "签名校验失败".to_string())
+}
+
+/// 为任务创建 Webhook，已存在时轮换令牌与密钥
+pub async fn create_webhook(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    body: Option<Json<CreateWebhookRequest>>,
+) -> Result<Json<ApiResponse<WebhookResponse>>, Error> {
+    let object_id = parse_object_id(&id).map_err(Error::Validation)?;
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+
+    state
+        .db
+        .get_task(object_id)
+        .await?
+        .filter(|task| task.deleted_at.is_none())
+        .ok_or_else(|| Error::Execution("任务不存在".to_string()))?;
+
+    let webhook = Webhook {
+        id: None,
+        task_id: object_id,
+        token: generate_secret(),
+        secret: req.signed.then(generate_secret),
+        created_at: chrono::Utc::now(),
+        last_triggered_at: None,
+    };
+    state.db.replace_webhook(&webhook).await?;
+
+    Ok(Json(ApiResponse::success(WebhookResponse::from_webhook(
+        webhook, true,
+    ))))
+}
+
+/// 获取任务的 Webhook 配置（不返回签名密钥）
+pub async fn get_webhook(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<WebhookResponse>>, Error> {
+    let object_id = parse_object_id(&id).map_err(Error::Validation)?;
+
+    let webhook = state
+        .db
+        .get_webhook_by_task(object_id)
+        .await?
+        .ok_or_else(|| Error::Execution("Webhook 不存在".to_string()))?;
+
+    Ok(Json(ApiResponse::success(WebhookResponse::from_webhook(
+        webhook, false,
+    ))))
+}
+
+/// 删除任务的 Webhook，原地址立即失效
+pub async fn delete_webhook(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<()>>, Error> {
+    let object_id = parse_object_id(&id).map_err(Error::Validation)?;
+
+    let deleted = state.db.delete_webhook_by_task(object_id).await?;
+    if !deleted {
+        return Err(Error::Execution("Webhook 不存在".to_string()));
+    }
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// 通过 Webhook 触发任务，请求体（JSON，可为空）保存在创建的实例上
+pub async fn trigger_webhook(
+    State(state): State<ApiState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<ApiResponse<TaskInstance>>, Error> {
+    let webhook = state
+        .db
+        .get_webhook_by_token(&token)
+        .await?
+        .ok_or_else(|| Error::Execution("Webhook 不存在".to_string()))?;
+
+    if let Some(secret) = &webhook.secret {
+        let signature = headers
+            .get(SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok());
+        verify_signature(secret, &body, signature).map_err(Error::Validation)?;
+    }
+
+    let payload = if body.is_empty() {
+        None
+    } else {
+        Some(
+            serde_json::from_slice::<serde_json::Value>(&body)
+                .map_err(|e| Error::Validation(format!("请求体不是有效的 JSON: {}", e)))?,
+        )
+    };
+
+    let task = state
+        .db
+        .get_task(webhook.task_id)
+        .await?
+        .filter(|task| task.deleted_at.is_none())
+        .ok_or_else(|| Error::Execution("任务不存在".to_string()))?;
+    if !task.enabled {
+        return Err(Error::Validation("任务未启用".to_string()));
+    }
+
+    // 请求体中的 parameters 对象用于覆盖任务参数
+    let parameters = payload
+        .as_ref()
+        .and_then(|payload| payload.get("parameters"))
+        .and_then(|parameters| parameters.as_object())
+        .cloned();
+    check_untrusted_overrides(&task, parameters.as_ref()).map_err(Error::Validation)?;
+    resolve_parameters(&task.parameters, parameters.as_ref()).map_err(Error::Validation)?;
+
+    let now = chrono::Utc::now();
+    let instance = enqueue_triggered_instance(
+        &state,
+        &task,
+        now,
+        TriggeredBy::Webhook,
+        payload,
+        parameters,
+    )
+    .await?;
+
+    if let Some(webhook_id) = webhook.id {
+        state
+            .db
+            .update_webhook(webhook_id, doc! { "$set": { "last_triggered_at": now } })
+            .await?;
+    }
+
+    Ok(Json(ApiResponse::success(instance)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign_payload(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let secret = "It's a Secret to Everybody";
+        let body = b"Hello, World!";
+        let signature = sign_payload(secret, body);
+
+        // GitHub 文档中的示例签名
+        assert_eq!(
+            signature,
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+        );
+        assert!(verify_signature(secret, body, Some(&signature)).is_ok());
+        assert!(verify_signature(secret, b"Hello, World?", Some(&signature)).is_err());
+        assert!(verify_signature("other", body, Some(&signature)).is_err());
+        assert!(verify_signature(secret, body, None).is_err());
+        assert!(verify_signature(secret, body, Some("sha1=abc")).is_err());
+    }
+
+    #[test]
+    fn test_generate_secret() {
+        let first = generate_secret();
+        assert_eq!(first.len(), 64);
+        assert_ne!(first, generate_secret());
+    }
+}
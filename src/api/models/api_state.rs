@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
+use crate::config::BackfillConfig;
 use crate::coord::EtcdManager;
 use crate::executor::TaskQueue;
+use crate::secrets::SecretCipher;
 use crate::storage::mongo::MongoDataSource;
 
 /// API 状态
@@ -10,6 +12,9 @@ pub struct ApiState {
     pub db: MongoDataSource,
     pub etcd_manager: Option<Arc<EtcdManager>>,
     pub task_queue: Option<Arc<TaskQueue>>,
+    pub backfill: BackfillConfig,
+    /// 加密密钥值使用的主密钥，未配置时不能创建或更新密钥值
+    pub secret_cipher: Option<Arc<SecretCipher>>,
 }
 
 impl ApiState {
@@ -18,6 +23,8 @@ impl ApiState {
             db,
             etcd_manager: None,
             task_queue: None,
+            backfill: BackfillConfig::default(),
+            secret_cipher: None,
         }
     }
 
@@ -30,4 +37,14 @@ impl ApiState {
         self.task_queue = Some(task_queue);
         self
     }
+
+    pub fn with_backfill_config(mut self, backfill: BackfillConfig) -> Self {
+        self.backfill = backfill;
+        self
+    }
+
+    pub fn with_secret_cipher(mut self, secret_cipher: Option<SecretCipher>) -> Self {
+        self.secret_cipher = secret_cipher.map(Arc::new);
+        self
+    }
 }
@@ -3,11 +3,15 @@ use std::sync::Arc;
 
 use crate::api::{
     ApiState,
-    handlers::{auth, clusters, dispatch, execution, tasks},
+    handlers::{
+        auth, backfill, clusters, dispatch, execution, grpc_descriptor_sets, pauses, secrets, sla,
+        tasks, wasm_modules, webhooks,
+    },
 };
-use crate::config::AuthConfig;
+use crate::config::{AuthConfig, BackfillConfig};
 use crate::coord::EtcdManager;
 use crate::executor::TaskQueue;
+use crate::secrets::SecretCipher;
 use crate::storage::mongo::MongoDataSource;
 
 pub fn create_router_with_etcd(
@@ -15,10 +19,14 @@ pub fn create_router_with_etcd(
     etcd_manager: Arc<EtcdManager>,
     task_queue: Arc<TaskQueue>,
     auth_config: AuthConfig,
+    backfill_config: BackfillConfig,
+    secret_cipher: Option<SecretCipher>,
 ) -> Router {
     let api_state = ApiState::new(db)
         .with_etcd(etcd_manager.clone())
-        .with_task_queue(task_queue);
+        .with_task_queue(task_queue)
+        .with_backfill_config(backfill_config)
+        .with_secret_cipher(secret_cipher);
     let cluster_api_state = clusters::ClusterApiState::new(api_state.clone(), etcd_manager);
     let auth_state = auth::AuthState::new(auth_config);
 
@@ -26,7 +34,17 @@ pub fn create_router_with_etcd(
         .nest("/tasks", task_routes(api_state.clone()))
         .nest("/clusters", cluster_routes_with_etcd(cluster_api_state))
         .nest("/execution", execution_routes(api_state.clone()))
-        .nest("/dispatch", dispatch_routes(api_state))
+        .nest("/dispatch", dispatch_routes(api_state.clone()))
+        .nest("/backfills", backfill_routes(api_state.clone()))
+        .nest("/sla", sla_routes(api_state.clone()))
+        .nest("/pauses", pause_routes(api_state.clone()))
+        .nest("/secrets", secret_routes(api_state.clone()))
+        .nest("/wasm-modules", wasm_module_routes(api_state.clone()))
+        .nest(
+            "/grpc-descriptor-sets",
+            grpc_descriptor_set_routes(api_state.clone()),
+        )
+        .nest("/webhooks", webhook_routes(api_state))
         .nest("/auth", auth_routes(auth_state))
 }
 
@@ -46,6 +64,20 @@ fn task_routes(state: ApiState) -> Router {
         .route("/:id/enable", axum::routing::post(tasks::enable_task))
         .route("/:id/disable", axum::routing::post(tasks::disable_task))
         .route("/:id/trigger", axum::routing::post(tasks::trigger_task))
+        .route(
+            "/:id/backfill",
+            axum::routing::post(backfill::create_backfill),
+        )
+        .route("/:id/webhook", axum::routing::get(webhooks::get_webhook))
+        .route(
+            "/:id/webhook",
+            axum::routing::post(webhooks::create_webhook),
+        )
+        .route(
+            "/:id/webhook",
+            axum::routing::delete(webhooks::delete_webhook),
+        )
+        .route("/preview", axum::routing::post(tasks::preview_schedule))
         .route("/instances", axum::routing::get(tasks::list_instances))
         .route("/instances/:id", axum::routing::get(tasks::get_instance))
         .route("/stats", axum::routing::get(tasks::get_stats))
@@ -63,6 +95,85 @@ fn dispatch_routes(state: ApiState) -> Router {
     Router::new()
         .route("/logs", axum::routing::get(dispatch::list_dispatch_logs))
         .route("/logs/:id", axum::routing::get(dispatch::get_dispatch_log))
+        .route(
+            "/logs/:id/tasks",
+            axum::routing::get(dispatch::get_dispatch_log_tasks),
+        )
+        .with_state(state)
+}
+
+fn backfill_routes(state: ApiState) -> Router {
+    Router::new()
+        .route("/", axum::routing::get(backfill::list_backfills))
+        .route("/:id", axum::routing::get(backfill::get_backfill))
+        .route(
+            "/:id/cancel",
+            axum::routing::post(backfill::cancel_backfill),
+        )
+        .with_state(state)
+}
+
+fn sla_routes(state: ApiState) -> Router {
+    Router::new()
+        .route("/events", axum::routing::get(sla::list_sla_events))
+        .with_state(state)
+}
+
+fn pause_routes(state: ApiState) -> Router {
+    Router::new()
+        .route("/", axum::routing::get(pauses::list_pauses))
+        .route("/", axum::routing::post(pauses::create_pause))
+        .route("/:id", axum::routing::get(pauses::get_pause))
+        .route("/:id/resume", axum::routing::post(pauses::resume_pause))
+        .with_state(state)
+}
+
+fn secret_routes(state: ApiState) -> Router {
+    Router::new()
+        .route("/", axum::routing::get(secrets::list_secrets))
+        .route("/", axum::routing::post(secrets::create_secret))
+        .route("/:name", axum::routing::get(secrets::get_secret))
+        .route("/:name", axum::routing::put(secrets::update_secret))
+        .route("/:name", axum::routing::delete(secrets::delete_secret))
+        .with_state(state)
+}
+
+fn wasm_module_routes(state: ApiState) -> Router {
+    Router::new()
+        .route("/", axum::routing::get(wasm_modules::list_wasm_modules))
+        .route("/", axum::routing::post(wasm_modules::create_wasm_module))
+        .route("/:name", axum::routing::get(wasm_modules::get_wasm_module))
+        .route(
+            "/:name",
+            axum::routing::delete(wasm_modules::delete_wasm_module),
+        )
+        .with_state(state)
+}
+
+fn grpc_descriptor_set_routes(state: ApiState) -> Router {
+    Router::new()
+        .route(
+            "/",
+            axum::routing::get(grpc_descriptor_sets::list_grpc_descriptor_sets),
+        )
+        .route(
+            "/",
+            axum::routing::post(grpc_descriptor_sets::create_grpc_descriptor_set),
+        )
+        .route(
+            "/:name",
+            axum::routing::get(grpc_descriptor_sets::get_grpc_descriptor_set),
+        )
+        .route(
+            "/:name",
+            axum::routing::delete(grpc_descriptor_sets::delete_grpc_descriptor_set),
+        )
+        .with_state(state)
+}
+
+fn webhook_routes(state: ApiState) -> Router {
+    Router::new()
+        .route("/:token", axum::routing::post(webhooks::trigger_webhook))
         .with_state(state)
 }
 
@@ -0,0 +1,141 @@
+use anyhow::{Context, Result, bail};
+use mongodb::bson::{doc, oid::ObjectId};
+
+use rapidcron::config;
+use rapidcron::scheduler::simulator::{DispatchSimulator, SimulationConfig, SimulationReport};
+use rapidcron::storage::mongo::MongoDataSource;
+use rapidcron::types::{CreateTaskRequest, Task};
+
+/// RapidCron Sim - 分发模拟器
+///
+/// 在模拟时钟上重放分发器的候选生成与调度策略，估算任务集对集群的压力
+///
+/// 使用方式：
+/// ```bash
+/// # 从 MongoDB 读取已启用的任务
+/// cargo run --bin rapidcron-sim -- --horizon 86400 --executors 4 --run-duration 30
+///
+/// # 从清单文件（CreateTaskRequest 的 JSON 数组）读取任务
+/// cargo run --bin rapidcron-sim -- --manifest tasks.json --json
+/// ```
+///
+/// 参数：
+/// - `--config <path>`：配置文件，默认 `config.toml`
+/// - `--manifest <path>`：任务清单文件，不指定时从 MongoDB 读取（`enabled` 为 false 的任务不参与模拟）
+/// - `--start <rfc3339>`：模拟开始时间，默认当前时间
+/// - `--horizon <secs>`：模拟时长（秒），默认 3600
+/// - `--executors <n>`：假定的执行器数量，默认 1
+/// - `--run-duration <secs>`：假定的单个实例执行时长（秒），默认 1
+/// - `--json`：以 JSON 输出报告
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let arg_value = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .and_then(|pos| args.get(pos + 1))
+            .cloned()
+    };
+
+    let cfg = config::load(&arg_value("--config").unwrap_or_else(|| "config.toml".to_string()))?;
+
+    let start = match arg_value("--start") {
+        Some(start) => chrono::DateTime::parse_from_rfc3339(&start)
+            .context("无效的开始时间格式")?
+            .with_timezone(&chrono::Utc),
+        None => chrono::Utc::now(),
+    };
+    let horizon_secs = parse_arg(arg_value("--horizon"), 3600)?;
+    let executor_count = parse_arg(arg_value("--executors"), 1)?;
+    let run_duration_secs = parse_arg(arg_value("--run-duration"), 1)?;
+    if horizon_secs <= 0 || executor_count == 0 || run_duration_secs <= 0 {
+        bail!("模拟时长、执行器数量与执行时长必须大于 0");
+    }
+
+    let tasks = match arg_value("--manifest") {
+        Some(path) => load_manifest(&path)?,
+        None => {
+            let db = MongoDataSource::new(&cfg.database).await?;
+            db.find_tasks(Some(doc! { "enabled": true, "deleted_at": null }), None)
+                .await?
+        }
+    };
+
+    let simulator = DispatchSimulator::new(
+        tasks,
+        SimulationConfig {
+            start,
+            horizon_secs,
+            scan_interval_secs: cfg.dispatcher.scan_interval_secs,
+            executor_count,
+            run_duration_secs,
+            scheduling: cfg.dispatcher.scheduling.clone(),
+        },
+    );
+    let report = simulator.run();
+
+    if args.iter().any(|arg| arg == "--json") {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+
+    Ok(())
+}
+
+fn parse_arg<T: std::str::FromStr>(value: Option<String>, default: T) -> Result<T> {
+    match value {
+        Some(value) => value
+            .parse::<T>()
+            .map_err(|_| anyhow::anyhow!("无效的参数值: {}", value)),
+        None => Ok(default),
+    }
+}
+
+/// 读取任务清单，格式与创建任务接口的请求体一致
+fn load_manifest(path: &str) -> Result<Vec<Task>> {
+    let content =
+        std::fs::read_to_string(path).with_context(|| format!("读取任务清单失败: {}", path))?;
+    let requests: Vec<CreateTaskRequest> =
+        serde_json::from_str(&content).context("解析任务清单失败")?;
+
+    requests
+        .iter()
+        .map(|req| {
+            let mut task = req
+                .to_task()
+                .map_err(|e| anyhow::anyhow!("任务 {} 无效: {}", req.name, e))?;
+            task.id = Some(ObjectId::new());
+            Ok(task)
+        })
+        .collect()
+}
+
+fn print_report(report: &SimulationReport) {
+    println!("模拟开始时间: {}", report.start.to_rfc3339());
+    println!("模拟时长: {} 秒", report.horizon_secs);
+    println!(
+        "任务数: {}，执行器: {}，单实例执行时长: {} 秒",
+        report.task_count, report.executor_count, report.run_duration_secs
+    );
+    println!();
+    println!("实例总数: {}", report.total_instances);
+    println!("平均每秒实例数: {:.3}", report.avg_instances_per_sec);
+    println!("单秒峰值实例数: {}", report.peak_instances_per_sec);
+    println!("峰值分钟:");
+    for load in &report.peak_minutes {
+        println!(
+            "  {}  {} 个实例",
+            load.minute.format("%Y-%m-%d %H:%M"),
+            load.instances
+        );
+    }
+    println!();
+    println!("执行器持有未到期消息峰值: {}", report.max_held_messages);
+    println!("队列积压峰值: {}", report.max_backlog);
+    println!("平均队列积压: {:.2}", report.avg_backlog);
+    println!("结束时队列积压: {}", report.final_backlog);
+    println!("最长排队等待: {} 秒", report.max_queue_wait_secs);
+    println!("平均排队等待: {:.2} 秒", report.avg_queue_wait_secs);
+    println!("执行器利用率: {:.1}%", report.executor_utilization * 100.0);
+}
@@ -1,7 +1,10 @@
 use anyhow::Result;
 use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 
+use crate::types::TaskType;
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
@@ -13,6 +16,15 @@ pub struct Config {
     pub logging: LoggingConfig,
     pub service: ServiceConfig,
     pub auth: AuthConfig,
+    #[serde(default)]
+    pub backfill: BackfillConfig,
+    #[serde(default)]
+    pub sla: SlaConfig,
+    /// 仅由执行器进程使用
+    #[serde(default)]
+    pub executor: ExecutorConfig,
+    #[serde(default)]
+    pub secrets: SecretsConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -50,14 +62,28 @@ pub struct EtcdConfig {
 pub struct DispatcherConfig {
     pub scan_interval_secs: u64,
     pub log_retention_days: u32,
+    /// 全局在途实例（待执行与执行中）上限，0 表示不限制
+    #[serde(default)]
+    pub max_concurrent_tasks: usize,
+    /// 被推迟的候选实例最长保留时间（秒），超过后放弃本次触发
+    #[serde(default = "default_max_defer_secs")]
+    pub max_defer_secs: u64,
     #[serde(default)]
     pub scheduling: SchedulingPolicyConfig,
 }
 
+fn default_max_defer_secs() -> u64 {
+    3600
+}
+
+/// 改进 EDF 调度权重，未配置的权重使用默认值
 #[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct SchedulingPolicyConfig {
     pub urgency_weight: f64,
+    pub priority_weight: f64,
     pub aging_weight: f64,
+    /// 按任务 `max_retries` 扣减分数的权重，默认 0（不扣减）
     pub retry_penalty_weight: f64,
     pub aging_window_secs: u64,
 }
@@ -65,9 +91,10 @@ pub struct SchedulingPolicyConfig {
 impl Default for SchedulingPolicyConfig {
     fn default() -> Self {
         Self {
-            urgency_weight: 0.7,
-            aging_weight: 0.25,
-            retry_penalty_weight: 0.05,
+            urgency_weight: 0.5,
+            priority_weight: 0.4,
+            aging_weight: 0.1,
+            retry_penalty_weight: 0.0,
             aging_window_secs: 3600,
         }
     }
@@ -83,6 +110,329 @@ pub struct RetryConfig {
     pub exponential_max_delay: i64,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackfillConfig {
+    pub default_rate_per_sec: u32,
+    pub max_rate_per_sec: u32,
+    pub max_slots: usize,
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            default_rate_per_sec: 5,
+            max_rate_per_sec: 50,
+            max_slots: 10000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlaConfig {
+    pub check_interval_secs: u64,
+    pub expire_after_secs: u64,
+}
+
+impl Default for SlaConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_secs: 30,
+            expire_after_secs: 3600,
+        }
+    }
+}
+
+/// 执行器进程配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecutorConfig {
+    /// 执行命令任务使用的 shell
+    #[serde(default = "default_shell")]
+    pub shell: String,
+    /// 传给 shell 的参数，命令作为最后一个参数传入
+    #[serde(default = "default_shell_args")]
+    pub shell_args: Vec<String>,
+    /// stdout、stderr 各自保留的最大字节数，超出部分丢弃
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+    /// 任务与载荷均未设置超时时间时使用的默认超时（秒），0 表示不限制
+    #[serde(default = "default_task_timeout_secs")]
+    pub default_timeout_secs: u64,
+    /// 脚本任务临时文件所在目录，未设置时使用系统临时目录
+    #[serde(default)]
+    pub script_dir: Option<String>,
+    /// 命令与脚本任务可通过 `run_as_user` 切换到的系统用户，为空时拒绝切换用户
+    #[serde(default)]
+    pub allowed_run_as_users: Vec<String>,
+    /// wasm 任务未设置 fuel 时的默认上限，未设置时只受超时限制
+    #[serde(default)]
+    pub wasm_default_fuel: Option<u64>,
+    /// 允许以 `path` 引用的 wasm 模块文件所在目录，未设置时只能使用上传到数据库中的模块
+    #[serde(default)]
+    pub wasm_module_dir: Option<String>,
+    /// wasm 任务线性内存的上限（字节），任务的 `memory_bytes` 不能超过该值
+    #[serde(default = "default_wasm_max_memory_bytes")]
+    pub wasm_max_memory_bytes: u64,
+    /// 同时执行的实例上限
+    #[serde(default = "default_max_concurrent_tasks")]
+    pub max_concurrent_tasks: usize,
+    /// 执行器暂存的未到期消息数上限，超出的消息转入延迟队列；与并发上限分开计入预取数
+    #[serde(default = "default_prefetch_hold_budget")]
+    pub prefetch_hold_budget: usize,
+    /// 消费者预取（未确认）的消息数上限，未设置时为 `max_concurrent_tasks` 加 `prefetch_hold_budget`
+    #[serde(default)]
+    pub prefetch_count: Option<u16>,
+    /// 各任务类型同时执行的实例上限，未设置的类型只受 `max_concurrent_tasks` 限制
+    #[serde(default)]
+    pub task_type_limits: HashMap<TaskType, usize>,
+    /// 停止时等待正在执行的实例完成的最长时间（秒），超时后仍未完成的实例标记为已中断
+    #[serde(default = "default_drain_timeout_secs")]
+    pub drain_timeout_secs: u64,
+    /// HTTP 服务监听地址（`host:port`）
+    #[serde(default = "default_listen_addr")]
+    pub listen_addr: String,
+    /// 注册到 etcd 的地址（`host:port`），调度器与集群视图通过该地址访问执行器，
+    /// 未设置时为 `localhost` 与监听端口
+    #[serde(default)]
+    pub advertise_addr: Option<String>,
+    /// RabbitMQ 连接地址，未设置时由 `[rabbitmq]` 组成
+    #[serde(default)]
+    pub amqp_url: Option<String>,
+    /// 消费的队列，未设置时使用 `[rabbitmq]` 的 `queue_name`
+    #[serde(default)]
+    pub queue_name: Option<String>,
+    /// etcd 地址列表（`host:port`），为空时使用 `[etcd]` 的 `host` 与 `port`
+    #[serde(default)]
+    pub etcd_endpoints: Vec<String>,
+    /// 执行器标签，随服务注册信息上报到集群视图
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+}
+
+/// 执行器配置中可由命令行参数（`--listen-addr`）与环境变量（`RAPIDCRON_EXECUTOR_LISTEN_ADDR`）覆盖的配置项
+pub const EXECUTOR_OVERRIDE_KEYS: [&str; 7] = [
+    "listen_addr",
+    "advertise_addr",
+    "amqp_url",
+    "queue_name",
+    "etcd_endpoints",
+    "max_concurrent_tasks",
+    "labels",
+];
+
+/// 覆盖执行器配置的环境变量前缀
+pub const EXECUTOR_ENV_PREFIX: &str = "RAPIDCRON_EXECUTOR_";
+
+fn default_shell() -> String {
+    "/bin/sh".to_string()
+}
+
+fn default_shell_args() -> Vec<String> {
+    vec!["-c".to_string()]
+}
+
+fn default_max_output_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_task_timeout_secs() -> u64 {
+    300
+}
+
+fn default_wasm_max_memory_bytes() -> u64 {
+    256 * 1024 * 1024
+}
+
+fn default_prefetch_hold_budget() -> usize {
+    16
+}
+
+fn default_max_concurrent_tasks() -> usize {
+    8
+}
+
+fn default_drain_timeout_secs() -> u64 {
+    30
+}
+
+fn default_listen_addr() -> String {
+    "0.0.0.0:8081".to_string()
+}
+
+/// 拆分 `host:port` 形式的地址
+pub fn split_host_port(addr: &str) -> std::result::Result<(String, u16), String> {
+    let (host, port) = addr
+        .rsplit_once(':')
+        .ok_or_else(|| format!("地址格式应为 host:port: {}", addr))?;
+    let port = port
+        .parse::<u16>()
+        .map_err(|_| format!("无效的端口: {}", addr))?;
+    if host.is_empty() {
+        return Err(format!("地址缺少主机名: {}", addr));
+    }
+    Ok((host.to_string(), port))
+}
+
+/// 逗号分隔的列表，忽略空项
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl ExecutorConfig {
+    /// 消费者预取数，至少为 1
+    ///
+    /// 默认为并发上限加暂存名额：暂存的未到期消息不超过 `prefetch_hold_budget`，
+    /// 其余名额始终留给已到期、等待或正在执行的消息
+    pub fn prefetch_count(&self) -> u16 {
+        self.prefetch_count
+            .unwrap_or_else(|| {
+                self.max_concurrent_tasks
+                    .saturating_add(self.prefetch_hold_budget)
+                    .min(u16::MAX as usize) as u16
+            })
+            .max(1)
+    }
+
+    /// 按配置项名称覆盖配置，`labels` 为逗号分隔的 `key=value` 并合并到已有标签，
+    /// `etcd_endpoints` 为逗号分隔的地址列表
+    pub fn set_override(&mut self, key: &str, value: &str) -> std::result::Result<(), String> {
+        let value = value.trim();
+        match key {
+            "listen_addr" => {
+                split_host_port(value)?;
+                self.listen_addr = value.to_string();
+            }
+            "advertise_addr" => {
+                split_host_port(value)?;
+                self.advertise_addr = Some(value.to_string());
+            }
+            "amqp_url" => self.amqp_url = Some(value.to_string()),
+            "queue_name" => self.queue_name = Some(value.to_string()),
+            "etcd_endpoints" => self.etcd_endpoints = split_list(value),
+            "max_concurrent_tasks" => {
+                self.max_concurrent_tasks = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or_else(|| format!("max_concurrent_tasks 必须为正整数: {}", value))?;
+            }
+            "labels" => {
+                for label in split_list(value) {
+                    let (name, label_value) = label
+                        .split_once('=')
+                        .ok_or_else(|| format!("标签格式应为 key=value: {}", label))?;
+                    let name = name.trim();
+                    if name.is_empty() {
+                        return Err(format!("标签名不能为空: {}", label));
+                    }
+                    self.labels
+                        .insert(name.to_string(), label_value.trim().to_string());
+                }
+            }
+            _ => return Err(format!("未知的执行器配置项: {}", key)),
+        }
+        Ok(())
+    }
+
+    /// 用 `RAPIDCRON_EXECUTOR_<配置项>` 环境变量覆盖配置，`lookup` 按变量名读取环境变量
+    pub fn apply_env(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> std::result::Result<(), String> {
+        for key in EXECUTOR_OVERRIDE_KEYS {
+            let name = format!("{}{}", EXECUTOR_ENV_PREFIX, key.to_uppercase());
+            if let Some(value) = lookup(&name).filter(|value| !value.trim().is_empty()) {
+                self.set_override(key, &value)
+                    .map_err(|e| format!("{}: {}", name, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 校验地址与并发配置，覆盖配置后调用
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        split_host_port(&self.listen_addr)?;
+        if let Some(addr) = &self.advertise_addr {
+            split_host_port(addr)?;
+        }
+        if self.max_concurrent_tasks == 0 {
+            return Err("max_concurrent_tasks 必须大于 0".to_string());
+        }
+        if self.wasm_default_fuel == Some(0) {
+            return Err("wasm_default_fuel 必须大于 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// 生效的注册地址
+    pub fn effective_advertise_addr(&self) -> std::result::Result<(String, u16), String> {
+        match &self.advertise_addr {
+            Some(addr) => split_host_port(addr),
+            None => {
+                let (_, port) = split_host_port(&self.listen_addr)?;
+                Ok(("localhost".to_string(), port))
+            }
+        }
+    }
+
+    /// 生效的 RabbitMQ 连接地址
+    pub fn effective_amqp_url(&self, rabbitmq: &RabbitMQConfig) -> String {
+        self.amqp_url.clone().unwrap_or_else(|| {
+            format!(
+                "amqp://{}:{}@{}:{}",
+                rabbitmq.username, rabbitmq.password, rabbitmq.host, rabbitmq.port
+            )
+        })
+    }
+
+    /// 生效的队列名称
+    pub fn effective_queue_name(&self, rabbitmq: &RabbitMQConfig) -> String {
+        self.queue_name
+            .clone()
+            .unwrap_or_else(|| rabbitmq.queue_name.clone())
+    }
+
+    /// 生效的 etcd 地址列表
+    pub fn effective_etcd_endpoints(&self, etcd: &EtcdConfig) -> Vec<String> {
+        if self.etcd_endpoints.is_empty() {
+            vec![format!("{}:{}", etcd.host, etcd.port)]
+        } else {
+            self.etcd_endpoints.clone()
+        }
+    }
+}
+
+impl Default for ExecutorConfig {
+    fn default() -> Self {
+        Self {
+            shell: default_shell(),
+            shell_args: default_shell_args(),
+            max_output_bytes: default_max_output_bytes(),
+            default_timeout_secs: default_task_timeout_secs(),
+            script_dir: None,
+            allowed_run_as_users: Vec::new(),
+            wasm_default_fuel: None,
+            wasm_module_dir: None,
+            wasm_max_memory_bytes: default_wasm_max_memory_bytes(),
+            max_concurrent_tasks: default_max_concurrent_tasks(),
+            prefetch_hold_budget: default_prefetch_hold_budget(),
+            prefetch_count: None,
+            task_type_limits: HashMap::new(),
+            drain_timeout_secs: default_drain_timeout_secs(),
+            listen_addr: default_listen_addr(),
+            advertise_addr: None,
+            amqp_url: None,
+            queue_name: None,
+            etcd_endpoints: Vec::new(),
+            labels: BTreeMap::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct LoggingConfig {
     pub level: String,
@@ -104,8 +454,133 @@ pub struct AuthConfig {
     pub role: String,
 }
 
+/// 密钥存储配置
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SecretsConfig {
+    /// 加密密钥值的主密钥（64 个十六进制字符），可被环境变量 `RAPIDCRON_MASTER_KEY` 覆盖
+    #[serde(default)]
+    pub master_key: String,
+}
+
+impl SecretsConfig {
+    /// 生效的主密钥，环境变量优先；都未设置时返回 None
+    pub fn master_key(&self) -> Option<String> {
+        std::env::var(crate::secrets::MASTER_KEY_ENV)
+            .ok()
+            .filter(|key| !key.is_empty())
+            .or_else(|| Some(self.master_key.clone()).filter(|key| !key.is_empty()))
+    }
+}
+
 pub fn load(path: &str) -> Result<Config> {
     let content = fs::read_to_string(path)?;
     let config: Config = toml::from_str(&content)?;
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rabbitmq() -> RabbitMQConfig {
+        RabbitMQConfig {
+            host: "mq".to_string(),
+            port: 5672,
+            username: "guest".to_string(),
+            password: "secret".to_string(),
+            queue_name: "rapidcron-tasks".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_scheduling_section_without_new_weights() {
+        // 旧版本的配置没有 priority_weight，只有 retry_penalty_weight
+        let scheduling: SchedulingPolicyConfig = toml::from_str(
+            r#"
+            urgency_weight = 0.7
+            aging_weight = 0.25
+            retry_penalty_weight = 0.05
+            aging_window_secs = 3600
+            "#,
+        )
+        .unwrap();
+        assert_eq!(scheduling.urgency_weight, 0.7);
+        assert_eq!(scheduling.priority_weight, 0.4);
+        assert_eq!(scheduling.retry_penalty_weight, 0.05);
+    }
+
+    #[test]
+    fn test_partial_executor_section_uses_defaults() {
+        let config: ExecutorConfig = toml::from_str("max_concurrent_tasks = 4").unwrap();
+        assert_eq!(config.shell, "/bin/sh");
+        assert_eq!(config.shell_args, vec!["-c".to_string()]);
+        assert_eq!(config.max_output_bytes, 65536);
+        assert_eq!(config.max_concurrent_tasks, 4);
+    }
+
+    #[test]
+    fn test_executor_defaults_fall_back_to_shared_sections() {
+        let config = ExecutorConfig::default();
+        let etcd = EtcdConfig {
+            host: "etcd".to_string(),
+            port: 2379,
+            service_prefix: "rapidcron/services".to_string(),
+            dead_threshold_secs: 60,
+        };
+
+        assert_eq!(
+            config.effective_amqp_url(&rabbitmq()),
+            "amqp://guest:secret@mq:5672"
+        );
+        assert_eq!(config.effective_queue_name(&rabbitmq()), "rapidcron-tasks");
+        assert_eq!(config.effective_etcd_endpoints(&etcd), vec!["etcd:2379"]);
+        assert_eq!(
+            config.effective_advertise_addr().unwrap(),
+            ("localhost".to_string(), 8081)
+        );
+    }
+
+    #[test]
+    fn test_executor_env_and_cli_overrides() {
+        let mut config = ExecutorConfig::default();
+        config.labels.insert("zone".to_string(), "a".to_string());
+
+        let env = HashMap::from([
+            (
+                "RAPIDCRON_EXECUTOR_ADVERTISE_ADDR".to_string(),
+                "executor-1.internal:9000".to_string(),
+            ),
+            (
+                "RAPIDCRON_EXECUTOR_ETCD_ENDPOINTS".to_string(),
+                "etcd-1:2379, etcd-2:2379".to_string(),
+            ),
+            (
+                "RAPIDCRON_EXECUTOR_LABELS".to_string(),
+                "gpu=true".to_string(),
+            ),
+        ]);
+        config.apply_env(|name| env.get(name).cloned()).unwrap();
+        // 命令行参数在环境变量之后应用
+        config.set_override("listen_addr", "0.0.0.0:9000").unwrap();
+        config.set_override("labels", "zone=b").unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(
+            config.effective_advertise_addr().unwrap(),
+            ("executor-1.internal".to_string(), 9000)
+        );
+        assert_eq!(config.etcd_endpoints, vec!["etcd-1:2379", "etcd-2:2379"]);
+        assert_eq!(config.labels["zone"], "b");
+        assert_eq!(config.labels["gpu"], "true");
+
+        assert!(config.set_override("listen_addr", "8081").is_err());
+        assert!(config.set_override("max_concurrent_tasks", "0").is_err());
+        assert!(config.set_override("labels", "gpu").is_err());
+        assert!(config.set_override("shell", "/bin/bash").is_err());
+        let env = HashMap::from([(
+            "RAPIDCRON_EXECUTOR_MAX_CONCURRENT_TASKS".to_string(),
+            "many".to_string(),
+        )]);
+        assert!(config.apply_env(|name| env.get(name).cloned()).is_err());
+    }
+}
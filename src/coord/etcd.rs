@@ -1,6 +1,6 @@
 use crate::error::{Error, Result};
 use etcd_client::{Client, GetOptions, PutOptions};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{Mutex, RwLock};
@@ -17,6 +17,9 @@ pub struct ServiceInfo {
     pub metadata: Option<String>,
     pub started_at: i64,
     pub last_heartbeat: i64,
+    /// 服务标签（执行器的 `executor.labels`）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
 }
 
 /// 服务注册和发现
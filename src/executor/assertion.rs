@@ -0,0 +1,283 @@
+//! HTTP 任务的响应断言
+//!
+//! 断言按状态码、响应头、耗时、响应体子串、正则、JSONPath 的顺序求值，
+//! 返回首个未通过的断言。
+
+use crate::types::{AssertionFailure, AssertionKind, HttpAssertions};
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderName};
+use serde_json_path::JsonPath;
+use std::time::Duration;
+
+/// 参与断言的响应信息
+pub struct HttpResponse<'a> {
+    pub status: u16,
+    pub headers: &'a HeaderMap,
+    pub body: &'a str,
+    /// 从发送请求到读完响应体的耗时
+    pub latency: Duration,
+}
+
+/// 校验断言配置，在创建或更新任务时调用
+pub fn validate_assertions(assertions: &HttpAssertions) -> Result<(), String> {
+    if let Some(status) = &assertions.status {
+        if status.is_empty() {
+            return Err("断言的状态码集合不能为空".to_string());
+        }
+        if let Some(code) = status.iter().find(|code| !(100..=599).contains(*code)) {
+            return Err(format!("无效的断言状态码: {}", code));
+        }
+    }
+    if assertions.body_contains.as_deref() == Some("") {
+        return Err("body_contains 不能为空".to_string());
+    }
+    if let Some(pattern) = &assertions.body_regex {
+        Regex::new(pattern).map_err(|e| format!("无效的 body_regex: {}", e))?;
+    }
+    for assertion in &assertions.json_path {
+        JsonPath::parse(&assertion.path)
+            .map_err(|e| format!("无效的 JSONPath {}: {}", assertion.path, e))?;
+    }
+    for name in &assertions.headers_present {
+        HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("无效的响应头名称: {}", name))?;
+    }
+    if assertions.max_latency_ms == Some(0) {
+        return Err("max_latency_ms 必须大于0".to_string());
+    }
+    Ok(())
+}
+
+fn failure(kind: AssertionKind, message: String) -> Result<(), AssertionFailure> {
+    Err(AssertionFailure { kind, message })
+}
+
+/// 对响应求值断言；未设置状态码断言时要求 2xx
+pub fn evaluate(
+    assertions: Option<&HttpAssertions>,
+    response: &HttpResponse<'_>,
+) -> Result<(), AssertionFailure> {
+    let expected_status = assertions.and_then(|assertions| assertions.status.as_ref());
+    let status_ok = match expected_status {
+        Some(expected) => expected.contains(&response.status),
+        None => (200..300).contains(&response.status),
+    };
+    if !status_ok {
+        let message = match expected_status {
+            Some(expected) => format!(
+                "状态码 {} 不在期望的集合 {:?} 中",
+                response.status, expected
+            ),
+            None => format!("HTTP 错误: {}", response.status),
+        };
+        return failure(AssertionKind::Status, message);
+    }
+
+    let Some(assertions) = assertions else {
+        return Ok(());
+    };
+
+    if let Some(name) = assertions
+        .headers_present
+        .iter()
+        .find(|name| !response.headers.contains_key(name.as_str()))
+    {
+        return failure(AssertionKind::Header, format!("缺少响应头 {}", name));
+    }
+
+    if let Some(max_latency_ms) = assertions.max_latency_ms {
+        let latency_ms = response.latency.as_millis();
+        if latency_ms > u128::from(max_latency_ms) {
+            return failure(
+                AssertionKind::MaxLatency,
+                format!("耗时 {}ms 超过上限 {}ms", latency_ms, max_latency_ms),
+            );
+        }
+    }
+
+    if let Some(expected) = &assertions.body_contains
+        && !response.body.contains(expected.as_str())
+    {
+        return failure(
+            AssertionKind::BodyContains,
+            format!("响应体不包含 {:?}", expected),
+        );
+    }
+
+    if let Some(pattern) = &assertions.body_regex {
+        match Regex::new(pattern) {
+            Ok(regex) if regex.is_match(response.body) => {}
+            Ok(_) => {
+                return failure(
+                    AssertionKind::BodyRegex,
+                    format!("响应体不匹配正则 {}", pattern),
+                );
+            }
+            Err(e) => {
+                return failure(AssertionKind::BodyRegex, format!("无效的正则: {}", e));
+            }
+        }
+    }
+
+    if !assertions.json_path.is_empty() {
+        let body: serde_json::Value = match serde_json::from_str(response.body) {
+            Ok(body) => body,
+            Err(e) => {
+                return failure(AssertionKind::JsonPath, format!("响应体不是 JSON: {}", e));
+            }
+        };
+        for assertion in &assertions.json_path {
+            let path = match JsonPath::parse(&assertion.path) {
+                Ok(path) => path,
+                Err(e) => {
+                    return failure(AssertionKind::JsonPath, format!("无效的 JSONPath: {}", e));
+                }
+            };
+            match path.query(&body).exactly_one() {
+                Ok(actual) if *actual == assertion.equals => {}
+                Ok(actual) => {
+                    return failure(
+                        AssertionKind::JsonPath,
+                        format!(
+                            "{} 的值为 {}，期望 {}",
+                            assertion.path, actual, assertion.equals
+                        ),
+                    );
+                }
+                Err(_) => {
+                    return failure(
+                        AssertionKind::JsonPath,
+                        format!("{} 未选中唯一的值", assertion.path),
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::JsonPathAssertion;
+
+    fn response<'a>(status: u16, headers: &'a HeaderMap, body: &'a str) -> HttpResponse<'a> {
+        HttpResponse {
+            status,
+            headers,
+            body,
+            latency: Duration::from_millis(120),
+        }
+    }
+
+    fn failed_kind(
+        assertions: Option<&HttpAssertions>,
+        response: &HttpResponse<'_>,
+    ) -> Option<AssertionKind> {
+        evaluate(assertions, response)
+            .err()
+            .map(|failure| failure.kind)
+    }
+
+    #[test]
+    fn test_default_requires_2xx() {
+        let headers = HeaderMap::new();
+        assert!(evaluate(None, &response(204, &headers, "")).is_ok());
+        let failure = evaluate(None, &response(503, &headers, "")).unwrap_err();
+        assert_eq!(failure.kind, AssertionKind::Status);
+        assert_eq!(failure.message, "HTTP 错误: 503");
+    }
+
+    #[test]
+    fn test_evaluate_assertions() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", "application/json".parse().unwrap());
+        let body = r#"{"status":"ok","items":[{"id":1},{"id":2}]}"#;
+        let assertions = HttpAssertions {
+            status: Some(vec![200, 404]),
+            body_contains: Some("items".to_string()),
+            body_regex: Some(r#""id":\s*2"#.to_string()),
+            json_path: vec![
+                JsonPathAssertion {
+                    path: "$.status".to_string(),
+                    equals: serde_json::json!("ok"),
+                },
+                JsonPathAssertion {
+                    path: "$.items[1].id".to_string(),
+                    equals: serde_json::json!(2),
+                },
+            ],
+            headers_present: vec!["Content-Type".to_string()],
+            max_latency_ms: Some(500),
+        };
+        assert!(evaluate(Some(&assertions), &response(200, &headers, body)).is_ok());
+        // 期望集合中的非 2xx 状态码同样通过
+        assert!(evaluate(Some(&assertions), &response(404, &headers, body)).is_ok());
+
+        assert_eq!(
+            failed_kind(Some(&assertions), &response(500, &headers, body)),
+            Some(AssertionKind::Status)
+        );
+        assert_eq!(
+            failed_kind(Some(&assertions), &response(200, &HeaderMap::new(), body)),
+            Some(AssertionKind::Header)
+        );
+
+        let mut slow = response(200, &headers, body);
+        slow.latency = Duration::from_millis(800);
+        assert_eq!(
+            failed_kind(Some(&assertions), &slow),
+            Some(AssertionKind::MaxLatency)
+        );
+
+        let error_body = r#"{"status":"error","items":[{"id":2}]}"#;
+        let failure =
+            evaluate(Some(&assertions), &response(200, &headers, error_body)).unwrap_err();
+        assert_eq!(failure.kind, AssertionKind::JsonPath);
+        assert!(failure.message.contains("$.status"));
+
+        assert_eq!(
+            failed_kind(Some(&assertions), &response(200, &headers, "plain text")),
+            Some(AssertionKind::BodyContains)
+        );
+    }
+
+    #[test]
+    fn test_validate_assertions() {
+        assert!(validate_assertions(&HttpAssertions::default()).is_ok());
+
+        let invalid = [
+            HttpAssertions {
+                status: Some(vec![]),
+                ..Default::default()
+            },
+            HttpAssertions {
+                status: Some(vec![200, 700]),
+                ..Default::default()
+            },
+            HttpAssertions {
+                body_regex: Some("(unclosed".to_string()),
+                ..Default::default()
+            },
+            HttpAssertions {
+                json_path: vec![JsonPathAssertion {
+                    path: "status".to_string(),
+                    equals: serde_json::json!("ok"),
+                }],
+                ..Default::default()
+            },
+            HttpAssertions {
+                headers_present: vec!["bad header".to_string()],
+                ..Default::default()
+            },
+            HttpAssertions {
+                max_latency_ms: Some(0),
+                ..Default::default()
+            },
+        ];
+        for assertions in &invalid {
+            assert!(validate_assertions(assertions).is_err(), "{:?}", assertions);
+        }
+    }
+}
@@ -0,0 +1,663 @@
+//! 命令任务执行：通过配置的 shell 在独立进程组中运行命令并采集输出
+//!
+//! 命令退出后（包括超时被终止）会向整个进程组发送 SIGKILL，
+//! 避免命令派生的子进程在执行器上残留。
+
+use crate::config::ExecutorConfig;
+use crate::executor::runner::{RunContext, RunOutcome, TaskRunner};
+use crate::executor::sandbox::{self, LimitViolation};
+use crate::types::{ExecutionResult, ResourceLimits, SandboxOptions, TaskPayload, TaskStatus};
+use futures::future::BoxFuture;
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::os::unix::process::ExitStatusExt;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tracing::info;
+
+/// 一次命令执行的参数
+#[derive(Debug, Clone, Default)]
+pub struct CommandSpec<'a> {
+    pub command: &'a str,
+    /// 额外的环境变量
+    pub env: Option<&'a BTreeMap<String, String>>,
+    /// 工作目录，`None` 时使用执行器的当前目录
+    pub working_dir: Option<&'a str>,
+    /// 以指定的系统用户身份运行，需要执行器具有切换用户的权限
+    pub run_as_user: Option<&'a str>,
+    /// 写入标准输入的内容，`None` 时标准输入为空
+    pub stdin: Option<&'a str>,
+    /// 资源限制
+    pub limits: Option<ResourceLimits>,
+    /// 隔离选项
+    pub sandbox: Option<SandboxOptions>,
+    /// 超时时间，`None` 时不限制执行时长
+    pub timeout: Option<Duration>,
+}
+
+/// 命令执行结果
+#[derive(Debug, Clone, Default)]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// 进程退出码，被信号终止时为 `None`
+    pub exit_code: Option<i32>,
+    /// 终止进程的信号
+    pub signal: Option<i32>,
+    pub stdout_truncated: bool,
+    pub stderr_truncated: bool,
+    pub timed_out: bool,
+    /// 由资源限制引起的失败
+    pub limit_violation: Option<LimitViolation>,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.exit_code == Some(0)
+    }
+
+    /// 写入执行结果的退出码，被信号终止时按 shell 惯例记为 128 + 信号值
+    pub fn status_code(&self) -> Option<i32> {
+        self.exit_code.or(self.signal.map(|signal| 128 + signal))
+    }
+
+    /// 失败原因，成功时为 `None`
+    pub fn failure_reason(&self) -> Option<String> {
+        if self.timed_out {
+            Some("命令执行超时，已终止进程组".to_string())
+        } else if self.success() {
+            None
+        } else if let Some(violation) = self.limit_violation {
+            Some(violation.to_string())
+        } else if let Some(signal) = self.signal {
+            Some(format!("命令被信号 {} 终止", signal))
+        } else {
+            match self.exit_code {
+                Some(0) => None,
+                Some(code) => Some(format!("命令退出码为 {}", code)),
+                None => Some("命令异常退出".to_string()),
+            }
+        }
+    }
+}
+
+/// 命令执行器
+#[derive(Debug, Clone)]
+pub struct CommandRunner {
+    shell: String,
+    shell_args: Vec<String>,
+    max_output_bytes: usize,
+    allowed_run_as_users: Vec<String>,
+}
+
+impl CommandRunner {
+    pub fn new(config: &ExecutorConfig) -> Self {
+        Self {
+            shell: config.shell.clone(),
+            shell_args: config.shell_args.clone(),
+            max_output_bytes: config.max_output_bytes,
+            allowed_run_as_users: config.allowed_run_as_users.clone(),
+        }
+    }
+
+    /// 确认 `executor.allowed_run_as_users` 允许切换到该用户
+    pub fn check_run_as_user(&self, name: &str) -> std::io::Result<()> {
+        if self
+            .allowed_run_as_users
+            .iter()
+            .any(|allowed| allowed == name)
+        {
+            return Ok(());
+        }
+        Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!(
+                "用户 {} 不在 executor.allowed_run_as_users 中，拒绝切换用户",
+                name
+            ),
+        ))
+    }
+
+    /// 执行命令
+    pub async fn run(&self, spec: &CommandSpec<'_>) -> std::io::Result<CommandOutput> {
+        let mut command = Command::new(&self.shell);
+        command.args(&self.shell_args).arg(spec.command);
+        self.run_program(command, spec).await
+    }
+
+    /// 按 `spec` 中除 `command` 外的参数运行已指定程序与参数的进程
+    pub async fn run_program(
+        &self,
+        mut command: Command,
+        spec: &CommandSpec<'_>,
+    ) -> std::io::Result<CommandOutput> {
+        command
+            .stdin(if spec.stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .kill_on_drop(true);
+        if let Some(name) = spec.run_as_user {
+            self.check_run_as_user(name)?;
+        }
+        let user = spec.run_as_user.map(lookup_user).transpose()?;
+        if let Some(user) = &user {
+            command
+                .env("HOME", &user.home)
+                .env("USER", &user.name)
+                .env("LOGNAME", &user.name);
+        }
+        // 启用隔离时需先进入命名空间再切换用户，由 sandbox::apply 完成
+        let drop_to = match (&user, spec.sandbox) {
+            (Some(user), Some(_)) => Some((user.uid, user.gid)),
+            (Some(user), None) => {
+                command.uid(user.uid).gid(user.gid);
+                None
+            }
+            (None, _) => None,
+        };
+        sandbox::apply(&mut command, spec.limits, spec.sandbox, drop_to);
+        if let Some(env) = spec.env {
+            command.envs(env);
+        }
+        if let Some(working_dir) = spec.working_dir {
+            command.current_dir(working_dir);
+        }
+
+        let mut child = command.spawn()?;
+        // kill_on_drop 只终止 shell，执行被中止（如执行器停止时 abort）时由守卫终止整个进程组；
+        // 守卫在 child 之前释放，此时组长尚未被回收，进程组号不会被复用
+        let mut group = ProcessGroupGuard(child.id());
+
+        if let (Some(mut stdin), Some(input)) = (child.stdin.take(), spec.stdin) {
+            let input = input.as_bytes().to_vec();
+            // 命令可能不读取标准输入，写入失败（管道已关闭）时忽略
+            tokio::spawn(async move {
+                let _ = stdin.write_all(&input).await;
+            });
+        }
+
+        let stdout = child.stdout.take().map(|out| {
+            let max = self.max_output_bytes;
+            tokio::spawn(async move { read_capped(out, max).await })
+        });
+        let stderr = child.stderr.take().map(|err| {
+            let max = self.max_output_bytes;
+            tokio::spawn(async move { read_capped(err, max).await })
+        });
+
+        let (status, timed_out) = match spec.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, child.wait()).await {
+                Ok(status) => (status?, false),
+                Err(_) => {
+                    group.kill();
+                    (child.wait().await?, true)
+                }
+            },
+            None => (child.wait().await?, false),
+        };
+        // 清理命令退出后残留在进程组中的子进程，使输出管道得以关闭
+        group.release();
+
+        let (stdout, stdout_truncated) = join_output(stdout).await;
+        let (stderr, stderr_truncated) = join_output(stderr).await;
+
+        let limit_violation = spec.limits.as_ref().and_then(|limits| {
+            sandbox::detect_limit_violation(limits, status.signal(), status.code(), &stderr)
+        });
+
+        Ok(CommandOutput {
+            stdout,
+            stderr,
+            exit_code: status.code(),
+            signal: status.signal(),
+            stdout_truncated,
+            stderr_truncated,
+            timed_out,
+            limit_violation,
+        })
+    }
+}
+
+impl TaskRunner for CommandRunner {
+    fn execute<'a>(&'a self, ctx: RunContext<'a>) -> BoxFuture<'a, RunOutcome> {
+        Box::pin(async move {
+            let TaskPayload::Command {
+                command,
+                env,
+                working_dir,
+                run_as_user,
+                stdin,
+                limits,
+                sandbox,
+                ..
+            } = ctx.payload
+            else {
+                return RunOutcome::payload_mismatch(&ctx.task.task_type);
+            };
+            info!("执行命令任务: {}", ctx.secret_mask.mask(command));
+            let spec = CommandSpec {
+                command,
+                env: env.as_ref(),
+                working_dir: working_dir.as_deref(),
+                run_as_user: run_as_user.as_deref(),
+                stdin: stdin.as_deref(),
+                limits: *limits,
+                sandbox: *sandbox,
+                timeout: ctx.timeout,
+            };
+            match self.run(&spec).await {
+                Ok(output) => command_outcome(output),
+                Err(e) => RunOutcome::failed("启动命令失败", e),
+            }
+        })
+    }
+}
+
+/// 将命令输出转换为执行结果
+pub fn command_outcome(output: CommandOutput) -> RunOutcome {
+    let failure = output.failure_reason();
+    let status_code = output.status_code();
+    let mut stdout = output.stdout;
+    if output.stdout_truncated {
+        stdout.push_str("\n...（输出已截断）");
+    }
+    let mut stderr = output.stderr;
+    if output.stderr_truncated {
+        stderr.push_str("\n...（输出已截断）");
+    }
+    let stderr = (!stderr.is_empty()).then_some(stderr);
+
+    match failure {
+        None => RunOutcome {
+            result: ExecutionResult {
+                output: Some(stdout),
+                error: stderr,
+                exit_code: output.exit_code,
+                failed_assertion: None,
+            },
+            status: TaskStatus::Success,
+            summary: Some("命令执行成功".to_string()),
+            error: None,
+        },
+        Some(reason) => {
+            let status = if output.timed_out {
+                TaskStatus::TimedOut
+            } else {
+                TaskStatus::Failed
+            };
+            let error = match &stderr {
+                Some(stderr) => format!("{}: {}", reason, stderr.trim_end()),
+                None => reason.clone(),
+            };
+            RunOutcome {
+                result: ExecutionResult {
+                    output: Some(stdout),
+                    error: Some(error.clone()),
+                    exit_code: status_code,
+                    failed_assertion: None,
+                },
+                status,
+                summary: Some(reason),
+                error: Some(error),
+            }
+        }
+    }
+}
+
+/// 系统用户信息
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct UserInfo {
+    pub(crate) name: String,
+    pub(crate) uid: u32,
+    pub(crate) gid: u32,
+    pub(crate) home: String,
+}
+
+/// 按用户名查询系统用户
+pub(crate) fn lookup_user(name: &str) -> std::io::Result<UserInfo> {
+    let not_found = || {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("用户不存在: {}", name),
+        )
+    };
+    let c_name = CString::new(name).map_err(|_| not_found())?;
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut buf = vec![0 as libc::c_char; 16 * 1024];
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    // SAFETY: 各指针在调用期间有效，buf 的长度与传入的长度一致
+    let code = unsafe {
+        libc::getpwnam_r(
+            c_name.as_ptr(),
+            &mut passwd,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        )
+    };
+    if code != 0 {
+        return Err(std::io::Error::from_raw_os_error(code));
+    }
+    if result.is_null() {
+        return Err(not_found());
+    }
+    // SAFETY: 查询成功时 pw_dir 指向 buf 中以空字符结尾的字符串
+    let home = unsafe { CStr::from_ptr(passwd.pw_dir) }
+        .to_string_lossy()
+        .into_owned();
+    Ok(UserInfo {
+        name: name.to_string(),
+        uid: passwd.pw_uid,
+        gid: passwd.pw_gid,
+        home,
+    })
+}
+
+/// 向进程组发送 SIGKILL，进程组已不存在时忽略
+fn kill_process_group(pgid: u32) {
+    // SAFETY: kill 只发送信号，不涉及内存访问
+    unsafe {
+        libc::kill(-(pgid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+/// 释放时向命令的进程组发送 SIGKILL
+struct ProcessGroupGuard(Option<u32>);
+
+impl ProcessGroupGuard {
+    fn kill(&self) {
+        if let Some(pgid) = self.0 {
+            kill_process_group(pgid);
+        }
+    }
+
+    /// 命令结束后终止残留的进程并解除守卫，组长已被回收，之后不再向该进程组号发送信号
+    fn release(&mut self) {
+        self.kill();
+        self.0 = None;
+    }
+}
+
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+/// 读取全部输出，只保留前 `max` 个字节；超出部分继续读取并丢弃，避免子进程阻塞在管道上
+async fn read_capped<R: AsyncRead + Unpin>(mut reader: R, max: usize) -> (Vec<u8>, bool) {
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut buf = [0u8; 8192];
+    loop {
+        match reader.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                let room = max.saturating_sub(kept.len());
+                kept.extend_from_slice(&buf[..n.min(room)]);
+                truncated |= n > room;
+            }
+        }
+    }
+    (kept, truncated)
+}
+
+async fn join_output(handle: Option<tokio::task::JoinHandle<(Vec<u8>, bool)>>) -> (String, bool) {
+    match handle {
+        Some(handle) => {
+            let (bytes, truncated) = handle.await.unwrap_or_default();
+            (String::from_utf8_lossy(&bytes).into_owned(), truncated)
+        }
+        None => (String::new(), false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runner(max_output_bytes: usize) -> CommandRunner {
+        CommandRunner::new(&ExecutorConfig {
+            max_output_bytes,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_run_captures_output_and_exit_code() {
+        let output = runner(1024)
+            .run(&CommandSpec {
+                command: "echo out; echo err >&2; exit 3",
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output.stdout, "out\n");
+        assert_eq!(output.stderr, "err\n");
+        assert_eq!(output.exit_code, Some(3));
+        assert!(!output.success());
+        assert_eq!(output.failure_reason().unwrap(), "命令退出码为 3");
+    }
+
+    #[tokio::test]
+    async fn test_run_as_user_requires_allowlist() {
+        let spec = CommandSpec {
+            command: "id -u",
+            run_as_user: Some("root"),
+            ..Default::default()
+        };
+        let err = runner(1024).run(&spec).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+
+        let allowed = CommandRunner::new(&ExecutorConfig {
+            allowed_run_as_users: vec!["nobody".to_string()],
+            ..Default::default()
+        });
+        assert!(allowed.check_run_as_user("nobody").is_ok());
+        assert!(allowed.run(&spec).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_truncates_output() {
+        let output = runner(4)
+            .run(&CommandSpec {
+                command: "printf 0123456789",
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert!(output.success());
+        assert_eq!(output.stdout, "0123");
+        assert!(output.stdout_truncated);
+        assert!(!output.stderr_truncated);
+    }
+
+    #[tokio::test]
+    async fn test_run_kills_process_group_on_timeout() {
+        let started = std::time::Instant::now();
+        // 后台子进程持有输出管道，只有终止整个进程组才能及时返回
+        let output = runner(1024)
+            .run(&CommandSpec {
+                command: "sleep 30 & sleep 30",
+                timeout: Some(Duration::from_millis(200)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert!(output.timed_out);
+        assert_eq!(output.signal, Some(libc::SIGKILL));
+        assert_eq!(output.status_code(), Some(128 + libc::SIGKILL));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_aborting_run_kills_process_group() {
+        let pid_file = std::env::temp_dir().join(format!("rapidcron-{}.pid", uuid::Uuid::new_v4()));
+        let command = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+        let handle = tokio::spawn(async move {
+            runner(1024)
+                .run(&CommandSpec {
+                    command: &command,
+                    ..Default::default()
+                })
+                .await
+        });
+
+        let mut pid = None;
+        for _ in 0..100 {
+            if let Some(found) = std::fs::read_to_string(&pid_file)
+                .ok()
+                .and_then(|content| content.trim().parse::<u32>().ok())
+            {
+                pid = Some(found);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let _ = std::fs::remove_file(&pid_file);
+        let pid = pid.expect("后台进程未启动");
+
+        // 执行器停止时中止执行，后台进程随进程组一起被终止（已退出或只剩僵尸进程）
+        handle.abort();
+        assert!(handle.await.unwrap_err().is_cancelled());
+        let alive = || {
+            std::fs::read_to_string(format!("/proc/{}/stat", pid)).is_ok_and(|stat| {
+                !stat
+                    .rsplit(')')
+                    .next()
+                    .unwrap_or("")
+                    .trim_start()
+                    .starts_with('Z')
+            })
+        };
+        for _ in 0..100 {
+            if !alive() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("后台进程 {} 未被终止", pid);
+    }
+
+    #[tokio::test]
+    async fn test_run_applies_env_working_dir_and_stdin() {
+        let env = BTreeMap::from([("GREETING".to_string(), "hello".to_string())]);
+        let output = runner(1024)
+            .run(&CommandSpec {
+                command: "echo \"$GREETING $(pwd) $(cat)\"",
+                env: Some(&env),
+                working_dir: Some("/tmp"),
+                stdin: Some("from-stdin"),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert!(output.success(), "{:?}", output);
+        assert_eq!(output.stdout, "hello /tmp from-stdin\n");
+    }
+
+    #[test]
+    fn test_lookup_user() {
+        let root = lookup_user("root").unwrap();
+        assert_eq!((root.uid, root.gid), (0, 0));
+        assert_eq!(
+            lookup_user("no-such-user-rapidcron").unwrap_err().kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_limit_violations() {
+        let output = runner(1024)
+            .run(&CommandSpec {
+                command: "while :; do :; done",
+                limits: Some(ResourceLimits {
+                    cpu_seconds: Some(1),
+                    ..Default::default()
+                }),
+                timeout: Some(Duration::from_secs(30)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.limit_violation, Some(LimitViolation::Cpu));
+        assert_eq!(output.failure_reason().unwrap(), "超出 CPU 时间限制");
+
+        let output = runner(1024)
+            .run(&CommandSpec {
+                command: "f=$(mktemp); trap 'rm -f $f' EXIT; head -c 65536 /dev/zero > $f",
+                limits: Some(ResourceLimits {
+                    file_size_bytes: Some(1024),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(output.limit_violation, Some(LimitViolation::FileSize));
+    }
+
+    #[tokio::test]
+    async fn test_run_in_read_only_sandbox() {
+        let spec = CommandSpec {
+            command: "touch /tmp/rapidcron-sandbox && ! touch /rapidcron-sandbox 2>/dev/null",
+            sandbox: Some(SandboxOptions {
+                no_network: true,
+                read_only_root: true,
+            }),
+            ..Default::default()
+        };
+        // 隔离需要 root 与创建命名空间的权限，不满足时跳过
+        let output = match runner(1024).run(&spec).await {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return,
+            Err(e) => panic!("启动命令失败: {}", e),
+        };
+
+        assert!(output.success(), "{:?}", output);
+        assert!(!std::path::Path::new("/rapidcron-sandbox").exists());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_sandbox_covers_submounts() {
+        // 需要一个可写的独立挂载点，不存在时跳过
+        let mountinfo = std::fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+        let is_mount = mountinfo
+            .lines()
+            .any(|line| line.split_whitespace().nth(4) == Some("/dev/shm"));
+        let probe = std::path::Path::new("/dev/shm/rapidcron-sandbox-probe");
+        if !is_mount || std::fs::write(probe, b"").is_err() {
+            return;
+        }
+        let _ = std::fs::remove_file(probe);
+        let _ = std::fs::remove_file("/dev/shm/rapidcron-sandbox");
+
+        let spec = CommandSpec {
+            command: "! touch /dev/shm/rapidcron-sandbox 2>/dev/null",
+            sandbox: Some(SandboxOptions {
+                no_network: false,
+                read_only_root: true,
+            }),
+            ..Default::default()
+        };
+        let output = match runner(1024).run(&spec).await {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return,
+            Err(e) => panic!("启动命令失败: {}", e),
+        };
+
+        assert!(output.success(), "{:?}", output);
+        assert!(!std::path::Path::new("/dev/shm/rapidcron-sandbox").exists());
+    }
+}
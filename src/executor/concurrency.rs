@@ -0,0 +1,201 @@
+//! 执行器的并发控制：限制同时执行的实例总数以及各任务类型的实例数
+//!
+//! 先取得任务类型的许可再取得全局许可，等待类型许可的实例不会占用全局名额，
+//! 避免某一类型积压时阻塞其它类型的任务。消费消息时使用 [`TaskLimiter::acquire_within_type_limit`]，
+//! 任务类型已达上限的消息不在执行器中等待，由调用方转入延迟队列，不占用预取名额；
+//! 预取数因此只需覆盖全局并发上限与暂存未到期消息的名额（见 `ExecutorConfig::prefetch_count`）。
+
+use crate::config::ExecutorConfig;
+use crate::types::TaskType;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// 执行许可的分配器
+#[derive(Clone)]
+pub struct TaskLimiter {
+    global: Arc<Semaphore>,
+    per_type: Arc<HashMap<TaskType, Arc<Semaphore>>>,
+    active: Arc<AtomicU64>,
+}
+
+impl TaskLimiter {
+    /// `max_concurrent` 为 0 时按 1 处理，类型上限为 0 的条目被忽略
+    pub fn new(max_concurrent: usize, type_limits: &HashMap<TaskType, usize>) -> Self {
+        let per_type = type_limits
+            .iter()
+            .filter(|(_, limit)| **limit > 0)
+            .map(|(task_type, limit)| (task_type.clone(), Arc::new(Semaphore::new(*limit))))
+            .collect();
+        Self {
+            global: Arc::new(Semaphore::new(max_concurrent.max(1))),
+            per_type: Arc::new(per_type),
+            active: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn from_config(config: &ExecutorConfig) -> Self {
+        Self::new(config.max_concurrent_tasks, &config.task_type_limits)
+    }
+
+    /// 等待执行许可，`task_type` 为 `None`（任务不存在）时只受全局上限限制
+    pub async fn acquire(&self, task_type: Option<&TaskType>) -> TaskPermit {
+        let type_permit = match task_type.and_then(|task_type| self.per_type.get(task_type)) {
+            Some(semaphore) => Some(
+                Arc::clone(semaphore)
+                    .acquire_owned()
+                    .await
+                    .expect("任务类型信号量不会被关闭"),
+            ),
+            None => None,
+        };
+        let global_permit = Arc::clone(&self.global)
+            .acquire_owned()
+            .await
+            .expect("全局信号量不会被关闭");
+        self.active.fetch_add(1, Ordering::SeqCst);
+        TaskPermit {
+            _global: global_permit,
+            _type: type_permit,
+            active: Arc::clone(&self.active),
+        }
+    }
+
+    /// 与 [`TaskLimiter::acquire`] 相同，但任务类型已达上限时不等待，立即返回 `None`
+    pub async fn acquire_within_type_limit(
+        &self,
+        task_type: Option<&TaskType>,
+    ) -> Option<TaskPermit> {
+        let type_permit = match task_type.and_then(|task_type| self.per_type.get(task_type)) {
+            Some(semaphore) => Some(Arc::clone(semaphore).try_acquire_owned().ok()?),
+            None => None,
+        };
+        let global_permit = Arc::clone(&self.global)
+            .acquire_owned()
+            .await
+            .expect("全局信号量不会被关闭");
+        self.active.fetch_add(1, Ordering::SeqCst);
+        Some(TaskPermit {
+            _global: global_permit,
+            _type: type_permit,
+            active: Arc::clone(&self.active),
+        })
+    }
+
+    /// 正在执行的实例数
+    pub fn active(&self) -> u64 {
+        self.active.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for TaskLimiter {
+    /// 不限制并发
+    fn default() -> Self {
+        Self::new(Semaphore::MAX_PERMITS, &HashMap::new())
+    }
+}
+
+/// 执行许可，释放时归还名额
+pub struct TaskPermit {
+    _global: OwnedSemaphorePermit,
+    _type: Option<OwnedSemaphorePermit>,
+    active: Arc<AtomicU64>,
+}
+
+impl Drop for TaskPermit {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_global_and_per_type_limits() {
+        let limiter = TaskLimiter::new(2, &HashMap::from([(TaskType::Http, 1)]));
+
+        let http = limiter.acquire(Some(&TaskType::Http)).await;
+        assert_eq!(limiter.active(), 1);
+
+        // HTTP 已达类型上限，等待中的实例不占用全局名额
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(Some(&TaskType::Http)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        let command = limiter.acquire(Some(&TaskType::Command)).await;
+        assert_eq!(limiter.active(), 2);
+
+        // 全局名额已满
+        let blocked = tokio::time::timeout(Duration::from_millis(20), limiter.acquire(None)).await;
+        assert!(blocked.is_err());
+
+        drop(command);
+        assert_eq!(limiter.active(), 1);
+        drop(http);
+        let _second_http = waiting.await.unwrap();
+        assert_eq!(limiter.active(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_within_type_limit_does_not_wait() {
+        let limiter = TaskLimiter::new(4, &HashMap::from([(TaskType::Http, 1)]));
+
+        let http = limiter
+            .acquire_within_type_limit(Some(&TaskType::Http))
+            .await
+            .unwrap();
+        assert!(
+            limiter
+                .acquire_within_type_limit(Some(&TaskType::Http))
+                .await
+                .is_none()
+        );
+        // 未达上限的类型不受影响，被拒绝的请求不占用名额
+        assert!(
+            limiter
+                .acquire_within_type_limit(Some(&TaskType::Command))
+                .await
+                .is_some()
+        );
+        assert_eq!(limiter.active(), 1);
+
+        drop(http);
+        assert!(
+            limiter
+                .acquire_within_type_limit(Some(&TaskType::Http))
+                .await
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_type_limits_from_config() {
+        let config: ExecutorConfig = toml::from_str(
+            r#"
+            shell = "/bin/sh"
+            shell_args = ["-c"]
+            max_output_bytes = 1024
+            max_concurrent_tasks = 4
+
+            [task_type_limits]
+            http = 2
+            wasm = 0
+            "#,
+        )
+        .unwrap();
+        // 预取数为并发上限加暂存未到期消息的名额
+        assert_eq!(config.prefetch_count(), 4 + 16);
+
+        let limiter = TaskLimiter::from_config(&config);
+        assert_eq!(limiter.global.available_permits(), 4);
+        assert_eq!(limiter.per_type.len(), 1);
+        assert_eq!(limiter.per_type[&TaskType::Http].available_permits(), 2);
+    }
+}
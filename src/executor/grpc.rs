@@ -0,0 +1,567 @@
+//! gRPC 任务执行：按描述符将 JSON 请求编码为 protobuf，发起一元调用并按状态码判定结果
+//!
+//! 方法描述来自上传的描述符集，未指定描述符集时通过服务端反射（`grpc.reflection.v1`）获取。
+
+use crate::executor::runner::{RunContext, RunOutcome, TaskRunner};
+use crate::storage::mongo::MongoDataSource;
+use crate::types::{
+    ExecutionResult, GRPC_STATUS_CODES, GrpcSpec, TaskPayload, TaskStatus, split_grpc_method,
+    validate_grpc_spec,
+};
+use futures::future::BoxFuture;
+use prost_reflect::prost::Message;
+use prost_reflect::prost_types::FileDescriptorProto;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::{MetadataKey, MetadataValue};
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::{Code, Status};
+use tonic_reflection::pb::v1::ServerReflectionRequest;
+use tonic_reflection::pb::v1::server_reflection_client::ServerReflectionClient;
+use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
+use tracing::info;
+
+/// 建立连接的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 解析结果缓存的描述符集数上限
+const DESCRIPTOR_CACHE_SIZE: usize = 32;
+/// 通过反射获取的文件数上限
+const MAX_REFLECTED_FILES: usize = 256;
+
+/// 解析描述符集，返回其中定义的服务，在上传描述符集时调用
+pub fn parse_descriptor_set(bytes: &[u8]) -> Result<(DescriptorPool, Vec<String>), String> {
+    let pool = DescriptorPool::decode(bytes).map_err(|e| format!("无效的描述符集: {}", e))?;
+    let services: Vec<String> = pool
+        .services()
+        .map(|service| service.full_name().to_string())
+        .collect();
+    if services.is_empty() {
+        return Err("描述符集中没有服务定义".to_string());
+    }
+    Ok((pool, services))
+}
+
+/// 按方法描述编解码动态消息，`decode` 为待解码消息的类型
+#[derive(Clone)]
+struct DynamicCodec {
+    decode: MessageDescriptor,
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = Self;
+    type Decoder = Self;
+
+    fn encoder(&mut self) -> Self {
+        self.clone()
+    }
+
+    fn decoder(&mut self) -> Self {
+        self.clone()
+    }
+}
+
+impl Encoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: DynamicMessage, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(format!("编码消息失败: {}", e)))
+    }
+}
+
+impl Decoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<DynamicMessage>, Status> {
+        DynamicMessage::decode(self.decode.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::internal(format!("解码消息失败: {}", e)))
+    }
+}
+
+/// 错误及其来源，tonic 的传输错误本身只有“transport error”
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
+}
+
+fn code_name(code: Code) -> &'static str {
+    GRPC_STATUS_CODES
+        .get(code as usize)
+        .copied()
+        .unwrap_or("UNKNOWN")
+}
+
+async fn connect(endpoint: &str) -> Result<Channel, String> {
+    let mut builder = Endpoint::from_shared(endpoint.to_string())
+        .map_err(|e| format!("无效的 gRPC 地址 {}: {}", endpoint, e))?
+        .connect_timeout(CONNECT_TIMEOUT);
+    if endpoint.starts_with("https://") {
+        builder = builder
+            .tls_config(ClientTlsConfig::new().with_native_roots())
+            .map_err(|e| error_chain(&e))?;
+    }
+    builder.connect().await.map_err(|e| error_chain(&e))
+}
+
+/// 通过服务端反射获取定义 `service` 的文件及其依赖
+async fn reflect(channel: Channel, service: &str) -> Result<DescriptorPool, String> {
+    let mut client = ServerReflectionClient::new(channel);
+    let mut files: BTreeMap<String, FileDescriptorProto> = BTreeMap::new();
+    let mut requests = vec![MessageRequest::FileContainingSymbol(service.to_string())];
+    while !requests.is_empty() {
+        let stream =
+            futures::stream::iter(requests.into_iter().map(|request| ServerReflectionRequest {
+                host: String::new(),
+                message_request: Some(request),
+            }));
+        let mut responses = client
+            .server_reflection_info(stream)
+            .await
+            .map_err(|status| format!("服务端反射不可用: {}", status.message()))?
+            .into_inner();
+        while let Some(response) = responses
+            .message()
+            .await
+            .map_err(|status| format!("服务端反射失败: {}", status.message()))?
+        {
+            match response.message_response {
+                Some(MessageResponse::FileDescriptorResponse(response)) => {
+                    for bytes in response.file_descriptor_proto {
+                        let file = FileDescriptorProto::decode(bytes.as_slice())
+                            .map_err(|e| format!("解析反射返回的文件描述失败: {}", e))?;
+                        files.insert(file.name().to_string(), file);
+                    }
+                }
+                Some(MessageResponse::ErrorResponse(error)) => {
+                    return Err(format!("服务端反射失败: {}", error.error_message));
+                }
+                _ => {}
+            }
+        }
+        if files.len() > MAX_REFLECTED_FILES {
+            return Err("服务端反射返回的文件过多".to_string());
+        }
+        // 服务端可能只返回部分依赖，逐轮补齐缺少的文件
+        let missing: HashSet<&str> = files
+            .values()
+            .flat_map(|file| file.dependency.iter())
+            .map(String::as_str)
+            .filter(|name| !files.contains_key(*name))
+            .collect();
+        requests = missing
+            .into_iter()
+            .map(|name| MessageRequest::FileByFilename(name.to_string()))
+            .collect();
+    }
+    let mut pool = DescriptorPool::new();
+    pool.add_file_descriptor_protos(files.into_values())
+        .map_err(|e| format!("服务端反射返回的文件描述无效: {}", e))?;
+    Ok(pool)
+}
+
+fn find_method(pool: &DescriptorPool, method: &str) -> Result<MethodDescriptor, String> {
+    let (service, name) = split_grpc_method(method)?;
+    let method = pool
+        .get_service_by_name(service)
+        .ok_or_else(|| format!("服务不存在: {}", service))?
+        .methods()
+        .find(|method| method.name() == name)
+        .ok_or_else(|| format!("方法不存在: {}/{}", service, name))?;
+    if method.is_client_streaming() || method.is_server_streaming() {
+        return Err(format!("仅支持一元调用: {}", method.full_name()));
+    }
+    Ok(method)
+}
+
+/// 将调用结果转换为执行结果：状态码在期望列表中时视为成功
+fn grpc_outcome(
+    result: Result<DynamicMessage, Status>,
+    expected_codes: Option<&[String]>,
+    timeout: Option<Duration>,
+) -> RunOutcome {
+    let (code, output, message) = match result {
+        Ok(response) => match serde_json::to_string(&response) {
+            Ok(json) => (Code::Ok, Some(json), None),
+            Err(e) => (Code::Ok, None, Some(format!("响应转换为 JSON 失败: {}", e))),
+        },
+        Err(status) => (status.code(), None, Some(status.message().to_string())),
+    };
+    let name = code_name(code);
+    let expected = match expected_codes {
+        Some(codes) => codes.iter().any(|expected| expected == name),
+        None => code == Code::Ok,
+    };
+    if expected {
+        return RunOutcome {
+            result: ExecutionResult {
+                output,
+                error: message,
+                exit_code: Some(code as i32),
+                failed_assertion: None,
+            },
+            status: TaskStatus::Success,
+            summary: Some(format!("gRPC {} 成功", name)),
+            error: None,
+        };
+    }
+    if code == Code::DeadlineExceeded {
+        return RunOutcome::timed_out(timeout);
+    }
+    let error = match message.filter(|message| !message.is_empty()) {
+        Some(message) => format!("gRPC 状态 {}: {}", name, message),
+        None => format!("gRPC 状态 {}", name),
+    };
+    RunOutcome {
+        result: ExecutionResult {
+            output,
+            error: Some(error.clone()),
+            exit_code: Some(code as i32),
+            failed_assertion: None,
+        },
+        status: TaskStatus::Failed,
+        summary: Some(format!("gRPC {} 失败", name)),
+        error: Some(error),
+    }
+}
+
+/// gRPC 任务运行器
+#[derive(Clone, Default)]
+pub struct GrpcRunner {
+    db: Option<Arc<MongoDataSource>>,
+    /// 按描述符集内容的 SHA-256 缓存解析结果
+    descriptor_sets: Arc<Mutex<HashMap<String, DescriptorPool>>>,
+}
+
+impl GrpcRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 读取上传到数据库中的描述符集
+    pub fn with_db(mut self, db: Arc<MongoDataSource>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    async fn load_descriptor_set(&self, name: &str) -> Result<DescriptorPool, String> {
+        let db = self
+            .db
+            .as_ref()
+            .ok_or_else(|| "执行器未连接数据库，无法读取描述符集".to_string())?;
+        let set = db
+            .get_grpc_descriptor_set_by_name(name)
+            .await
+            .map_err(|e| format!("读取描述符集 {} 失败: {}", name, e))?
+            .ok_or_else(|| format!("描述符集不存在: {}", name))?;
+        let sha256 = hex::encode(Sha256::digest(&set.data.bytes));
+        if let Some(pool) = self.descriptor_sets.lock().unwrap().get(&sha256) {
+            return Ok(pool.clone());
+        }
+        let (pool, _) = parse_descriptor_set(&set.data.bytes)?;
+        let mut descriptor_sets = self.descriptor_sets.lock().unwrap();
+        if descriptor_sets.len() >= DESCRIPTOR_CACHE_SIZE {
+            descriptor_sets.clear();
+        }
+        descriptor_sets.insert(sha256, pool.clone());
+        Ok(pool)
+    }
+
+    /// 连接服务并按描述符集或服务端反射解析方法，再发起调用
+    async fn invoke(&self, spec: &GrpcSpec, timeout: Option<Duration>) -> RunOutcome {
+        let channel = match connect(&spec.endpoint).await {
+            Ok(channel) => channel,
+            Err(e) => return RunOutcome::failed("连接 gRPC 服务失败", e),
+        };
+        let method = match &spec.descriptor_set {
+            Some(name) => self.load_descriptor_set(name).await,
+            None => match split_grpc_method(&spec.method) {
+                Ok((service, _)) => reflect(channel.clone(), service).await,
+                Err(e) => Err(e),
+            },
+        }
+        .and_then(|pool| find_method(&pool, &spec.method));
+        match method {
+            Ok(method) => call(channel, spec, method, timeout).await,
+            Err(e) => RunOutcome::failed("获取方法描述失败", e),
+        }
+    }
+
+    async fn run(&self, ctx: RunContext<'_>) -> RunOutcome {
+        let TaskPayload::Grpc { grpc, .. } = ctx.payload else {
+            return RunOutcome::payload_mismatch(&ctx.task.task_type);
+        };
+        if let Err(e) = validate_grpc_spec(grpc) {
+            return RunOutcome::failed("gRPC 任务校验失败", e);
+        }
+
+        info!(
+            "执行 gRPC 任务: {} {}",
+            ctx.secret_mask.mask(&grpc.endpoint),
+            grpc.method
+        );
+        // 截止时间覆盖连接、获取方法描述与调用的全过程
+        match ctx.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.invoke(grpc, ctx.timeout))
+                .await
+                .unwrap_or_else(|_| RunOutcome::timed_out(ctx.timeout)),
+            None => self.invoke(grpc, None).await,
+        }
+    }
+}
+
+/// 编码请求并发起一元调用
+async fn call(
+    channel: Channel,
+    spec: &GrpcSpec,
+    method: MethodDescriptor,
+    timeout: Option<Duration>,
+) -> RunOutcome {
+    let body = spec
+        .request
+        .clone()
+        .unwrap_or_else(|| serde_json::Value::Object(Default::default()));
+    let message = match DynamicMessage::deserialize(method.input(), body) {
+        Ok(message) => message,
+        Err(e) => return RunOutcome::failed("编码 gRPC 请求失败", e),
+    };
+    let mut request = tonic::Request::new(message);
+    for (key, value) in spec.metadata.iter().flatten() {
+        let (Ok(key), Ok(value)) = (
+            MetadataKey::from_bytes(key.as_bytes()),
+            MetadataValue::try_from(value.as_str()),
+        ) else {
+            return RunOutcome::failed("无效的请求元数据", key);
+        };
+        request.metadata_mut().insert(key, value);
+    }
+    if let Some(timeout) = timeout {
+        request.set_timeout(timeout);
+    }
+    let path = match PathAndQuery::try_from(format!(
+        "/{}/{}",
+        method.parent_service().full_name(),
+        method.name()
+    )) {
+        Ok(path) => path,
+        Err(e) => return RunOutcome::failed("无效的方法名", e),
+    };
+
+    let mut client = tonic::client::Grpc::new(channel);
+    if let Err(e) = client.ready().await {
+        return RunOutcome::failed("连接 gRPC 服务失败", error_chain(&e));
+    }
+    let codec = DynamicCodec {
+        decode: method.output(),
+    };
+    let result = client
+        .unary(request, path, codec)
+        .await
+        .map(tonic::Response::into_inner);
+    grpc_outcome(result, spec.expected_codes.as_deref(), timeout)
+}
+
+impl TaskRunner for GrpcRunner {
+    fn execute<'a>(&'a self, ctx: RunContext<'a>) -> BoxFuture<'a, RunOutcome> {
+        Box::pin(self.run(ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_reflect::Value;
+    use prost_reflect::prost_types::field_descriptor_proto::{Label, Type};
+    use prost_reflect::prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorSet, MethodDescriptorProto,
+        ServiceDescriptorProto,
+    };
+    use std::convert::Infallible;
+    use tonic::codegen::{BoxFuture as ServiceFuture, Service, http};
+    use tonic::server::{NamedService, UnaryService};
+
+    /// `test.v1.Greeter/SayHello(HelloRequest{name}) -> HelloReply{greeting}`
+    fn descriptor_set() -> Vec<u8> {
+        let message = |name: &str, field: &str| DescriptorProto {
+            name: Some(name.to_string()),
+            field: vec![FieldDescriptorProto {
+                name: Some(field.to_string()),
+                json_name: Some(field.to_string()),
+                number: Some(1),
+                label: Some(Label::Optional as i32),
+                r#type: Some(Type::String as i32),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let file = FileDescriptorProto {
+            name: Some("greeter.proto".to_string()),
+            package: Some("test.v1".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![
+                message("HelloRequest", "name"),
+                message("HelloReply", "greeting"),
+            ],
+            service: vec![ServiceDescriptorProto {
+                name: Some("Greeter".to_string()),
+                method: vec![MethodDescriptorProto {
+                    name: Some("SayHello".to_string()),
+                    input_type: Some(".test.v1.HelloRequest".to_string()),
+                    output_type: Some(".test.v1.HelloReply".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        FileDescriptorSet { file: vec![file] }.encode_to_vec()
+    }
+
+    /// 以 `x-greeting` 元数据问候请求中的 name，name 为空时返回 NOT_FOUND
+    #[derive(Clone)]
+    struct Greeter {
+        method: MethodDescriptor,
+    }
+
+    impl NamedService for Greeter {
+        const NAME: &'static str = "test.v1.Greeter";
+    }
+
+    impl UnaryService<DynamicMessage> for Greeter {
+        type Response = DynamicMessage;
+        type Future = ServiceFuture<tonic::Response<DynamicMessage>, Status>;
+
+        fn call(&mut self, request: tonic::Request<DynamicMessage>) -> Self::Future {
+            let output = self.method.output();
+            Box::pin(async move {
+                let greeting = request
+                    .metadata()
+                    .get("x-greeting")
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or("hello")
+                    .to_string();
+                let name = request.get_ref().get_field_by_name("name").unwrap();
+                let name = name.as_str().unwrap_or_default();
+                if name.is_empty() {
+                    return Err(Status::not_found("name is required"));
+                }
+                let mut reply = DynamicMessage::new(output);
+                reply
+                    .set_field_by_name("greeting", Value::String(format!("{} {}", greeting, name)));
+                Ok(tonic::Response::new(reply))
+            })
+        }
+    }
+
+    impl<B> Service<http::Request<B>> for Greeter
+    where
+        B: tonic::codegen::Body + Send + 'static,
+        B::Error: Into<tonic::codegen::StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Infallible;
+        type Future = ServiceFuture<Self::Response, Infallible>;
+
+        fn poll_ready(
+            &mut self,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<(), Infallible>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<B>) -> Self::Future {
+            let service = self.clone();
+            Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(DynamicCodec {
+                    decode: service.method.input(),
+                });
+                Ok(grpc.unary(service, request).await)
+            })
+        }
+    }
+
+    async fn serve(method: MethodDescriptor) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let incoming =
+            tonic::transport::server::TcpIncoming::from_listener(listener, true, None).unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(Greeter { method })
+                .serve_with_incoming(incoming),
+        );
+        endpoint
+    }
+
+    fn spec(endpoint: &str, request: serde_json::Value) -> GrpcSpec {
+        GrpcSpec {
+            endpoint: endpoint.to_string(),
+            method: "test.v1.Greeter/SayHello".to_string(),
+            descriptor_set: Some("greeter".to_string()),
+            request: Some(request),
+            metadata: Some([("x-greeting".to_string(), "hi".to_string())].into()),
+            expected_codes: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_descriptor_set() {
+        let (pool, services) = parse_descriptor_set(&descriptor_set()).unwrap();
+        assert_eq!(services, vec!["test.v1.Greeter".to_string()]);
+        assert!(find_method(&pool, "/test.v1.Greeter/SayHello").is_ok());
+        assert!(find_method(&pool, "test.v1.Greeter/Missing").is_err());
+        assert!(parse_descriptor_set(b"\xff\xff").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unary_call_and_status_mapping() {
+        let (pool, _) = parse_descriptor_set(&descriptor_set()).unwrap();
+        let method = find_method(&pool, "test.v1.Greeter/SayHello").unwrap();
+        let endpoint = serve(method.clone()).await;
+        let timeout = Some(Duration::from_secs(5));
+
+        let spec_ok = spec(&endpoint, serde_json::json!({ "name": "cron" }));
+        let channel = connect(&endpoint).await.unwrap();
+        let outcome = call(channel.clone(), &spec_ok, method.clone(), timeout).await;
+        assert_eq!(outcome.status, TaskStatus::Success);
+        assert_eq!(outcome.result.exit_code, Some(0));
+        assert_eq!(
+            outcome.result.output.as_deref(),
+            Some(r#"{"greeting":"hi cron"}"#)
+        );
+
+        let mut spec_missing = spec(&endpoint, serde_json::json!({}));
+        let outcome = call(channel.clone(), &spec_missing, method.clone(), timeout).await;
+        assert_eq!(outcome.status, TaskStatus::Failed);
+        assert_eq!(outcome.result.exit_code, Some(Code::NotFound as i32));
+        assert_eq!(
+            outcome.error.as_deref(),
+            Some("gRPC 状态 NOT_FOUND: name is required")
+        );
+
+        // 期望的状态码视为成功
+        spec_missing.expected_codes = Some(vec!["OK".to_string(), "NOT_FOUND".to_string()]);
+        let outcome = call(channel.clone(), &spec_missing, method.clone(), timeout).await;
+        assert_eq!(outcome.status, TaskStatus::Success);
+
+        // 请求与方法的输入类型不匹配
+        let spec_invalid = spec(&endpoint, serde_json::json!({ "unknown": 1 }));
+        let outcome = call(channel, &spec_invalid, method, timeout).await;
+        assert_eq!(outcome.summary.as_deref(), Some("编码 gRPC 请求失败"));
+    }
+}
@@ -0,0 +1,154 @@
+//! HTTP 任务执行：发送请求并按响应断言判定结果
+
+use crate::executor::assertion::{self, HttpResponse};
+use crate::executor::runner::{RunContext, RunOutcome, TaskRunner};
+use crate::types::{AssertionFailure, ExecutionResult, TaskPayload, TaskStatus};
+use futures::future::BoxFuture;
+use tracing::info;
+
+/// HTTP 任务运行器
+#[derive(Debug, Clone, Default)]
+pub struct HttpRunner {
+    client: reqwest::Client,
+}
+
+impl HttpRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 使用自定义的客户端（代理、证书等）
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    async fn run(&self, ctx: RunContext<'_>) -> RunOutcome {
+        let TaskPayload::Http {
+            url,
+            method,
+            headers,
+            body,
+            assertions,
+            ..
+        } = ctx.payload
+        else {
+            return RunOutcome::payload_mismatch(&ctx.task.task_type);
+        };
+        let method = method.as_deref().unwrap_or("GET");
+
+        info!("执行 HTTP 任务: {} {}", method, ctx.secret_mask.mask(url));
+
+        let mut request_builder = match method.to_uppercase().as_str() {
+            "GET" => self.client.get(url),
+            "POST" => self.client.post(url),
+            "PUT" => self.client.put(url),
+            "DELETE" => self.client.delete(url),
+            _ => self.client.get(url),
+        };
+        if let Some(obj) = headers.as_ref().and_then(|headers| headers.as_object()) {
+            for (key, value) in obj {
+                if let Some(value_str) = value.as_str() {
+                    request_builder = request_builder.header(key, value_str);
+                }
+            }
+        }
+        if let Some(body) = body {
+            request_builder = request_builder.body(body.clone());
+        }
+        // 超时覆盖连接、发送请求与读取响应体的全过程
+        if let Some(timeout) = ctx.timeout {
+            request_builder = request_builder.timeout(timeout);
+        }
+
+        let started = std::time::Instant::now();
+        match request_builder.send().await {
+            Ok(response) => {
+                let status = response.status();
+                let response_headers = response.headers().clone();
+                match response.text().await {
+                    Err(e) if e.is_timeout() => RunOutcome::timed_out(ctx.timeout),
+                    text => {
+                        let latency = started.elapsed();
+                        let (body, output) = match text {
+                            Ok(text) => (text.clone(), Some(text)),
+                            Err(e) => (String::new(), Some(format!("读取响应失败: {}", e))),
+                        };
+                        let verdict = assertion::evaluate(
+                            assertions.as_ref(),
+                            &HttpResponse {
+                                status: status.as_u16(),
+                                headers: &response_headers,
+                                body: &body,
+                                latency,
+                            },
+                        );
+                        http_outcome(status, output, verdict, assertions.is_some())
+                    }
+                }
+            }
+            Err(e) if e.is_timeout() => RunOutcome::timed_out(ctx.timeout),
+            Err(e) => {
+                let mut outcome = RunOutcome::failed("HTTP 请求失败", e);
+                outcome.result.output = Some("请求失败".to_string());
+                outcome
+            }
+        }
+    }
+}
+
+impl TaskRunner for HttpRunner {
+    fn execute<'a>(&'a self, ctx: RunContext<'a>) -> BoxFuture<'a, RunOutcome> {
+        Box::pin(self.run(ctx))
+    }
+}
+
+/// 将 HTTP 响应与断言结果转换为执行结果
+///
+/// 未配置断言时只检查 2xx，不记录未通过的断言
+fn http_outcome(
+    status: reqwest::StatusCode,
+    output: Option<String>,
+    verdict: Result<(), AssertionFailure>,
+    has_assertions: bool,
+) -> RunOutcome {
+    let exit_code = Some(status.as_u16() as i32);
+    match verdict {
+        Ok(()) => RunOutcome {
+            result: ExecutionResult {
+                output,
+                error: None,
+                exit_code: Some(0),
+                failed_assertion: None,
+            },
+            status: TaskStatus::Success,
+            summary: Some(format!("HTTP {} 成功", status)),
+            error: None,
+        },
+        Err(failure) if !has_assertions => RunOutcome {
+            result: ExecutionResult {
+                output,
+                error: Some(failure.message.clone()),
+                exit_code,
+                failed_assertion: None,
+            },
+            status: TaskStatus::Failed,
+            summary: Some(format!("HTTP {} 失败", status)),
+            error: Some(failure.message),
+        },
+        Err(failure) => {
+            let message = format!("断言失败: {}", failure.message);
+            RunOutcome {
+                result: ExecutionResult {
+                    output,
+                    error: Some(message.clone()),
+                    exit_code,
+                    failed_assertion: Some(failure),
+                },
+                status: TaskStatus::Failed,
+                summary: Some(format!("HTTP {} 断言失败", status)),
+                error: Some(message),
+            }
+        }
+    }
+}
@@ -1,5 +1,17 @@
+pub mod assertion;
+pub mod command;
+pub mod concurrency;
+pub mod grpc;
+pub mod http;
 pub mod retry;
+pub mod runner;
+pub mod sandbox;
+pub mod script;
 pub mod task_queue;
+pub mod template;
+pub mod timer;
+pub mod wasm;
+pub mod worker;
 
 pub use retry::RetryManager;
 pub use task_queue::TaskQueue;
@@ -1,8 +1,10 @@
+use crate::clock::{Clock, SharedClock, SystemClock};
 use crate::error::{Error, Result};
 use crate::executor::TaskQueue;
+use crate::scheduler::pause::PauseSet;
 use crate::storage::mongo::MongoDataSource;
 use crate::types::{ExecutionResult, Task, TaskInstance, TaskStatus};
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use mongodb::bson::{doc, oid::ObjectId};
 use std::sync::Arc;
 use tracing::{debug, info, warn};
@@ -33,6 +35,35 @@ impl Default for RetryStrategy {
     }
 }
 
+impl RetryStrategy {
+    /// 第 `retry_count` 次重试前的延迟（秒）
+    pub fn delay_seconds(&self, retry_count: i32) -> i64 {
+        match *self {
+            Self::Fixed { delay_seconds } => delay_seconds,
+            Self::Exponential {
+                base_delay_seconds,
+                max_delay_seconds,
+            } => {
+                let delay = base_delay_seconds * 2_i64.pow(retry_count as u32);
+                delay.min(max_delay_seconds)
+            }
+            Self::Linear {
+                initial_delay_seconds,
+                increment_seconds,
+            } => initial_delay_seconds + increment_seconds * retry_count as i64,
+        }
+    }
+}
+
+/// 按时钟的当前时间与重试策略计算实例的下一次计划执行时间
+pub fn next_retry_time(
+    clock: &dyn Clock,
+    strategy: &RetryStrategy,
+    instance: &TaskInstance,
+) -> DateTime<Utc> {
+    clock.now() + Duration::seconds(strategy.delay_seconds(instance.retry_count))
+}
+
 /// 重试配置
 #[derive(Debug, Clone, Default)]
 pub struct RetryConfig {
@@ -45,6 +76,7 @@ pub struct RetryManager {
     db: Arc<MongoDataSource>,
     task_queue: Arc<TaskQueue>,
     config: crate::config::RetryConfig,
+    clock: SharedClock,
 }
 
 impl RetryManager {
@@ -58,9 +90,16 @@ impl RetryManager {
             db,
             task_queue,
             config,
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// 使用指定的时钟计算重试时间
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// 判断是否应该重试
     pub fn should_retry(
         &self,
@@ -98,22 +137,7 @@ impl RetryManager {
         instance: &TaskInstance,
         config: &RetryConfig,
     ) -> i64 {
-        let retry_count = instance.retry_count;
-
-        match config.strategy {
-            RetryStrategy::Fixed { delay_seconds } => delay_seconds,
-            RetryStrategy::Exponential {
-                base_delay_seconds,
-                max_delay_seconds,
-            } => {
-                let delay = base_delay_seconds * 2_i64.pow(retry_count as u32);
-                delay.min(max_delay_seconds)
-            }
-            RetryStrategy::Linear {
-                initial_delay_seconds,
-                increment_seconds,
-            } => initial_delay_seconds + increment_seconds * retry_count as i64,
-        }
+        config.strategy.delay_seconds(instance.retry_count)
     }
 
     /// 执行重试
@@ -129,8 +153,13 @@ impl RetryManager {
             .map_err(|e| Error::Database(format!("查询任务实例失败: {}", e)))?
             .ok_or_else(|| Error::Execution("任务实例不存在".to_string()))?;
 
-        if instance.status != TaskStatus::Failed {
-            return Err(Error::Execution("只能重试失败的任务".to_string()));
+        if !matches!(
+            instance.status,
+            TaskStatus::Failed | TaskStatus::TimedOut | TaskStatus::Interrupted
+        ) {
+            return Err(Error::Execution(
+                "只能重试失败、超时或被中断的任务".to_string(),
+            ));
         }
 
         let task = self
@@ -140,6 +169,15 @@ impl RetryManager {
             .map_err(|e| Error::Database(format!("查询任务失败: {}", e)))?
             .ok_or_else(|| Error::Execution("任务不存在".to_string()))?;
 
+        // 任务处于暂停范围内时不产生新的重试
+        if PauseSet::load(&self.db, self.clock.now())
+            .await?
+            .is_paused(&task)
+        {
+            debug!("[RetryManager] 任务 {} 处于暂停中，跳过重试", task.name);
+            return Ok(false);
+        }
+
         let result = instance
             .result
             .as_ref()
@@ -169,7 +207,7 @@ impl RetryManager {
         }
 
         let delay_seconds = self.calculate_retry_delay(&task, &instance, &retry_config);
-        let retry_time = Utc::now() + Duration::seconds(delay_seconds);
+        let retry_time = next_retry_time(self.clock.as_ref(), &retry_config.strategy, &instance);
 
         let update = doc! {
             "$set": {
@@ -195,10 +233,11 @@ impl RetryManager {
             scheduled_time: retry_time.timestamp(),
             retry_count: retry_count + 1,
             triggered_by: instance.triggered_by,
+            priority: task.effective_priority() as u8,
         };
 
         self.task_queue
-            .publish_task(task_msg)
+            .publish_task(task_msg, self.clock.now())
             .await
             .map_err(|e| Error::Execution(format!("发布任务到队列失败: {}", e)))?;
 
@@ -212,7 +251,7 @@ impl RetryManager {
         Ok(true)
     }
 
-    /// 批量重试失败的任务
+    /// 批量重试失败、超时或被中断的任务
     pub async fn retry_failed_tasks(
         &self,
         task_id: Option<ObjectId>,
@@ -220,12 +259,12 @@ impl RetryManager {
     ) -> Result<usize> {
         let filter = if let Some(tid) = task_id {
             doc! {
-                "status": "failed",
+                "status": { "$in": ["failed", "timed_out", "interrupted"] },
                 "task_id": tid
             }
         } else {
             doc! {
-                "status": "failed"
+                "status": { "$in": ["failed", "timed_out", "interrupted"] }
             }
         };
 
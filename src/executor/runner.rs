@@ -0,0 +1,199 @@
+//! 任务运行器：执行器按任务类型选择 [`TaskRunner`] 执行实例
+//!
+//! 领取实例、渲染载荷、解密密钥、写回实例状态与执行日志由 [`crate::executor::worker::Worker`] 负责，
+//! 运行器只按已渲染的载荷执行一次并返回 [`RunOutcome`]。自定义执行器可以向 [`RunnerRegistry`]
+//! 注册自己的运行器，替换内置实现或支持新的任务类型。
+
+use crate::config::ExecutorConfig;
+use crate::executor::command::CommandRunner;
+use crate::executor::grpc::GrpcRunner;
+use crate::executor::http::HttpRunner;
+use crate::executor::script::ScriptRunner;
+use crate::executor::wasm::WasmRunner;
+use crate::secrets::SecretMask;
+use crate::storage::mongo::MongoDataSource;
+use crate::types::{ExecutionResult, Task, TaskPayload, TaskStatus, TaskType};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 执行一个实例所需的信息
+pub struct RunContext<'a> {
+    pub task: &'a Task,
+    /// 已渲染模板并替换密钥的载荷
+    pub payload: &'a TaskPayload,
+    /// 生效的超时时间，`None` 表示不限制
+    pub timeout: Option<Duration>,
+    /// 记录日志时用于遮盖载荷中的密钥明文
+    pub secret_mask: &'a SecretMask,
+}
+
+/// 一次执行的结果
+#[derive(Debug, Clone)]
+pub struct RunOutcome {
+    pub result: ExecutionResult,
+    pub status: TaskStatus,
+    /// 写入执行日志的输出摘要
+    pub summary: Option<String>,
+    /// 写入执行日志的错误信息
+    pub error: Option<String>,
+}
+
+impl RunOutcome {
+    /// 执行前或执行过程中出错：摘要为 `summary`，错误信息为 `summary: error`
+    pub fn failed(summary: &str, error: impl std::fmt::Display) -> Self {
+        let message = format!("{}: {}", summary, error);
+        Self {
+            result: ExecutionResult {
+                output: None,
+                error: Some(message.clone()),
+                exit_code: Some(1),
+                failed_assertion: None,
+            },
+            status: TaskStatus::Failed,
+            summary: Some(summary.to_string()),
+            error: Some(message),
+        }
+    }
+
+    /// 执行超时
+    pub fn timed_out(timeout: Option<Duration>) -> Self {
+        let message = match timeout {
+            Some(timeout) => format!("执行超时（{} 秒）", timeout.as_secs()),
+            None => "执行超时".to_string(),
+        };
+        Self {
+            result: ExecutionResult {
+                output: None,
+                error: Some(message.clone()),
+                exit_code: None,
+                failed_assertion: None,
+            },
+            status: TaskStatus::TimedOut,
+            summary: Some("执行超时".to_string()),
+            error: Some(message),
+        }
+    }
+
+    /// 载荷与运行器不匹配
+    pub fn payload_mismatch(task_type: &TaskType) -> Self {
+        Self::failed("载荷与任务类型不匹配", format!("{:?}", task_type))
+    }
+}
+
+/// 某种任务类型的执行逻辑
+///
+/// 运行器自行处理超时（[`RunContext::timeout`]），超时时返回 [`RunOutcome::timed_out`]。
+pub trait TaskRunner: Send + Sync {
+    fn execute<'a>(&'a self, ctx: RunContext<'a>) -> BoxFuture<'a, RunOutcome>;
+}
+
+/// 任务类型到运行器的映射
+#[derive(Clone, Default)]
+pub struct RunnerRegistry {
+    runners: HashMap<TaskType, Arc<dyn TaskRunner>>,
+}
+
+impl RunnerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册内置的 HTTP、gRPC、命令、脚本与 wasm 运行器，`db` 用于读取上传的描述符集与 wasm 模块
+    pub fn with_defaults(config: &ExecutorConfig, db: Arc<MongoDataSource>) -> Self {
+        let mut registry = Self::new();
+        registry.register(TaskType::Http, HttpRunner::new());
+        registry.register(TaskType::Grpc, GrpcRunner::new().with_db(Arc::clone(&db)));
+        registry.register(TaskType::Command, CommandRunner::new(config));
+        registry.register(TaskType::Script, ScriptRunner::new(config));
+        registry.register(TaskType::Wasm, WasmRunner::new(config).with_db(db));
+        registry
+    }
+
+    /// 注册运行器，已注册的同类型运行器会被替换
+    pub fn register(
+        &mut self,
+        task_type: TaskType,
+        runner: impl TaskRunner + 'static,
+    ) -> &mut Self {
+        self.runners.insert(task_type, Arc::new(runner));
+        self
+    }
+
+    pub fn get(&self, task_type: &TaskType) -> Option<&Arc<dyn TaskRunner>> {
+        self.runners.get(task_type)
+    }
+
+    /// 按任务类型选择运行器执行
+    pub async fn execute(&self, ctx: RunContext<'_>) -> RunOutcome {
+        match self.get(&ctx.task.task_type) {
+            Some(runner) => runner.execute(ctx).await,
+            None => RunOutcome::failed("没有对应的任务运行器", format!("{:?}", ctx.task.task_type)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoRunner;
+
+    impl TaskRunner for EchoRunner {
+        fn execute<'a>(&'a self, ctx: RunContext<'a>) -> BoxFuture<'a, RunOutcome> {
+            Box::pin(async move {
+                RunOutcome {
+                    result: ExecutionResult {
+                        output: Some(ctx.task.name.clone()),
+                        error: None,
+                        exit_code: Some(0),
+                        failed_assertion: None,
+                    },
+                    status: TaskStatus::Success,
+                    summary: None,
+                    error: None,
+                }
+            })
+        }
+    }
+
+    fn make_task(task_type: TaskType) -> Task {
+        Task {
+            task_type,
+            ..Task::for_test("echo")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_dispatches_by_task_type() {
+        let mut registry = RunnerRegistry::new();
+        registry.register(TaskType::Http, EchoRunner);
+        let mask = SecretMask::default();
+
+        let task = make_task(TaskType::Http);
+        let outcome = registry
+            .execute(RunContext {
+                task: &task,
+                payload: &task.payload,
+                timeout: None,
+                secret_mask: &mask,
+            })
+            .await;
+        assert_eq!(outcome.status, TaskStatus::Success);
+        assert_eq!(outcome.result.output.as_deref(), Some("echo"));
+
+        // 未注册的任务类型直接失败
+        let task = make_task(TaskType::Command);
+        let outcome = registry
+            .execute(RunContext {
+                task: &task,
+                payload: &task.payload,
+                timeout: None,
+                secret_mask: &mask,
+            })
+            .await;
+        assert_eq!(outcome.status, TaskStatus::Failed);
+        assert_eq!(outcome.summary.as_deref(), Some("没有对应的任务运行器"));
+    }
+}
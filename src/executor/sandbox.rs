@@ -0,0 +1,265 @@
+//! 命令任务的资源限制与隔离
+//!
+//! 资源限制通过 rlimit 施加在命令进程上；隔离通过 Linux 命名空间实现：
+//! 独立的网络命名空间使命令无法访问网络，独立的挂载命名空间中根文件系统及其下的所有挂载点
+//! 被重新挂载为只读，/tmp 挂载为独立的 tmpfs。隔离需要执行器以 root 运行，只读挂载依赖
+//! `mount_setattr`（Linux 5.12 及以上）。
+//!
+//! `RLIMIT_NPROC` 按真实 uid 计数，包括该用户的所有进程与线程，因此只在切换运行用户时施加；
+//! 以执行器自身的用户运行时该限制会把执行器及其他任务的进程一并计入，不予设置。
+
+use crate::types::{ResourceLimits, SandboxOptions};
+use tokio::process::Command;
+
+/// 超出资源限制导致的失败
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitViolation {
+    Cpu,
+    Memory,
+    OpenFiles,
+    FileSize,
+    Processes,
+}
+
+impl std::fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            LimitViolation::Cpu => "超出 CPU 时间限制",
+            LimitViolation::Memory => "超出内存限制",
+            LimitViolation::OpenFiles => "超出打开文件数限制",
+            LimitViolation::FileSize => "超出输出文件大小限制",
+            LimitViolation::Processes => "超出进程数限制",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// 内存分配失败时常见的错误信息（小写）
+const MEMORY_ERRORS: [&str; 6] = [
+    "cannot allocate memory",
+    "out of memory",
+    "memory exhausted",
+    "memoryerror",
+    "memory allocation of",
+    "bad_alloc",
+];
+
+/// 根据终止信号、退出码与错误输出判断失败是否由资源限制引起
+///
+/// CPU 时间与文件大小超限由内核发送 SIGXCPU、SIGXFSZ；shell 的子进程被信号终止时，
+/// shell 以 128 + 信号值退出。其余限制只能通过错误输出中的系统错误信息识别；
+/// SIGSEGV、SIGABRT 等信号本身不足以说明内存超限，只有错误输出中带有分配失败的信息时才归为内存超限。
+pub fn detect_limit_violation(
+    limits: &ResourceLimits,
+    signal: Option<i32>,
+    exit_code: Option<i32>,
+    stderr: &str,
+) -> Option<LimitViolation> {
+    let signal = signal.or(exit_code.filter(|code| *code > 128).map(|code| code - 128));
+    let stderr = stderr.to_lowercase();
+
+    if limits.cpu_seconds.is_some() && signal == Some(libc::SIGXCPU) {
+        return Some(LimitViolation::Cpu);
+    }
+    if limits.file_size_bytes.is_some() && signal == Some(libc::SIGXFSZ) {
+        return Some(LimitViolation::FileSize);
+    }
+    if limits.open_files.is_some() && stderr.contains("too many open files") {
+        return Some(LimitViolation::OpenFiles);
+    }
+    if limits.max_processes.is_some()
+        && stderr.contains("fork")
+        && stderr.contains("resource temporarily unavailable")
+    {
+        return Some(LimitViolation::Processes);
+    }
+    let out_of_memory = MEMORY_ERRORS.iter().any(|message| stderr.contains(message));
+    if limits.memory_bytes.is_some() && out_of_memory {
+        return Some(LimitViolation::Memory);
+    }
+    None
+}
+
+/// 为命令设置资源限制与隔离
+///
+/// 启用隔离时，切换运行用户必须在进入命名空间之后进行，由 `drop_to` 传入目标 uid/gid，
+/// 调用方不应再通过 [`Command::uid`] 设置用户。
+pub fn apply(
+    command: &mut Command,
+    limits: Option<ResourceLimits>,
+    sandbox: Option<SandboxOptions>,
+    drop_to: Option<(u32, u32)>,
+) {
+    if limits.is_none() && sandbox.is_none() && drop_to.is_none() {
+        return;
+    }
+    // SAFETY: 闭包在 fork 之后、exec 之前执行，只调用异步信号安全的系统调用，不分配内存
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(sandbox) = sandbox {
+                enter_sandbox(sandbox)?;
+            }
+            if let Some((uid, gid)) = drop_to {
+                drop_privileges(uid, gid)?;
+            }
+            if let Some(limits) = limits {
+                apply_limits(limits, drop_to.is_some())?;
+            }
+            Ok(())
+        });
+    }
+}
+
+fn check(code: libc::c_int) -> std::io::Result<()> {
+    if code == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+fn enter_sandbox(sandbox: SandboxOptions) -> std::io::Result<()> {
+    let mut flags = 0;
+    if sandbox.no_network {
+        flags |= libc::CLONE_NEWNET;
+    }
+    if sandbox.read_only_root {
+        flags |= libc::CLONE_NEWNS;
+    }
+    if flags == 0 {
+        return Ok(());
+    }
+    // SAFETY: 参数均为静态字符串或空指针
+    unsafe {
+        check(libc::unshare(flags))?;
+        if sandbox.read_only_root {
+            // 挂载变更不传播回执行器所在的命名空间
+            check(libc::mount(
+                std::ptr::null(),
+                c"/".as_ptr(),
+                std::ptr::null(),
+                libc::MS_REC | libc::MS_PRIVATE,
+                std::ptr::null(),
+            ))?;
+            // MS_REMOUNT 忽略 MS_REC，只有 mount_setattr 能一次将所有子挂载点设为只读
+            let attr = libc::mount_attr {
+                attr_set: libc::MOUNT_ATTR_RDONLY,
+                attr_clr: 0,
+                propagation: 0,
+                userns_fd: 0,
+            };
+            let code = libc::syscall(
+                libc::SYS_mount_setattr,
+                libc::AT_FDCWD,
+                c"/".as_ptr(),
+                libc::AT_RECURSIVE,
+                &attr as *const libc::mount_attr,
+                std::mem::size_of::<libc::mount_attr>(),
+            );
+            check(code as libc::c_int)?;
+            check(libc::mount(
+                c"tmpfs".as_ptr(),
+                c"/tmp".as_ptr(),
+                c"tmpfs".as_ptr(),
+                libc::MS_NOSUID | libc::MS_NODEV,
+                std::ptr::null(),
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+fn drop_privileges(uid: u32, gid: u32) -> std::io::Result<()> {
+    // SAFETY: setgroups 的空列表不需要有效指针
+    unsafe {
+        check(libc::setgroups(0, std::ptr::null()))?;
+        check(libc::setgid(gid))?;
+        check(libc::setuid(uid))?;
+    }
+    Ok(())
+}
+
+fn apply_limits(limits: ResourceLimits, switched_user: bool) -> std::io::Result<()> {
+    // CPU 时间的硬限制比软限制多 1 秒，使进程先收到 SIGXCPU 而不是直接被 SIGKILL 终止
+    let resources = [
+        (libc::RLIMIT_CPU, limits.cpu_seconds, 1),
+        (libc::RLIMIT_AS, limits.memory_bytes, 0),
+        (libc::RLIMIT_NOFILE, limits.open_files, 0),
+        (libc::RLIMIT_FSIZE, limits.file_size_bytes, 0),
+        (
+            libc::RLIMIT_NPROC,
+            limits.max_processes.filter(|_| switched_user),
+            0,
+        ),
+    ];
+    for (resource, value, hard_extra) in resources {
+        if let Some(value) = value {
+            let limit = libc::rlimit {
+                rlim_cur: value as libc::rlim_t,
+                rlim_max: value.saturating_add(hard_extra) as libc::rlim_t,
+            };
+            // SAFETY: limit 在调用期间有效
+            check(unsafe { libc::setrlimit(resource, &limit) })?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_limit_violation() {
+        let limits = ResourceLimits {
+            cpu_seconds: Some(1),
+            file_size_bytes: Some(1024),
+            open_files: Some(16),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            detect_limit_violation(&limits, Some(libc::SIGXCPU), None, ""),
+            Some(LimitViolation::Cpu)
+        );
+        assert_eq!(
+            detect_limit_violation(&limits, None, Some(128 + libc::SIGXFSZ), ""),
+            Some(LimitViolation::FileSize)
+        );
+        assert_eq!(
+            detect_limit_violation(&limits, None, Some(1), "open: Too many open files"),
+            Some(LimitViolation::OpenFiles)
+        );
+        // 段错误等信号不能说明内存超限
+        let memory = ResourceLimits {
+            memory_bytes: Some(64 * 1024 * 1024),
+            ..Default::default()
+        };
+        assert_eq!(
+            detect_limit_violation(&memory, Some(libc::SIGSEGV), None, ""),
+            None
+        );
+        assert_eq!(
+            detect_limit_violation(&memory, None, Some(128 + libc::SIGABRT), ""),
+            None
+        );
+        assert_eq!(
+            detect_limit_violation(
+                &memory,
+                Some(libc::SIGABRT),
+                None,
+                "memory allocation of 1048576 bytes failed"
+            ),
+            Some(LimitViolation::Memory)
+        );
+        // 未设置的限制不参与判断
+        assert_eq!(
+            detect_limit_violation(&limits, Some(libc::SIGSEGV), None, "Cannot allocate memory"),
+            None
+        );
+        assert_eq!(
+            detect_limit_violation(&ResourceLimits::default(), Some(libc::SIGXCPU), None, ""),
+            None
+        );
+    }
+}
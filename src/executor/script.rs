@@ -0,0 +1,247 @@
+//! 脚本任务执行：将脚本写入私有临时文件后由解释器执行
+//!
+//! 临时文件权限为 0600，设置运行用户时属主改为该用户；执行结束（包括超时与取消）后删除。
+//! 超时、资源限制与隔离与命令任务相同，由 [`CommandRunner`] 处理。
+
+use crate::config::ExecutorConfig;
+use crate::executor::command::{CommandRunner, CommandSpec, command_outcome, lookup_user};
+use crate::executor::runner::{RunContext, RunOutcome, TaskRunner};
+use crate::types::{TaskPayload, validate_script};
+use futures::future::BoxFuture;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::{info, warn};
+
+/// 脚本任务运行器
+#[derive(Debug, Clone)]
+pub struct ScriptRunner {
+    commands: CommandRunner,
+    script_dir: PathBuf,
+}
+
+/// 执行结束时删除的脚本文件
+struct ScriptFile {
+    path: PathBuf,
+}
+
+impl ScriptFile {
+    /// 以 0600 权限创建新文件并写入脚本，`owner` 为文件属主的 uid/gid
+    fn create(dir: &Path, script: &str, owner: Option<(u32, u32)>) -> std::io::Result<Self> {
+        let path = dir.join(format!("rapidcron-script-{}", uuid::Uuid::new_v4()));
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)?;
+        // 先构造守卫，写入失败时同样删除文件
+        let script_file = Self { path };
+        file.write_all(script.as_bytes())?;
+        file.sync_all()?;
+        if let Some((uid, gid)) = owner {
+            std::os::unix::fs::chown(&script_file.path, Some(uid), Some(gid))?;
+        }
+        Ok(script_file)
+    }
+}
+
+impl Drop for ScriptFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("删除脚本文件 {} 失败: {}", self.path.display(), e);
+        }
+    }
+}
+
+impl ScriptRunner {
+    pub fn new(config: &ExecutorConfig) -> Self {
+        Self {
+            commands: CommandRunner::new(config),
+            script_dir: config
+                .script_dir
+                .as_ref()
+                .map(PathBuf::from)
+                .unwrap_or_else(std::env::temp_dir),
+        }
+    }
+
+    async fn run(&self, ctx: RunContext<'_>) -> RunOutcome {
+        let TaskPayload::Script {
+            interpreter,
+            script,
+            env,
+            working_dir,
+            run_as_user,
+            stdin,
+            limits,
+            sandbox,
+            ..
+        } = ctx.payload
+        else {
+            return RunOutcome::payload_mismatch(&ctx.task.task_type);
+        };
+        // 解释器在创建任务时已校验，执行前再次确认，防止绕过 API 写入的任务
+        if let Err(e) = validate_script(interpreter, script) {
+            return RunOutcome::failed("脚本校验失败", e);
+        }
+        // 只读根文件系统隔离会在 /tmp 挂载新的 tmpfs，其下的脚本文件对进程不可见
+        if sandbox.is_some_and(|sandbox| sandbox.read_only_root)
+            && self.script_dir.starts_with("/tmp")
+        {
+            return RunOutcome::failed(
+                "脚本目录不可用",
+                "启用只读根文件系统隔离时 executor.script_dir 不能位于 /tmp 下",
+            );
+        }
+
+        if let Some(name) = run_as_user.as_deref()
+            && let Err(e) = self.commands.check_run_as_user(name)
+        {
+            return RunOutcome::failed("启动脚本失败", e);
+        }
+        let owner = match run_as_user.as_deref().map(lookup_user).transpose() {
+            Ok(user) => user.map(|user| (user.uid, user.gid)),
+            Err(e) => return RunOutcome::failed("启动脚本失败", e),
+        };
+        let script_file = match ScriptFile::create(&self.script_dir, script, owner) {
+            Ok(script_file) => script_file,
+            Err(e) => return RunOutcome::failed("写入脚本文件失败", e),
+        };
+
+        info!(
+            "执行脚本任务: {} {}",
+            interpreter,
+            script_file.path.display()
+        );
+        let mut command = Command::new(interpreter);
+        command.arg(&script_file.path);
+        let spec = CommandSpec {
+            command: "",
+            env: env.as_ref(),
+            working_dir: working_dir.as_deref(),
+            run_as_user: run_as_user.as_deref(),
+            stdin: stdin.as_deref(),
+            limits: *limits,
+            sandbox: *sandbox,
+            timeout: ctx.timeout,
+        };
+        let outcome = match self.commands.run_program(command, &spec).await {
+            Ok(output) => command_outcome(output),
+            Err(e) => RunOutcome::failed("启动脚本失败", e),
+        };
+        drop(script_file);
+        outcome
+    }
+}
+
+impl TaskRunner for ScriptRunner {
+    fn execute<'a>(&'a self, ctx: RunContext<'a>) -> BoxFuture<'a, RunOutcome> {
+        Box::pin(self.run(ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::SecretMask;
+    use crate::types::{Task, TaskStatus, TaskType};
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn script_task(interpreter: &str, script: &str) -> Task {
+        Task {
+            id: None,
+            name: "script".to_string(),
+            description: None,
+            dependency_ids: Vec::new(),
+            tags: Vec::new(),
+            task_type: TaskType::Script,
+            schedule: "0 * * * * *".to_string(),
+            enabled: true,
+            payload: TaskPayload::Script {
+                interpreter: interpreter.to_string(),
+                script: script.to_string(),
+                timeout_seconds: None,
+                env: Some([("GREETING".to_string(), "hello".to_string())].into()),
+                working_dir: None,
+                run_as_user: None,
+                stdin: None,
+                limits: None,
+                sandbox: None,
+            },
+            timeout_seconds: None,
+            max_retries: None,
+            priority: None,
+            deadline_seconds: None,
+            start_sla_seconds: None,
+            run_sla_seconds: None,
+            max_concurrent_instances: None,
+            rate_limit: None,
+            parameters: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+        }
+    }
+
+    fn runner(dir: &Path) -> ScriptRunner {
+        ScriptRunner::new(&ExecutorConfig {
+            script_dir: Some(dir.to_string_lossy().into_owned()),
+            ..Default::default()
+        })
+    }
+
+    async fn run(runner: &ScriptRunner, task: &Task, timeout: Option<Duration>) -> RunOutcome {
+        runner
+            .execute(RunContext {
+                task,
+                payload: &task.payload,
+                timeout,
+                secret_mask: &SecretMask::default(),
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_run_script_and_remove_file() {
+        let dir = std::env::temp_dir().join(format!("rapidcron-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        let runner = runner(&dir);
+
+        let task = script_task(
+            "sh",
+            "set -e\necho \"$GREETING from $(basename \"$0\" | cut -c1-16)\"\nexit 0\n",
+        );
+        let outcome = run(&runner, &task, None).await;
+        assert_eq!(outcome.status, TaskStatus::Success);
+        assert_eq!(
+            outcome.result.output.as_deref(),
+            Some("hello from rapidcron-script\n")
+        );
+
+        let task = script_task("sh", "echo failing >&2\nexit 4\n");
+        let outcome = run(&runner, &task, None).await;
+        assert_eq!(outcome.status, TaskStatus::Failed);
+        assert_eq!(outcome.result.exit_code, Some(4));
+
+        let task = script_task("sh", "sleep 5\n");
+        let outcome = run(&runner, &task, Some(Duration::from_millis(200))).await;
+        assert_eq!(outcome.status, TaskStatus::TimedOut);
+
+        // 执行结束后不残留脚本文件
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reject_interpreter_outside_allowlist() {
+        let dir = std::env::temp_dir();
+        let task = script_task("/usr/bin/env", "echo hi\n");
+        let outcome = run(&runner(&dir), &task, None).await;
+        assert_eq!(outcome.status, TaskStatus::Failed);
+        assert_eq!(outcome.summary.as_deref(), Some("脚本校验失败"));
+    }
+}
@@ -1,9 +1,11 @@
 use crate::error::{Error, Result};
-use crate::types::TriggeredBy;
+use crate::types::{MAX_TASK_PRIORITY, TriggeredBy};
+use chrono::{DateTime, Utc};
 use lapin::{
     BasicProperties, Channel, Connection, ConnectionProperties,
     options::{BasicPublishOptions, QueueDeclareOptions},
-    types::FieldTable,
+    protocol::{AMQPErrorKind, AMQPSoftError},
+    types::{AMQPValue, FieldTable},
 };
 use mongodb::bson::oid::ObjectId;
 use std::sync::Arc;
@@ -16,8 +18,151 @@ pub struct TaskMessage {
     pub task_id: ObjectId,
     pub task_name: String,
     pub scheduled_time: i64,
+    #[serde(default)]
     pub retry_count: i32,
     pub triggered_by: TriggeredBy,
+    /// 消息优先级（0-9），对应任务的 `priority`
+    #[serde(default)]
+    pub priority: u8,
+}
+
+/// 任务队列的声明参数（分发端与执行端必须一致）
+pub fn queue_arguments() -> FieldTable {
+    let mut arguments = FieldTable::default();
+    arguments.insert(
+        "x-max-priority".into(),
+        AMQPValue::LongInt(MAX_TASK_PRIORITY),
+    );
+    arguments
+}
+
+/// 延迟队列的时长档位（秒）
+///
+/// 未到计划执行时间的消息发布到不超过剩余时间的最大档位，在延迟队列中过期后由 RabbitMQ
+/// 转回任务队列，剩余时间仍超过 1 秒时再进入下一档。执行器因此只持有即将到期的消息，
+/// 不会因预取大量未到期消息而阻塞其它执行器。
+pub const DELAY_BUCKETS_SECS: [i64; 10] = [1, 2, 4, 8, 16, 32, 64, 128, 256, 512];
+
+/// 延迟队列名称
+pub fn delay_queue_name(queue_name: &str, delay_secs: i64) -> String {
+    format!("{}.delay.{}s", queue_name, delay_secs)
+}
+
+/// 距计划执行时间还剩 `remaining_secs` 秒的消息应进入的延迟档位，不足 1 秒时返回 `None`
+pub fn delay_bucket(remaining_secs: i64) -> Option<i64> {
+    DELAY_BUCKETS_SECS
+        .iter()
+        .rev()
+        .copied()
+        .find(|bucket| *bucket <= remaining_secs)
+}
+
+/// 距计划执行时间的剩余整秒数（向下取整），`now` 的毫秒部分计入剩余时间
+pub fn remaining_secs(scheduled_time: i64, now: DateTime<Utc>) -> i64 {
+    (scheduled_time.saturating_mul(1000) - now.timestamp_millis()).div_euclid(1000)
+}
+
+fn delay_queue_arguments(queue_name: &str, delay_secs: i64) -> FieldTable {
+    let mut arguments = FieldTable::default();
+    arguments.insert(
+        "x-message-ttl".into(),
+        AMQPValue::LongLongInt(delay_secs * 1000),
+    );
+    arguments.insert(
+        "x-dead-letter-exchange".into(),
+        AMQPValue::LongString("".into()),
+    );
+    arguments.insert(
+        "x-dead-letter-routing-key".into(),
+        AMQPValue::LongString(queue_name.into()),
+    );
+    arguments
+}
+
+/// 声明任务队列及其延迟队列
+///
+/// 旧版本声明的队列没有 `x-max-priority`，RabbitMQ 会以 PRECONDITION_FAILED 拒绝重新声明，
+/// 此时返回说明迁移方式的错误（见 README 的“升级说明”）
+pub async fn declare_task_queue(channel: &Channel, queue_name: &str) -> Result<()> {
+    channel
+        .queue_declare(
+            queue_name,
+            QueueDeclareOptions {
+                durable: true,
+                ..Default::default()
+            },
+            queue_arguments(),
+        )
+        .await
+        .map_err(|e| match &e {
+            lapin::Error::ProtocolError(amqp_error)
+                if *amqp_error.kind() == AMQPErrorKind::Soft(AMQPSoftError::PRECONDITIONFAILED) =>
+            {
+                Error::MessageQueue(format!(
+                    "队列 {} 已存在且声明参数不同（缺少 x-max-priority），\
+                     请等待队列排空后删除该队列，或配置新的 queue_name: {}",
+                    queue_name, e
+                ))
+            }
+            _ => Error::MessageQueue(format!("声明队列失败: {}", e)),
+        })?;
+
+    for delay_secs in DELAY_BUCKETS_SECS {
+        channel
+            .queue_declare(
+                &delay_queue_name(queue_name, delay_secs),
+                QueueDeclareOptions {
+                    durable: true,
+                    ..Default::default()
+                },
+                delay_queue_arguments(queue_name, delay_secs),
+            )
+            .await
+            .map_err(|e| Error::MessageQueue(format!("声明延迟队列失败: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// 发布任务消息：已到或即将到计划执行时间的消息直接进入任务队列，其余进入延迟队列
+pub async fn publish_task_message(
+    channel: &Channel,
+    queue_name: &str,
+    task_msg: &TaskMessage,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let routing_key = match delay_bucket(remaining_secs(task_msg.scheduled_time, now)) {
+        Some(delay_secs) => delay_queue_name(queue_name, delay_secs),
+        None => queue_name.to_string(),
+    };
+    publish_to(channel, &routing_key, task_msg).await
+}
+
+/// 将任务消息发布到指定档位的延迟队列，过期后回到任务队列
+pub async fn publish_delayed(
+    channel: &Channel,
+    queue_name: &str,
+    task_msg: &TaskMessage,
+    delay_secs: i64,
+) -> Result<()> {
+    publish_to(channel, &delay_queue_name(queue_name, delay_secs), task_msg).await
+}
+
+async fn publish_to(channel: &Channel, routing_key: &str, task_msg: &TaskMessage) -> Result<()> {
+    let payload = serde_json::to_vec(task_msg).map_err(Error::Serialization)?;
+
+    channel
+        .basic_publish(
+            "",
+            routing_key,
+            BasicPublishOptions::default(),
+            &payload,
+            BasicProperties::default().with_priority(task_msg.priority),
+        )
+        .await
+        .map_err(|e| Error::MessageQueue(format!("发布任务失败: {}", e)))?;
+
+    debug!("发布任务到队列 {}: {}", routing_key, task_msg.task_name);
+    Ok(())
 }
 
 /// 任务队列
@@ -39,17 +184,7 @@ impl TaskQueue {
             .await
             .map_err(|e| Error::MessageQueue(format!("创建 channel 失败: {}", e)))?;
 
-        channel
-            .queue_declare(
-                &queue_name,
-                QueueDeclareOptions {
-                    durable: true,
-                    ..Default::default()
-                },
-                FieldTable::default(),
-            )
-            .await
-            .map_err(|e| Error::MessageQueue(format!("声明队列失败: {}", e)))?;
+        declare_task_queue(&channel, &queue_name).await?;
 
         info!("成功连接到 RabbitMQ: {}", queue_name);
 
@@ -60,23 +195,61 @@ impl TaskQueue {
         })
     }
 
-    /// 发布任务到队列
-    pub async fn publish_task(&self, task_msg: TaskMessage) -> Result<()> {
-        let payload = serde_json::to_vec(&task_msg).map_err(Error::Serialization)?;
-
-        self.channel
-            .basic_publish(
-                "",
-                &self.queue_name,
-                BasicPublishOptions::default(),
-                &payload,
-                BasicProperties::default(),
-            )
-            .await
-            .map_err(|e| Error::MessageQueue(format!("发布任务失败: {}", e)))?;
+    /// 发布任务到队列，`now` 用于计算距调度时间的延迟
+    pub async fn publish_task(&self, task_msg: TaskMessage, now: DateTime<Utc>) -> Result<()> {
+        publish_task_message(&self.channel, &self.queue_name, &task_msg, now).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_delay_bucket_picks_largest_bucket_within_remaining_time() {
+        assert_eq!(delay_bucket(-5), None);
+        assert_eq!(delay_bucket(0), None);
+        assert_eq!(delay_bucket(1), Some(1));
+        assert_eq!(delay_bucket(3), Some(2));
+        assert_eq!(delay_bucket(45), Some(32));
+        assert_eq!(delay_bucket(3600), Some(512));
 
-        debug!("发布任务到队列: {}", task_msg.task_name);
+        // 逐档转回任务队列后，剩余时间不足 1 秒时才会直接进入任务队列
+        let mut remaining = 45;
+        let mut hops = Vec::new();
+        while let Some(delay_secs) = delay_bucket(remaining) {
+            hops.push(delay_secs);
+            remaining -= delay_secs;
+        }
+        assert_eq!(hops, vec![32, 8, 4, 1]);
+        assert_eq!(remaining, 0);
+    }
+
+    #[test]
+    fn test_remaining_secs_rounds_down_partial_seconds() {
+        let now = Utc.timestamp_opt(1_700_000_000, 500_000_000).unwrap();
+        // 剩余 0.5 秒的消息不再进入 1 秒档位，避免晚于计划时间到达
+        assert_eq!(remaining_secs(1_700_000_001, now), 0);
+        assert_eq!(remaining_secs(1_700_000_010, now), 9);
+        assert_eq!(remaining_secs(1_700_000_000, now), -1);
+    }
 
-        Ok(())
+    #[test]
+    fn test_delay_queue_dead_letters_to_task_queue() {
+        let arguments = delay_queue_arguments("rapidcron_tasks", 8);
+        let inner = arguments.inner();
+        assert_eq!(
+            inner.get("x-message-ttl"),
+            Some(&AMQPValue::LongLongInt(8000))
+        );
+        assert_eq!(
+            inner.get("x-dead-letter-routing-key"),
+            Some(&AMQPValue::LongString("rapidcron_tasks".into()))
+        );
+        assert_eq!(
+            delay_queue_name("rapidcron_tasks", 8),
+            "rapidcron_tasks.delay.8s"
+        );
     }
 }
@@ -0,0 +1,673 @@
+//! 任务载荷模板：执行前将 `{{ 变量 }}` 替换为运行时的值
+//!
+//! 支持的变量：
+//! - `task_name`、`instance_id`、`retry_count`
+//! - `scheduled_time`：计划执行时间（本地时区，RFC3339），可用 `{{ scheduled_time | %Y%m%d }}` 指定格式
+//! - `scheduled_date`：计划执行日期（`%Y-%m-%d`）
+//! - `scheduled_timestamp`：计划执行时间的 Unix 时间戳（秒）
+//! - `params.<name>`：任务参数
+//!
+//! `${secret:名称}` 引用密钥存储中的密钥，只在执行器中替换为明文。
+//! 变量与密钥引用在同一遍中替换，参数值中的 `${secret:...}` 不会被再次解析。
+//! 命令任务的 `command` 由 shell 解释，其中的参数值按 shell 单引号规则转义后插入。
+
+use crate::types::{GrpcSpec, Task, TaskParameter, TaskPayload, WasmSpec, resolve_parameters};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, Utc};
+use mongodb::bson::oid::ObjectId;
+use std::collections::{BTreeMap, BTreeSet};
+
+const SECRET_PREFIX: &str = "${secret:";
+
+const BUILTIN_VARIABLES: [&str; 6] = [
+    "task_name",
+    "instance_id",
+    "retry_count",
+    "scheduled_time",
+    "scheduled_date",
+    "scheduled_timestamp",
+];
+
+/// 模板渲染上下文
+pub struct TemplateContext<'a> {
+    pub task_name: &'a str,
+    pub instance_id: ObjectId,
+    pub scheduled_time: DateTime<Utc>,
+    pub retry_count: i32,
+    pub params: &'a BTreeMap<String, String>,
+    /// 已解密的密钥值
+    pub secrets: &'a BTreeMap<String, String>,
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Variable {
+        name: &'a str,
+        format: Option<&'a str>,
+    },
+    Secret(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = [rest.find("{{"), rest.find(SECRET_PREFIX)]
+        .into_iter()
+        .flatten()
+        .min()
+    {
+        segments.push(Segment::Text(&rest[..start]));
+        if let Some(after) = rest[start..].strip_prefix(SECRET_PREFIX) {
+            let end = after
+                .find('}')
+                .ok_or_else(|| format!("密钥引用缺少结束的 }}: {}", template))?;
+            segments.push(Segment::Secret(&after[..end]));
+            rest = &after[end + 1..];
+            continue;
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| format!("模板缺少结束的 }}}}: {}", template))?;
+        let expr = &after[..end];
+        let (name, format) = match expr.split_once('|') {
+            Some((name, format)) => (name.trim(), Some(format.trim())),
+            None => (expr.trim(), None),
+        };
+        if name.is_empty() {
+            return Err(format!("模板变量不能为空: {}", template));
+        }
+        segments.push(Segment::Variable { name, format });
+        rest = &after[end + 2..];
+    }
+    segments.push(Segment::Text(rest));
+    Ok(segments)
+}
+
+fn validate_time_format(format: &str) -> Result<(), String> {
+    if format.is_empty() || StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("无效的时间格式: {}", format));
+    }
+    Ok(())
+}
+
+fn check_variable(
+    name: &str,
+    format: Option<&str>,
+    parameters: &[TaskParameter],
+) -> Result<(), String> {
+    if let Some(param) = name.strip_prefix("params.") {
+        if !parameters.iter().any(|p| p.name == param) {
+            return Err(format!("模板引用了未定义的参数: {}", param));
+        }
+    } else if !BUILTIN_VARIABLES.contains(&name) {
+        return Err(format!("未知的模板变量: {}", name));
+    }
+    match format {
+        Some(format) if name == "scheduled_time" => validate_time_format(format),
+        Some(_) => Err(format!("变量 {} 不支持格式", name)),
+        None => Ok(()),
+    }
+}
+
+/// 校验模板语法以及引用的变量
+pub fn validate_template(template: &str, parameters: &[TaskParameter]) -> Result<(), String> {
+    for segment in parse(template)? {
+        match segment {
+            Segment::Text(_) => {}
+            Segment::Variable { name, format } => check_variable(name, format, parameters)?,
+            Segment::Secret(name) => crate::secrets::validate_secret_name(name)?,
+        }
+    }
+    Ok(())
+}
+
+/// 按 shell 单引号规则转义，只含安全字符的非空值原样返回
+pub fn shell_quote(value: &str) -> String {
+    let safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c));
+    if safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// 渲染模板
+pub fn render(template: &str, ctx: &TemplateContext<'_>) -> Result<String, String> {
+    render_segments(template, ctx, false)
+}
+
+/// 渲染由 shell 解释的模板，参数值经 [`shell_quote`] 转义
+pub fn render_shell(template: &str, ctx: &TemplateContext<'_>) -> Result<String, String> {
+    render_segments(template, ctx, true)
+}
+
+fn render_segments(
+    template: &str,
+    ctx: &TemplateContext<'_>,
+    quote_params: bool,
+) -> Result<String, String> {
+    let scheduled_local = ctx.scheduled_time.with_timezone(&Local);
+    let mut output = String::with_capacity(template.len());
+    for segment in parse(template)? {
+        match segment {
+            Segment::Text(text) => output.push_str(text),
+            Segment::Secret(name) => {
+                let value = ctx
+                    .secrets
+                    .get(name)
+                    .ok_or_else(|| format!("缺少密钥: {}", name))?;
+                output.push_str(value);
+            }
+            Segment::Variable { name, format } => {
+                if let Some(param) = name.strip_prefix("params.") {
+                    let value = ctx
+                        .params
+                        .get(param)
+                        .ok_or_else(|| format!("缺少参数: {}", param))?;
+                    if quote_params {
+                        output.push_str(&shell_quote(value));
+                    } else {
+                        output.push_str(value);
+                    }
+                    continue;
+                }
+                let value = match (name, format) {
+                    ("task_name", None) => ctx.task_name.to_string(),
+                    ("instance_id", None) => ctx.instance_id.to_hex(),
+                    ("retry_count", None) => ctx.retry_count.to_string(),
+                    ("scheduled_time", None) => scheduled_local.to_rfc3339(),
+                    ("scheduled_time", Some(format)) => {
+                        validate_time_format(format)?;
+                        scheduled_local.format(format).to_string()
+                    }
+                    ("scheduled_date", None) => scheduled_local.format("%Y-%m-%d").to_string(),
+                    ("scheduled_timestamp", None) => ctx.scheduled_time.timestamp().to_string(),
+                    _ => return Err(format!("无法渲染模板变量: {}", name)),
+                };
+                output.push_str(&value);
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// JSON 值中的全部字符串
+fn json_templates<'a>(value: &'a serde_json::Value, templates: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(template) => templates.push(template),
+        serde_json::Value::Array(items) => {
+            items
+                .iter()
+                .for_each(|item| json_templates(item, templates));
+        }
+        serde_json::Value::Object(map) => {
+            map.values()
+                .for_each(|item| json_templates(item, templates));
+        }
+        _ => {}
+    }
+}
+
+/// 载荷中所有支持模板的字段
+fn payload_templates(payload: &TaskPayload) -> Vec<&str> {
+    match payload {
+        TaskPayload::Command {
+            command,
+            env,
+            stdin,
+            ..
+        } => {
+            let mut templates = vec![command.as_str()];
+            if let Some(env) = env {
+                templates.extend(env.values().map(String::as_str));
+            }
+            if let Some(stdin) = stdin {
+                templates.push(stdin.as_str());
+            }
+            templates
+        }
+        // 脚本内容不参与渲染，参数与密钥通过环境变量或标准输入传入
+        TaskPayload::Script { env, stdin, .. } => {
+            let mut templates = Vec::new();
+            if let Some(env) = env {
+                templates.extend(env.values().map(String::as_str));
+            }
+            if let Some(stdin) = stdin {
+                templates.push(stdin.as_str());
+            }
+            templates
+        }
+        // 模块本身不参与渲染
+        TaskPayload::Wasm {
+            wasm, env, stdin, ..
+        } => {
+            let mut templates: Vec<&str> = wasm.args.iter().map(String::as_str).collect();
+            if let Some(env) = env {
+                templates.extend(env.values().map(String::as_str));
+            }
+            if let Some(stdin) = stdin {
+                templates.push(stdin.as_str());
+            }
+            templates
+        }
+        TaskPayload::Grpc { grpc, .. } => {
+            let mut templates = Vec::new();
+            if let Some(request) = &grpc.request {
+                json_templates(request, &mut templates);
+            }
+            if let Some(metadata) = &grpc.metadata {
+                templates.extend(metadata.values().map(String::as_str));
+            }
+            templates
+        }
+        TaskPayload::Http {
+            url, headers, body, ..
+        } => {
+            let mut templates = vec![url.as_str()];
+            if let Some(headers) = headers.as_ref().and_then(|h| h.as_object()) {
+                templates.extend(headers.values().filter_map(|value| value.as_str()));
+            }
+            if let Some(body) = body {
+                templates.push(body.as_str());
+            }
+            templates
+        }
+    }
+}
+
+/// 载荷引用的全部密钥名称
+pub fn secret_references(payload: &TaskPayload) -> Result<BTreeSet<String>, String> {
+    let mut names = BTreeSet::new();
+    for template in payload_templates(payload) {
+        for segment in parse(template)? {
+            if let Segment::Secret(name) = segment {
+                names.insert(name.to_string());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// 校验载荷中的模板
+pub fn validate_payload(payload: &TaskPayload, parameters: &[TaskParameter]) -> Result<(), String> {
+    payload_templates(payload)
+        .into_iter()
+        .try_for_each(|template| validate_template(template, parameters))
+}
+
+fn render_json(
+    value: &serde_json::Value,
+    ctx: &TemplateContext<'_>,
+) -> Result<serde_json::Value, String> {
+    Ok(match value {
+        serde_json::Value::String(template) => serde_json::Value::String(render(template, ctx)?),
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items
+                .iter()
+                .map(|item| render_json(item, ctx))
+                .collect::<Result<_, _>>()?,
+        ),
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(key, item)| Ok((key.clone(), render_json(item, ctx)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn render_env(
+    env: &Option<BTreeMap<String, String>>,
+    ctx: &TemplateContext<'_>,
+) -> Result<Option<BTreeMap<String, String>>, String> {
+    env.as_ref()
+        .map(|env| {
+            env.iter()
+                .map(|(key, value)| Ok((key.clone(), render(value, ctx)?)))
+                .collect()
+        })
+        .transpose()
+}
+
+/// 渲染载荷中的命令、模块参数、环境变量、标准输入、URL、请求头、请求体与 gRPC 请求及元数据
+pub fn render_payload(
+    payload: &TaskPayload,
+    ctx: &TemplateContext<'_>,
+) -> Result<TaskPayload, String> {
+    Ok(match payload {
+        TaskPayload::Command {
+            command,
+            timeout_seconds,
+            env,
+            working_dir,
+            run_as_user,
+            stdin,
+            limits,
+            sandbox,
+        } => TaskPayload::Command {
+            command: render_shell(command, ctx)?,
+            timeout_seconds: *timeout_seconds,
+            env: render_env(env, ctx)?,
+            working_dir: working_dir.clone(),
+            run_as_user: run_as_user.clone(),
+            stdin: stdin
+                .as_deref()
+                .map(|stdin| render(stdin, ctx))
+                .transpose()?,
+            limits: *limits,
+            sandbox: *sandbox,
+        },
+        TaskPayload::Script {
+            interpreter,
+            script,
+            timeout_seconds,
+            env,
+            working_dir,
+            run_as_user,
+            stdin,
+            limits,
+            sandbox,
+        } => TaskPayload::Script {
+            interpreter: interpreter.clone(),
+            script: script.clone(),
+            timeout_seconds: *timeout_seconds,
+            env: render_env(env, ctx)?,
+            working_dir: working_dir.clone(),
+            run_as_user: run_as_user.clone(),
+            stdin: stdin
+                .as_deref()
+                .map(|stdin| render(stdin, ctx))
+                .transpose()?,
+            limits: *limits,
+            sandbox: *sandbox,
+        },
+        TaskPayload::Wasm {
+            wasm,
+            timeout_seconds,
+            env,
+            stdin,
+        } => TaskPayload::Wasm {
+            wasm: WasmSpec {
+                args: wasm
+                    .args
+                    .iter()
+                    .map(|arg| render(arg, ctx))
+                    .collect::<Result<_, _>>()?,
+                ..wasm.clone()
+            },
+            timeout_seconds: *timeout_seconds,
+            env: render_env(env, ctx)?,
+            stdin: stdin
+                .as_deref()
+                .map(|stdin| render(stdin, ctx))
+                .transpose()?,
+        },
+        TaskPayload::Grpc {
+            grpc,
+            timeout_seconds,
+        } => TaskPayload::Grpc {
+            grpc: GrpcSpec {
+                request: grpc
+                    .request
+                    .as_ref()
+                    .map(|request| render_json(request, ctx))
+                    .transpose()?,
+                metadata: render_env(&grpc.metadata, ctx)?,
+                ..grpc.clone()
+            },
+            timeout_seconds: *timeout_seconds,
+        },
+        TaskPayload::Http {
+            url,
+            method,
+            headers,
+            body,
+            timeout_seconds,
+            assertions,
+        } => {
+            let headers = match headers {
+                Some(serde_json::Value::Object(map)) => {
+                    let mut rendered = serde_json::Map::new();
+                    for (key, value) in map {
+                        let value = match value.as_str() {
+                            Some(template) => serde_json::Value::String(render(template, ctx)?),
+                            None => value.clone(),
+                        };
+                        rendered.insert(key.clone(), value);
+                    }
+                    Some(serde_json::Value::Object(rendered))
+                }
+                other => other.clone(),
+            };
+            TaskPayload::Http {
+                url: render(url, ctx)?,
+                method: method.clone(),
+                headers,
+                body: body.as_deref().map(|body| render(body, ctx)).transpose()?,
+                timeout_seconds: *timeout_seconds,
+                assertions: assertions.clone(),
+            }
+        }
+    })
+}
+
+/// 按实例信息、参数覆盖值与已解密的密钥渲染任务载荷
+pub fn render_task_payload(
+    task: &Task,
+    instance_id: ObjectId,
+    scheduled_time: DateTime<Utc>,
+    retry_count: i32,
+    overrides: Option<&serde_json::Map<String, serde_json::Value>>,
+    secrets: &BTreeMap<String, String>,
+) -> Result<TaskPayload, String> {
+    let params = resolve_parameters(&task.parameters, overrides)?;
+    let ctx = TemplateContext {
+        task_name: &task.name,
+        instance_id,
+        scheduled_time,
+        retry_count,
+        params: &params,
+        secrets,
+    };
+    render_payload(&task.payload, &ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ParameterType;
+    use chrono::TimeZone;
+
+    fn make_params() -> Vec<TaskParameter> {
+        vec![TaskParameter {
+            name: "region".to_string(),
+            param_type: ParameterType::String,
+            default: Some(serde_json::json!("cn")),
+            description: None,
+            allowed_values: None,
+            pattern: None,
+        }]
+    }
+
+    #[test]
+    fn test_render_builtin_variables() {
+        let scheduled_time = Local
+            .with_ymd_and_hms(2026, 3, 4, 8, 30, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        let instance_id = ObjectId::new();
+        let params = BTreeMap::from([("region".to_string(), "eu".to_string())]);
+        let ctx = TemplateContext {
+            task_name: "daily-export",
+            instance_id,
+            scheduled_time,
+            retry_count: 2,
+            params: &params,
+            secrets: &BTreeMap::new(),
+        };
+
+        let rendered = render(
+            "export --date {{ scheduled_date }} --tag {{scheduled_time|%Y%m%d-%H%M}} \
+             --region {{ params.region }} --name {{ task_name }} --retry {{ retry_count }}",
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "export --date 2026-03-04 --tag 20260304-0830 --region eu --name daily-export --retry 2"
+        );
+        assert_eq!(
+            render("{{ instance_id }}@{{ scheduled_timestamp }}", &ctx).unwrap(),
+            format!("{}@{}", instance_id.to_hex(), scheduled_time.timestamp())
+        );
+        assert_eq!(render("no variables", &ctx).unwrap(), "no variables");
+    }
+
+    #[test]
+    fn test_validate_template() {
+        let params = make_params();
+
+        assert!(validate_template("run {{ params.region }} {{ scheduled_date }}", &params).is_ok());
+        assert!(validate_template("{{ params.missing }}", &params).is_err());
+        assert!(validate_template("{{ unknown }}", &params).is_err());
+        assert!(validate_template("{{ scheduled_date | %Y }}", &params).is_err());
+        assert!(validate_template("{{ scheduled_time | %Q }}", &params).is_err());
+        assert!(validate_template("{{ task_name", &params).is_err());
+    }
+
+    #[test]
+    fn test_render_secret_references() {
+        // 参数值原样插入，其中的密钥引用不会被解析
+        let params = BTreeMap::from([("region".to_string(), "${secret:token}".to_string())]);
+        let secrets = BTreeMap::from([("token".to_string(), "s3cr3t".to_string())]);
+        let ctx = TemplateContext {
+            task_name: "sync",
+            instance_id: ObjectId::new(),
+            scheduled_time: Utc::now(),
+            retry_count: 0,
+            params: &params,
+            secrets: &secrets,
+        };
+        assert_eq!(
+            render("Bearer ${secret:token} {{ params.region }}", &ctx).unwrap(),
+            "Bearer s3cr3t ${secret:token}"
+        );
+        assert!(render("${secret:missing}", &ctx).is_err());
+
+        assert!(validate_template("${secret:db.password}", &[]).is_ok());
+        assert!(validate_template("${secret:bad name}", &[]).is_err());
+        assert!(validate_template("${secret:token", &[]).is_err());
+
+        let payload = TaskPayload::Command {
+            command: "deploy --token ${secret:token}".to_string(),
+            timeout_seconds: None,
+            env: Some(BTreeMap::from([(
+                "DB_PASSWORD".to_string(),
+                "${secret:db.password}".to_string(),
+            )])),
+            working_dir: None,
+            run_as_user: None,
+            stdin: Some("${secret:token}".to_string()),
+            limits: None,
+            sandbox: None,
+        };
+        assert_eq!(
+            secret_references(&payload).unwrap(),
+            BTreeSet::from(["db.password".to_string(), "token".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_command_parameters_are_shell_quoted() {
+        assert_eq!(shell_quote("eu-west_1"), "eu-west_1");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+
+        let payload = TaskPayload::Command {
+            command: "printf '%s\n' {{ params.region }}".to_string(),
+            timeout_seconds: None,
+            env: Some(BTreeMap::from([(
+                "REGION".to_string(),
+                "{{ params.region }}".to_string(),
+            )])),
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
+        };
+        for value in [
+            "eu; echo injected",
+            "$(echo injected)",
+            "`echo injected`",
+            "a'b",
+        ] {
+            let params = BTreeMap::from([("region".to_string(), value.to_string())]);
+            let ctx = TemplateContext {
+                task_name: "export",
+                instance_id: ObjectId::new(),
+                scheduled_time: Utc::now(),
+                retry_count: 0,
+                params: &params,
+                secrets: &BTreeMap::new(),
+            };
+            let TaskPayload::Command { command, env, .. } = render_payload(&payload, &ctx).unwrap()
+            else {
+                panic!("应渲染为命令载荷");
+            };
+            // 环境变量不经 shell 解释，保持原值
+            assert_eq!(env.unwrap()["REGION"], value);
+
+            let output = std::process::Command::new("/bin/sh")
+                .arg("-c")
+                .arg(&command)
+                .output()
+                .unwrap();
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                format!("{}\n", value)
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_http_payload() {
+        let payload = TaskPayload::Http {
+            url: "https://example.com/export/{{ scheduled_date }}".to_string(),
+            method: Some("POST".to_string()),
+            headers: Some(serde_json::json!({ "X-Region": "{{ params.region }}", "X-Count": 1 })),
+            body: Some(r#"{"task":"{{ task_name }}"}"#.to_string()),
+            timeout_seconds: None,
+            assertions: None,
+        };
+        let params = BTreeMap::from([("region".to_string(), "cn".to_string())]);
+        let scheduled_time = Local
+            .with_ymd_and_hms(2026, 3, 4, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        let ctx = TemplateContext {
+            task_name: "export",
+            instance_id: ObjectId::new(),
+            scheduled_time,
+            retry_count: 0,
+            params: &params,
+            secrets: &BTreeMap::new(),
+        };
+
+        let TaskPayload::Http {
+            url, headers, body, ..
+        } = render_payload(&payload, &ctx).unwrap()
+        else {
+            panic!("应渲染为 HTTP 载荷");
+        };
+        assert_eq!(url, "https://example.com/export/2026-03-04");
+        let headers = headers.unwrap();
+        assert_eq!(headers["X-Region"], "cn");
+        assert_eq!(headers["X-Count"], 1);
+        assert_eq!(body.as_deref(), Some(r#"{"task":"export"}"#));
+    }
+}
@@ -0,0 +1,300 @@
+//! 执行端定时器：按计划执行时间暂存已收到的消息，到期后再交给执行器
+//!
+//! 分发器会提前一个扫描间隔发布实例，消息经延迟队列在计划执行时间前 1 秒内投递到执行器，
+//! 执行器收到消息后先放入 [`DueQueue`]，到达 `scheduled_time` 才开始执行。暂存的消息数
+//! 由 [`admit`] 限制在 `prefetch_hold_budget` 以内，超出的消息转入延迟队列。
+
+use crate::clock::Clock;
+use crate::executor::task_queue::task_queue::{DELAY_BUCKETS_SECS, delay_bucket, remaining_secs};
+use crate::types::{Task, TaskPayload};
+use chrono::{DateTime, Utc};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+struct Entry<T> {
+    due: DateTime<Utc>,
+    priority: u8,
+    seq: u64,
+    item: T,
+}
+
+impl<T> Entry<T> {
+    /// 计划时间早的先出队，同一时间按消息优先级降序、入队顺序升序
+    fn key(&self) -> (DateTime<Utc>, Reverse<u8>, u64) {
+        (self.due, Reverse(self.priority), self.seq)
+    }
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// 按到期时间排序的待执行队列
+pub struct DueQueue<T> {
+    heap: BinaryHeap<Reverse<Entry<T>>>,
+    next_seq: u64,
+}
+
+impl<T> Default for DueQueue<T> {
+    fn default() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            next_seq: 0,
+        }
+    }
+}
+
+impl<T> DueQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, due: DateTime<Utc>, priority: u8, item: T) {
+        self.heap.push(Reverse(Entry {
+            due,
+            priority,
+            seq: self.next_seq,
+            item,
+        }));
+        self.next_seq += 1;
+    }
+
+    /// 最早的到期时间
+    pub fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.heap.peek().map(|Reverse(entry)| entry.due)
+    }
+
+    /// 取出一个已到期的消息
+    pub fn pop_due(&mut self, now: DateTime<Utc>) -> Option<T> {
+        if self.next_due()? > now {
+            return None;
+        }
+        self.heap.pop().map(|Reverse(entry)| entry.item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+/// 执行器收到消息后的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// 转入指定档位（秒）的延迟队列
+    Delay(i64),
+    /// 暂存到计划执行时间
+    Hold,
+    /// 已到期，立即执行
+    Ready,
+}
+
+/// 根据计划执行时间与已暂存的消息数决定如何处理收到的消息
+///
+/// 距计划时间 1 秒以上的消息转入对应档位的延迟队列；不足 1 秒的消息在暂存数未达
+/// `hold_budget` 时暂存，否则转入最短的延迟队列，到期后最多晚 1 秒执行。
+pub fn admit(
+    scheduled_time: i64,
+    now: DateTime<Utc>,
+    held: usize,
+    hold_budget: usize,
+) -> Admission {
+    if let Some(delay_secs) = delay_bucket(remaining_secs(scheduled_time, now)) {
+        return Admission::Delay(delay_secs);
+    }
+    if scheduled_time * 1000 <= now.timestamp_millis() {
+        return Admission::Ready;
+    }
+    if held < hold_budget {
+        Admission::Hold
+    } else {
+        Admission::Delay(DELAY_BUCKETS_SECS[0])
+    }
+}
+
+/// 距到期时间的等待时长，已到期返回 0
+pub fn delay_until(due: DateTime<Utc>, now: DateTime<Utc>) -> std::time::Duration {
+    (due - now).to_std().unwrap_or_default()
+}
+
+/// 等待到指定时间，`None` 表示没有待执行的消息，永远等待
+pub async fn sleep_until(clock: &dyn Clock, due: Option<DateTime<Utc>>) {
+    match due {
+        Some(due) => tokio::time::sleep(delay_until(due, clock.now())).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// 任务的实际超时时间：依次取载荷、任务的 `timeout_seconds`，都未设置时取执行器默认值，
+/// 结果为 0 表示不限制
+pub fn effective_timeout(
+    task: &Task,
+    payload: &TaskPayload,
+    default_secs: u64,
+) -> Option<std::time::Duration> {
+    let payload_secs = match payload {
+        TaskPayload::Command {
+            timeout_seconds, ..
+        }
+        | TaskPayload::Http {
+            timeout_seconds, ..
+        }
+        | TaskPayload::Script {
+            timeout_seconds, ..
+        }
+        | TaskPayload::Wasm {
+            timeout_seconds, ..
+        }
+        | TaskPayload::Grpc {
+            timeout_seconds, ..
+        } => *timeout_seconds,
+    };
+    let secs = payload_secs
+        .or(task.timeout_seconds)
+        .map(|secs| secs.max(0) as u64)
+        .unwrap_or(default_secs);
+    (secs > 0).then(|| std::time::Duration::from_secs(secs))
+}
+
+/// 开始延迟（毫秒）：实际开始时间晚于计划执行时间的时长
+pub fn start_latency_ms(scheduled_time: DateTime<Utc>, start_time: DateTime<Utc>) -> i64 {
+    (start_time - scheduled_time).num_milliseconds()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_due_queue_releases_in_time_order() {
+        let now = Utc::now();
+        let mut queue = DueQueue::new();
+        queue.push(now + Duration::seconds(20), 0, "late");
+        queue.push(now + Duration::seconds(5), 0, "soon");
+        queue.push(now - Duration::seconds(1), 0, "overdue");
+
+        assert_eq!(queue.next_due(), Some(now - Duration::seconds(1)));
+        assert_eq!(queue.pop_due(now), Some("overdue"));
+        assert_eq!(queue.pop_due(now), None);
+        assert_eq!(queue.len(), 2);
+
+        let later = now + Duration::seconds(30);
+        assert_eq!(queue.pop_due(later), Some("soon"));
+        assert_eq!(queue.pop_due(later), Some("late"));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_due_queue_orders_same_time_by_priority() {
+        let now = Utc::now();
+        let mut queue = DueQueue::new();
+        queue.push(now, 1, "low-first");
+        queue.push(now, 9, "high");
+        queue.push(now, 1, "low-second");
+
+        assert_eq!(queue.pop_due(now), Some("high"));
+        assert_eq!(queue.pop_due(now), Some("low-first"));
+        assert_eq!(queue.pop_due(now), Some("low-second"));
+    }
+
+    #[test]
+    fn test_admit_bounds_held_messages_below_prefetch() {
+        // 并发上限 2、暂存名额 3，预取数为 5；先收到 20 条即将到期的消息，最后是一条已到期的消息
+        let (max_concurrent, hold_budget) = (2, 3);
+        let prefetch = max_concurrent + hold_budget;
+        let now = Utc.timestamp_opt(1_700_000_000, 500_000_000).unwrap();
+        let soon = now.timestamp() + 1;
+
+        let mut held = DueQueue::new();
+        let mut redelayed = 0;
+        for i in 0..20 {
+            match admit(soon, now, held.len(), hold_budget) {
+                Admission::Hold => held.push(now + Duration::milliseconds(500), 0, i),
+                Admission::Delay(secs) => {
+                    assert_eq!(secs, 1);
+                    redelayed += 1;
+                }
+                Admission::Ready => panic!("未到期的消息不应立即执行"),
+            }
+            assert!(held.len() <= hold_budget);
+        }
+        assert_eq!(held.len(), hold_budget);
+        assert_eq!(redelayed, 20 - hold_budget);
+
+        // 暂存名额已满，预取数中仍留有执行名额，到期的消息不会被未到期的消息挤占
+        assert!(held.len() + max_concurrent <= prefetch);
+        assert_eq!(
+            admit(now.timestamp(), now, held.len(), hold_budget),
+            Admission::Ready
+        );
+        // 距计划时间较远的消息始终转入对应档位
+        assert_eq!(
+            admit(now.timestamp() + 70, now, 0, hold_budget),
+            Admission::Delay(64)
+        );
+    }
+
+    #[test]
+    fn test_delay_until() {
+        let now = Utc::now();
+        assert_eq!(
+            delay_until(now + Duration::seconds(3), now),
+            std::time::Duration::from_secs(3)
+        );
+        assert_eq!(
+            delay_until(now - Duration::seconds(3), now),
+            std::time::Duration::ZERO
+        );
+        assert_eq!(
+            start_latency_ms(now, now + Duration::milliseconds(250)),
+            250
+        );
+    }
+
+    #[test]
+    fn test_effective_timeout() {
+        let mut task = Task::for_test("timeout-task");
+        let secs = std::time::Duration::from_secs;
+
+        assert_eq!(
+            effective_timeout(&task, &task.payload, 300),
+            Some(secs(300))
+        );
+        assert_eq!(effective_timeout(&task, &task.payload, 0), None);
+
+        task.timeout_seconds = Some(60);
+        assert_eq!(effective_timeout(&task, &task.payload, 300), Some(secs(60)));
+
+        let payload = TaskPayload::Command {
+            command: "echo ok".to_string(),
+            timeout_seconds: Some(5),
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
+        };
+        assert_eq!(effective_timeout(&task, &payload, 300), Some(secs(5)));
+    }
+}
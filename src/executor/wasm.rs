@@ -0,0 +1,743 @@
+//! wasm 任务执行：在嵌入的 wasmtime 运行时中运行 WASI（preview1）模块
+//!
+//! 模块只能访问显式授予的目录与环境变量，不能访问网络。执行受三类限制：
+//! fuel（约等于执行的指令数）、超时（通过 epoch 中断实现）与线性内存上限，fuel 与超时至少设置一项。
+//! 超时或执行被取消时，执行线程在下一次 epoch 检查时中断；阻塞在主机调用中的模块要等该调用返回后才会中断。
+
+use crate::config::ExecutorConfig;
+use crate::executor::runner::{RunContext, RunOutcome, TaskRunner};
+use crate::storage::mongo::MongoDataSource;
+use crate::types::{
+    ExecutionResult, MAX_WASM_MODULE_BYTES, TaskPayload, TaskStatus, WasmDirGrant, WasmModuleRef,
+    validate_wasm_spec,
+};
+use anyhow::Context;
+use futures::future::BoxFuture;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::info;
+use wasmtime::{Config, Engine, Linker, Module, ResourceLimiter, Store, Trap, UpdateDeadline};
+use wasmtime_wasi::pipe::MemoryInputPipe;
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{
+    DirPerms, FilePerms, I32Exit, OutputStream, Pollable, StdoutStream, StreamError, WasiCtxBuilder,
+};
+
+/// epoch 递增间隔，即超时的精度
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+/// 编译结果缓存的模块数上限
+const MODULE_CACHE_SIZE: usize = 32;
+/// 表元素数上限
+const MAX_TABLE_ELEMENTS: usize = 100_000;
+
+/// 创建开启 fuel 计量与 epoch 中断的引擎
+fn new_engine() -> anyhow::Result<Engine> {
+    let mut config = Config::new();
+    config.consume_fuel(true).epoch_interruption(true);
+    Engine::new(&config)
+}
+
+/// 校验模块能否被执行器编译并导出 `_start`，在上传模块时调用
+pub fn validate_module(bytes: &[u8]) -> Result<(), String> {
+    if bytes.len() > MAX_WASM_MODULE_BYTES {
+        return Err(format!("模块不能超过{}字节", MAX_WASM_MODULE_BYTES));
+    }
+    let engine = new_engine().map_err(|e| format!("创建 wasm 引擎失败: {}", e))?;
+    let module = Module::new(&engine, bytes).map_err(|e| format!("无效的 wasm 模块: {}", e))?;
+    if !matches!(
+        module.get_export("_start"),
+        Some(wasmtime::ExternType::Func(_))
+    ) {
+        return Err("模块必须导出 _start 函数（WASI 命令模块）".to_string());
+    }
+    Ok(())
+}
+
+/// 捕获模块的 stdout/stderr，超出上限的部分丢弃
+#[derive(Clone)]
+struct CapturedOutput {
+    buffer: Arc<Mutex<Vec<u8>>>,
+    truncated: Arc<AtomicBool>,
+    max_bytes: usize,
+}
+
+impl CapturedOutput {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            buffer: Arc::default(),
+            truncated: Arc::default(),
+            max_bytes,
+        }
+    }
+
+    fn take(&self) -> (String, bool) {
+        let buffer = std::mem::take(&mut *self.buffer.lock().unwrap());
+        (
+            String::from_utf8_lossy(&buffer).into_owned(),
+            self.truncated.load(Ordering::Relaxed),
+        )
+    }
+}
+
+impl StdoutStream for CapturedOutput {
+    fn stream(&self) -> Box<dyn OutputStream> {
+        Box::new(self.clone())
+    }
+
+    fn isatty(&self) -> bool {
+        false
+    }
+}
+
+#[wasmtime_wasi::async_trait]
+impl Pollable for CapturedOutput {
+    async fn ready(&mut self) {}
+}
+
+impl OutputStream for CapturedOutput {
+    fn write(&mut self, bytes: bytes::Bytes) -> Result<(), StreamError> {
+        let mut buffer = self.buffer.lock().unwrap();
+        let room = self.max_bytes.saturating_sub(buffer.len());
+        if bytes.len() > room {
+            self.truncated.store(true, Ordering::Relaxed);
+        }
+        buffer.extend_from_slice(&bytes[..bytes.len().min(room)]);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), StreamError> {
+        Ok(())
+    }
+
+    fn check_write(&mut self) -> Result<usize, StreamError> {
+        // 超出上限的输出直接丢弃，始终可写
+        Ok(64 * 1024)
+    }
+}
+
+/// 限制线性内存与表的大小，记录是否因超出内存上限而拒绝增长
+struct MemoryLimiter {
+    max_bytes: usize,
+    exceeded: bool,
+}
+
+impl ResourceLimiter for MemoryLimiter {
+    fn memory_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> anyhow::Result<bool> {
+        if desired > self.max_bytes {
+            self.exceeded = true;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> anyhow::Result<bool> {
+        Ok(desired <= MAX_TABLE_ELEMENTS)
+    }
+}
+
+struct WasmState {
+    wasi: WasiP1Ctx,
+    limiter: MemoryLimiter,
+}
+
+/// 模块的结束方式
+#[derive(Debug, PartialEq)]
+enum WasmExit {
+    Exited(i32),
+    OutOfFuel,
+    OutOfMemory,
+    TimedOut,
+    Failed(String),
+}
+
+/// 一次模块调用，在阻塞线程中执行
+struct Invocation {
+    module: Module,
+    args: Vec<String>,
+    env: BTreeMap<String, String>,
+    stdin: Option<String>,
+    dirs: Vec<WasmDirGrant>,
+    fuel: Option<u64>,
+    memory_bytes: u64,
+    timeout: Option<Duration>,
+    /// 置位后模块在下一次 epoch 检查时中断
+    cancelled: Arc<AtomicBool>,
+    stdout: CapturedOutput,
+    stderr: CapturedOutput,
+}
+
+/// 释放时取消对应的模块调用，保证超时返回或执行被中止后执行线程随之退出
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Invocation {
+    fn run(self, engine: &Engine) -> WasmExit {
+        self.execute(engine)
+            .unwrap_or_else(|e| WasmExit::Failed(format!("{:#}", e)))
+    }
+
+    fn execute(self, engine: &Engine) -> anyhow::Result<WasmExit> {
+        let mut builder = WasiCtxBuilder::new();
+        builder
+            .args(&self.args)
+            .stdout(self.stdout)
+            .stderr(self.stderr);
+        for (key, value) in &self.env {
+            builder.env(key, value);
+        }
+        if let Some(stdin) = self.stdin {
+            builder.stdin(MemoryInputPipe::new(stdin));
+        }
+        for dir in &self.dirs {
+            let (dir_perms, file_perms) = if dir.writable {
+                (DirPerms::all(), FilePerms::all())
+            } else {
+                (DirPerms::READ, FilePerms::READ)
+            };
+            builder
+                .preopened_dir(&dir.host_path, &dir.guest_path, dir_perms, file_perms)
+                .with_context(|| format!("授权目录 {} 不可用", dir.host_path))?;
+        }
+
+        let limiter = MemoryLimiter {
+            max_bytes: usize::try_from(self.memory_bytes).unwrap_or(usize::MAX),
+            exceeded: false,
+        };
+        let mut store = Store::new(
+            engine,
+            WasmState {
+                wasi: builder.build_p1(),
+                limiter,
+            },
+        );
+        store.limiter(|state| &mut state.limiter);
+        store.set_fuel(self.fuel.unwrap_or(u64::MAX))?;
+        // 每个 epoch 检查一次是否超时或被取消
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let cancelled = self.cancelled;
+        store.set_epoch_deadline(1);
+        store.epoch_deadline_callback(move |_| {
+            if cancelled.load(Ordering::Relaxed) || deadline.is_some_and(|d| Instant::now() >= d) {
+                return Err(Trap::Interrupt.into());
+            }
+            Ok(UpdateDeadline::Continue(1))
+        });
+
+        let mut linker = Linker::new(engine);
+        preview1::add_to_linker_sync(&mut linker, |state: &mut WasmState| &mut state.wasi)?;
+        let result = start(&linker, &mut store, &self.module);
+
+        let Err(e) = result else {
+            return Ok(WasmExit::Exited(0));
+        };
+        let exit_code = e.downcast_ref::<I32Exit>().map(|exit| exit.0);
+        Ok(if exit_code == Some(0) {
+            WasmExit::Exited(0)
+        } else if store.data().limiter.exceeded {
+            WasmExit::OutOfMemory
+        } else if let Some(code) = exit_code {
+            WasmExit::Exited(code)
+        } else {
+            match e.downcast_ref::<Trap>() {
+                Some(Trap::OutOfFuel) => WasmExit::OutOfFuel,
+                Some(Trap::Interrupt) => WasmExit::TimedOut,
+                _ => WasmExit::Failed(format!("{:#}", e)),
+            }
+        })
+    }
+}
+
+fn start(
+    linker: &Linker<WasmState>,
+    store: &mut Store<WasmState>,
+    module: &Module,
+) -> anyhow::Result<()> {
+    let instance = linker.instantiate(&mut *store, module)?;
+    let start = instance.get_typed_func::<(), ()>(&mut *store, "_start")?;
+    start.call(store, ())
+}
+
+/// 将模块的结束方式与输出转换为执行结果
+fn wasm_outcome(
+    exit: WasmExit,
+    (mut stdout, stdout_truncated): (String, bool),
+    (mut stderr, stderr_truncated): (String, bool),
+    timeout: Option<Duration>,
+) -> RunOutcome {
+    if stdout_truncated {
+        stdout.push_str("\n...（输出已截断）");
+    }
+    if stderr_truncated {
+        stderr.push_str("\n...（输出已截断）");
+    }
+    let stderr = (!stderr.is_empty()).then_some(stderr);
+
+    let (status, reason, exit_code) = match exit {
+        WasmExit::Exited(0) => {
+            return RunOutcome {
+                result: ExecutionResult {
+                    output: Some(stdout),
+                    error: stderr,
+                    exit_code: Some(0),
+                    failed_assertion: None,
+                },
+                status: TaskStatus::Success,
+                summary: Some("wasm 模块执行成功".to_string()),
+                error: None,
+            };
+        }
+        WasmExit::TimedOut => {
+            let mut outcome = RunOutcome::timed_out(timeout);
+            outcome.result.output = Some(stdout);
+            return outcome;
+        }
+        WasmExit::Exited(code) => (
+            TaskStatus::Failed,
+            format!("模块退出码为 {}", code),
+            Some(code),
+        ),
+        WasmExit::OutOfFuel => (TaskStatus::Failed, "超出 fuel 限制".to_string(), Some(1)),
+        WasmExit::OutOfMemory => (TaskStatus::Failed, "超出内存限制".to_string(), Some(1)),
+        WasmExit::Failed(message) => (
+            TaskStatus::Failed,
+            format!("模块执行失败: {}", message),
+            Some(1),
+        ),
+    };
+    let error = match &stderr {
+        Some(stderr) => format!("{}: {}", reason, stderr.trim_end()),
+        None => reason.clone(),
+    };
+    RunOutcome {
+        result: ExecutionResult {
+            output: Some(stdout),
+            error: Some(error.clone()),
+            exit_code,
+            failed_assertion: None,
+        },
+        status,
+        summary: Some(reason),
+        error: Some(error),
+    }
+}
+
+/// wasm 任务运行器
+#[derive(Clone)]
+pub struct WasmRunner {
+    engine: Engine,
+    db: Option<Arc<MongoDataSource>>,
+    /// 按模块内容的 SHA-256 缓存编译结果
+    modules: Arc<Mutex<HashMap<String, Module>>>,
+    default_fuel: Option<u64>,
+    max_memory_bytes: u64,
+    max_output_bytes: usize,
+    module_dir: Option<PathBuf>,
+}
+
+impl WasmRunner {
+    pub fn new(config: &ExecutorConfig) -> Self {
+        let engine = new_engine().expect("创建 wasm 引擎失败");
+        // 按固定间隔递增 epoch，各调用按超时时间折算的间隔数设置中断期限；引擎释放后线程退出
+        let weak = engine.weak();
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(EPOCH_TICK);
+                match weak.upgrade() {
+                    Some(engine) => engine.increment_epoch(),
+                    None => break,
+                }
+            }
+        });
+        Self {
+            engine,
+            db: None,
+            modules: Arc::default(),
+            default_fuel: config.wasm_default_fuel,
+            max_memory_bytes: config.wasm_max_memory_bytes,
+            max_output_bytes: config.max_output_bytes,
+            module_dir: config.wasm_module_dir.as_ref().map(PathBuf::from),
+        }
+    }
+
+    /// 解析模块文件路径，只允许 `executor.wasm_module_dir` 下的文件（解析符号链接后判断）
+    async fn resolve_module_path(&self, path: &str) -> Result<PathBuf, String> {
+        let dir = self
+            .module_dir
+            .as_deref()
+            .ok_or_else(|| "执行器未配置 wasm_module_dir，不能从路径加载模块".to_string())?;
+        let dir = tokio::fs::canonicalize(dir)
+            .await
+            .map_err(|e| format!("模块目录 {} 不可用: {}", dir.display(), e))?;
+        let resolved = tokio::fs::canonicalize(Path::new(path))
+            .await
+            .map_err(|e| format!("读取模块文件 {} 失败: {}", path, e))?;
+        if !resolved.starts_with(&dir) {
+            return Err(format!("模块文件 {} 不在 wasm_module_dir 中", path));
+        }
+        Ok(resolved)
+    }
+
+    /// 读取上传到数据库中的模块
+    pub fn with_db(mut self, db: Arc<MongoDataSource>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    async fn load_module(&self, module: &WasmModuleRef) -> Result<Module, String> {
+        let (bytes, sha256) = match module {
+            WasmModuleRef::Name(name) => {
+                let db = self
+                    .db
+                    .as_ref()
+                    .ok_or_else(|| "执行器未连接数据库，无法读取模块".to_string())?;
+                let module = db
+                    .get_wasm_module_by_name(name)
+                    .await
+                    .map_err(|e| format!("读取模块 {} 失败: {}", name, e))?
+                    .ok_or_else(|| format!("模块不存在: {}", name))?;
+                (module.data.bytes, module.sha256)
+            }
+            WasmModuleRef::Path(path) => {
+                let resolved = self.resolve_module_path(path).await?;
+                let bytes = tokio::fs::read(resolved)
+                    .await
+                    .map_err(|e| format!("读取模块文件 {} 失败: {}", path, e))?;
+                let sha256 = hex::encode(Sha256::digest(&bytes));
+                (bytes, sha256)
+            }
+        };
+        if let Some(module) = self.modules.lock().unwrap().get(&sha256) {
+            return Ok(module.clone());
+        }
+
+        let engine = self.engine.clone();
+        let module = tokio::task::spawn_blocking(move || Module::new(&engine, &bytes))
+            .await
+            .map_err(|e| format!("编译模块失败: {}", e))?
+            .map_err(|e| format!("编译模块失败: {}", e))?;
+        let mut modules = self.modules.lock().unwrap();
+        if modules.len() >= MODULE_CACHE_SIZE {
+            modules.clear();
+        }
+        modules.insert(sha256, module.clone());
+        Ok(module)
+    }
+
+    async fn run(&self, ctx: RunContext<'_>) -> RunOutcome {
+        let TaskPayload::Wasm {
+            wasm, env, stdin, ..
+        } = ctx.payload
+        else {
+            return RunOutcome::payload_mismatch(&ctx.task.task_type);
+        };
+        if let Err(e) = validate_wasm_spec(wasm) {
+            return RunOutcome::failed("wasm 任务校验失败", e);
+        }
+        let fuel = wasm.fuel.or(self.default_fuel);
+        if fuel.is_none() && ctx.timeout.is_none() {
+            return RunOutcome::failed("wasm 任务校验失败", "wasm 任务必须设置 fuel 或超时时间");
+        }
+        let module = match self.load_module(&wasm.module).await {
+            Ok(module) => module,
+            Err(e) => return RunOutcome::failed("加载 wasm 模块失败", e),
+        };
+
+        let program = match &wasm.module {
+            WasmModuleRef::Name(name) => name.clone(),
+            WasmModuleRef::Path(path) => path.clone(),
+        };
+        info!("执行 wasm 任务: {}", program);
+        let stdout = CapturedOutput::new(self.max_output_bytes);
+        let stderr = CapturedOutput::new(self.max_output_bytes);
+        let invocation = Invocation {
+            module,
+            args: std::iter::once(program)
+                .chain(wasm.args.iter().cloned())
+                .collect(),
+            env: env.clone().unwrap_or_default(),
+            stdin: stdin.clone(),
+            dirs: wasm.dirs.clone(),
+            fuel,
+            memory_bytes: wasm.memory_bytes.map_or(self.max_memory_bytes, |bytes| {
+                bytes.min(self.max_memory_bytes)
+            }),
+            timeout: ctx.timeout,
+            cancelled: Arc::default(),
+            stdout: stdout.clone(),
+            stderr: stderr.clone(),
+        };
+        let _cancel = CancelOnDrop(invocation.cancelled.clone());
+
+        let engine = self.engine.clone();
+        let handle = tokio::task::spawn_blocking(move || invocation.run(&engine));
+        // epoch 中断不能打断阻塞在主机调用中的模块，外层超时兜底；返回时取消调用，模块回到 wasm 代码后即中断
+        let joined = match ctx.timeout {
+            Some(timeout) => {
+                match tokio::time::timeout(timeout + Duration::from_secs(1), handle).await {
+                    Ok(joined) => joined,
+                    Err(_) => Ok(WasmExit::TimedOut),
+                }
+            }
+            None => handle.await,
+        };
+        let exit = joined.unwrap_or_else(|e| WasmExit::Failed(format!("执行线程异常: {}", e)));
+        wasm_outcome(exit, stdout.take(), stderr.take(), ctx.timeout)
+    }
+}
+
+impl TaskRunner for WasmRunner {
+    fn execute<'a>(&'a self, ctx: RunContext<'a>) -> BoxFuture<'a, RunOutcome> {
+        Box::pin(self.run(ctx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::SecretMask;
+    use crate::types::{Task, TaskType, WasmSpec};
+    use chrono::Utc;
+
+    /// 向 stdout 写入 `message`（不超过 64 字节），随后执行 `tail`
+    fn module(message: &str, tail: &str) -> Vec<u8> {
+        let data: String = message.bytes().map(|b| format!("\\{:02x}", b)).collect();
+        wat::parse_str(format!(
+            r#"(module
+                (import "wasi_snapshot_preview1" "fd_write"
+                    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+                (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 64) "{data}")
+                (func (export "_start")
+                    (i32.store (i32.const 0) (i32.const 64))
+                    (i32.store (i32.const 4) (i32.const {len}))
+                    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+                    {tail}))"#,
+            data = data,
+            len = message.len(),
+            tail = tail,
+        ))
+        .unwrap()
+    }
+
+    /// 未测试超时的用例使用的超时时间
+    const TIMEOUT: Option<Duration> = Some(Duration::from_secs(10));
+
+    struct Fixture {
+        dir: std::path::PathBuf,
+        runner: WasmRunner,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("rapidcron-wasm-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir(&dir).unwrap();
+            let runner = WasmRunner::new(&ExecutorConfig {
+                wasm_module_dir: Some(dir.to_string_lossy().into_owned()),
+                ..Default::default()
+            });
+            Self { dir, runner }
+        }
+
+        async fn run(
+            &self,
+            bytes: &[u8],
+            fuel: Option<u64>,
+            memory_bytes: Option<u64>,
+            timeout: Option<Duration>,
+        ) -> RunOutcome {
+            let path = self.dir.join(format!("{}.wasm", uuid::Uuid::new_v4()));
+            std::fs::write(&path, bytes).unwrap();
+            self.run_path(&path.to_string_lossy(), fuel, memory_bytes, timeout)
+                .await
+        }
+
+        async fn run_path(
+            &self,
+            path: &str,
+            fuel: Option<u64>,
+            memory_bytes: Option<u64>,
+            timeout: Option<Duration>,
+        ) -> RunOutcome {
+            let payload = TaskPayload::Wasm {
+                wasm: WasmSpec {
+                    module: WasmModuleRef::Path(path.to_string()),
+                    args: Vec::new(),
+                    dirs: Vec::new(),
+                    fuel,
+                    memory_bytes,
+                },
+                timeout_seconds: None,
+                env: None,
+                stdin: None,
+            };
+            let task = Task {
+                id: None,
+                name: "wasm".to_string(),
+                description: None,
+                dependency_ids: Vec::new(),
+                tags: Vec::new(),
+                task_type: TaskType::Wasm,
+                schedule: "0 * * * * *".to_string(),
+                enabled: true,
+                payload,
+                timeout_seconds: None,
+                max_retries: None,
+                priority: None,
+                deadline_seconds: None,
+                start_sla_seconds: None,
+                run_sla_seconds: None,
+                max_concurrent_instances: None,
+                rate_limit: None,
+                parameters: Vec::new(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                deleted_at: None,
+            };
+            self.runner
+                .execute(RunContext {
+                    task: &task,
+                    payload: &task.payload,
+                    timeout,
+                    secret_mask: &SecretMask::default(),
+                })
+                .await
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_module_and_capture_output() {
+        let fixture = Fixture::new();
+
+        let outcome = fixture
+            .run(&module("hello wasm\n", ""), None, None, TIMEOUT)
+            .await;
+        assert_eq!(outcome.status, TaskStatus::Success);
+        assert_eq!(outcome.result.output.as_deref(), Some("hello wasm\n"));
+
+        let outcome = fixture
+            .run(
+                &module("partial", "(call $proc_exit (i32.const 3))"),
+                None,
+                None,
+                TIMEOUT,
+            )
+            .await;
+        assert_eq!(outcome.status, TaskStatus::Failed);
+        assert_eq!(outcome.result.exit_code, Some(3));
+        assert_eq!(outcome.result.output.as_deref(), Some("partial"));
+    }
+
+    #[tokio::test]
+    async fn test_enforce_fuel_time_and_memory_limits() {
+        let fixture = Fixture::new();
+        let spin = module("", "(loop $spin (br $spin))");
+
+        let outcome = fixture.run(&spin, Some(10_000), None, None).await;
+        assert_eq!(outcome.status, TaskStatus::Failed);
+        assert_eq!(outcome.summary.as_deref(), Some("超出 fuel 限制"));
+
+        let outcome = fixture
+            .run(&spin, None, None, Some(Duration::from_millis(200)))
+            .await;
+        assert_eq!(outcome.status, TaskStatus::TimedOut);
+
+        // 增长失败时 memory.grow 返回 -1，模块随后陷入 unreachable
+        let grow = module(
+            "",
+            "(if (i32.eq (memory.grow (i32.const 16)) (i32.const -1)) (then unreachable))",
+        );
+        let outcome = fixture.run(&grow, None, Some(4 * 65536), TIMEOUT).await;
+        assert_eq!(outcome.status, TaskStatus::Failed);
+        assert_eq!(outcome.summary.as_deref(), Some("超出内存限制"));
+        let outcome = fixture.run(&grow, None, None, TIMEOUT).await;
+        assert_eq!(outcome.status, TaskStatus::Success);
+
+        // fuel 与超时都未设置时拒绝执行
+        let outcome = fixture.run(&spin, None, None, None).await;
+        assert_eq!(outcome.status, TaskStatus::Failed);
+        assert!(outcome.error.unwrap().contains("fuel 或超时时间"));
+    }
+
+    #[tokio::test]
+    async fn test_module_path_must_be_inside_module_dir() {
+        let fixture = Fixture::new();
+        let outside = std::env::temp_dir().join(format!("{}.wasm", uuid::Uuid::new_v4()));
+        std::fs::write(&outside, module("ok", "")).unwrap();
+        let escaped = fixture.dir.join("..").join(outside.file_name().unwrap());
+
+        for path in [&outside, &escaped] {
+            let outcome = fixture
+                .run_path(&path.to_string_lossy(), None, None, TIMEOUT)
+                .await;
+            assert_eq!(outcome.status, TaskStatus::Failed);
+            assert!(outcome.error.unwrap().contains("不在 wasm_module_dir 中"));
+        }
+
+        // 未配置模块目录时不能从路径加载模块
+        let runner = WasmRunner::new(&ExecutorConfig::default());
+        let err = runner
+            .resolve_module_path(&outside.to_string_lossy())
+            .await
+            .unwrap_err();
+        assert!(err.contains("未配置 wasm_module_dir"));
+        let _ = std::fs::remove_file(&outside);
+    }
+
+    #[test]
+    fn test_cancel_interrupts_running_module() {
+        let engine = WasmRunner::new(&ExecutorConfig::default()).engine;
+        let bytes = module("", "(loop $spin (br $spin))");
+        let invocation = Invocation {
+            module: Module::new(&engine, bytes).unwrap(),
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            stdin: None,
+            dirs: Vec::new(),
+            fuel: None,
+            memory_bytes: 65536,
+            timeout: None,
+            cancelled: Arc::default(),
+            stdout: CapturedOutput::new(1024),
+            stderr: CapturedOutput::new(1024),
+        };
+        let cancel = CancelOnDrop(invocation.cancelled.clone());
+        let handle = std::thread::spawn(move || invocation.run(&engine));
+        std::thread::sleep(Duration::from_millis(100));
+        drop(cancel);
+        assert_eq!(handle.join().unwrap(), WasmExit::TimedOut);
+    }
+
+    #[test]
+    fn test_validate_module() {
+        assert!(validate_module(&module("ok", "")).is_ok());
+        assert!(validate_module(b"not wasm").is_err());
+        let library = wat::parse_str("(module (func (export \"run\")))").unwrap();
+        assert!(validate_module(&library).is_err());
+    }
+}
@@ -0,0 +1,537 @@
+//! 执行器的公共处理流程：领取实例、渲染载荷、交给运行器执行、写回实例状态与执行日志
+//!
+//! 自定义执行器只需实现 [`TaskRunner`](crate::executor::runner::TaskRunner) 并注册到
+//! [`RunnerRegistry`]，消息消费、状态流转与日志记录均复用 [`Worker`]。
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::executor::concurrency::TaskLimiter;
+use crate::executor::runner::{RunContext, RunOutcome, RunnerRegistry};
+use crate::executor::task_queue::task_queue::TaskMessage;
+use crate::executor::template::{render_task_payload, secret_references};
+use crate::executor::timer;
+use crate::secrets::{EncryptedValue, SecretCipher, SecretMask};
+use crate::storage::mongo::MongoDataSource;
+use crate::types::{ExecutionLog, ExecutionResult, Task, TaskInstance, TaskStatus};
+use chrono::{DateTime, TimeZone, Utc};
+use mongodb::bson;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// [`Worker::process_until`] 的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessResult {
+    /// 消息已处理（执行完成，或实例已不是待执行状态），可以确认
+    Processed,
+    /// 任务类型已达并发上限，未开始执行，实例保持待执行状态
+    TypeLimited,
+    /// 执行器正在停止，未开始执行，实例保持待执行状态
+    Stopped,
+}
+
+/// 执行任务消息的工作者
+#[derive(Clone)]
+pub struct Worker {
+    executor_id: String,
+    db: Arc<MongoDataSource>,
+    runners: RunnerRegistry,
+    /// 任务与载荷均未设置超时时间时的默认超时（秒）
+    default_timeout_secs: u64,
+    /// 解密密钥使用的主密钥
+    secret_cipher: Option<SecretCipher>,
+    /// 同时执行的实例上限，默认不限制
+    limiter: TaskLimiter,
+    /// 记录开始与结束时间使用的时钟
+    clock: SharedClock,
+}
+
+impl Worker {
+    pub fn new(executor_id: String, db: Arc<MongoDataSource>, runners: RunnerRegistry) -> Self {
+        Self {
+            executor_id,
+            db,
+            runners,
+            default_timeout_secs: 0,
+            secret_cipher: None,
+            limiter: TaskLimiter::default(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_default_timeout_secs(mut self, default_timeout_secs: u64) -> Self {
+        self.default_timeout_secs = default_timeout_secs;
+        self
+    }
+
+    pub fn with_secret_cipher(mut self, secret_cipher: Option<SecretCipher>) -> Self {
+        self.secret_cipher = secret_cipher;
+        self
+    }
+
+    pub fn with_limiter(mut self, limiter: TaskLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// 使用指定的时钟（默认为系统时钟）
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn limiter(&self) -> &TaskLimiter {
+        &self.limiter
+    }
+
+    pub fn runners(&self) -> &RunnerRegistry {
+        &self.runners
+    }
+
+    /// 处理一条已到期的任务消息
+    ///
+    /// 超时由各任务自身的超时时间控制，执行失败或超时的实例交给重试管理器，不再由消息队列重新投递。
+    /// 等待执行许可期间实例保持待执行状态。
+    pub async fn process(&self, task_msg: &TaskMessage) {
+        self.run_message(task_msg, std::future::pending(), true)
+            .await;
+    }
+
+    /// 与 [`Worker::process`] 相同，但不等待任务类型的执行许可：类型已达上限时返回
+    /// [`ProcessResult::TypeLimited`]；`shutdown` 在取得执行许可前完成时返回
+    /// [`ProcessResult::Stopped`]。两种情况下实例均保持待执行状态，消息可以重新投递
+    pub async fn process_until(
+        &self,
+        task_msg: &TaskMessage,
+        shutdown: impl Future<Output = ()>,
+    ) -> ProcessResult {
+        self.run_message(task_msg, shutdown, false).await
+    }
+
+    async fn run_message(
+        &self,
+        task_msg: &TaskMessage,
+        shutdown: impl Future<Output = ()>,
+        wait_for_type: bool,
+    ) -> ProcessResult {
+        let task = self.db.get_task(task_msg.task_id).await;
+        let task_name = task_msg.task_name.clone();
+        let instance_id = task_msg.instance_id;
+
+        let task_type = match &task {
+            Ok(Some(task)) => Some(&task.task_type),
+            _ => None,
+        };
+        let acquire = async {
+            if wait_for_type {
+                Some(self.limiter.acquire(task_type).await)
+            } else {
+                self.limiter.acquire_within_type_limit(task_type).await
+            }
+        };
+        let _permit = tokio::select! {
+            permit = acquire => match permit {
+                Some(permit) => permit,
+                None => return ProcessResult::TypeLimited,
+            },
+            _ = shutdown => return ProcessResult::Stopped,
+        };
+
+        // 更新任务实例状态为运行中，并记录相对计划执行时间的开始延迟
+        let start_time = self.clock.now();
+        let scheduled_time = Utc
+            .timestamp_opt(task_msg.scheduled_time, 0)
+            .single()
+            .unwrap_or(start_time);
+        let start_latency_ms = timer::start_latency_ms(scheduled_time, start_time);
+        let update_running = bson::doc! {
+            "$set": {
+                "status": "running",
+                "executor_id": self.executor_id.clone(),
+                "start_time": start_time
+            }
+        };
+        // 仅领取待执行的实例，已过期或已取消的实例直接跳过
+        match self
+            .db
+            .update_task_instance_if_status(instance_id, "pending", update_running)
+            .await
+        {
+            Ok(true) => {
+                info!(
+                    "开始执行任务 {} (实例ID: {})，开始延迟 {} ms",
+                    task_name, instance_id, start_latency_ms
+                );
+            }
+            Ok(false) => {
+                info!("任务实例 {} 已不是待执行状态，跳过", instance_id);
+                return ProcessResult::Processed;
+            }
+            Err(e) => {
+                error!("更新任务实例状态为运行中失败: {}", e);
+            }
+        }
+
+        // 实际执行任务
+        let mut secret_mask = SecretMask::default();
+        let outcome = match task {
+            Ok(Some(task)) => {
+                // 渲染载荷模板（内置变量、任务参数与密钥）
+                let overrides = self
+                    .db
+                    .get_task_instance(instance_id)
+                    .await
+                    .ok()
+                    .flatten()
+                    .and_then(|instance| instance.parameters);
+                let rendered = match self.load_secrets(&task).await {
+                    Ok(secrets) => {
+                        secret_mask = SecretMask::new(secrets.values());
+                        render_task_payload(
+                            &task,
+                            instance_id,
+                            scheduled_time,
+                            task_msg.retry_count,
+                            overrides.as_ref(),
+                            &secrets,
+                        )
+                    }
+                    Err(e) => Err(e),
+                };
+                match rendered {
+                    Ok(payload) => {
+                        let timeout =
+                            timer::effective_timeout(&task, &payload, self.default_timeout_secs);
+                        self.runners
+                            .execute(RunContext {
+                                task: &task,
+                                payload: &payload,
+                                timeout,
+                                secret_mask: &secret_mask,
+                            })
+                            .await
+                    }
+                    Err(e) => RunOutcome::failed("渲染任务载荷失败", e),
+                }
+            }
+            // 任务不存在或查询失败
+            _ => RunOutcome {
+                result: ExecutionResult {
+                    output: None,
+                    error: Some("任务不存在或查询失败".to_string()),
+                    exit_code: Some(1),
+                    failed_assertion: None,
+                },
+                status: TaskStatus::Failed,
+                summary: Some("任务不存在".to_string()),
+                error: Some("任务不存在或查询失败".to_string()),
+            },
+        };
+        // 密钥明文不写入实例结果与执行日志
+        let execution_result = secret_mask.mask_result(outcome.result);
+        let output_summary = secret_mask.mask_option(outcome.summary);
+        let error_message = secret_mask.mask_option(outcome.error);
+        let task_status = outcome.status;
+        let end_time = self.clock.now();
+        let duration_ms = (end_time - start_time).num_milliseconds();
+
+        // 将ExecutionResult转换为Bson
+        let result_bson = match serde_json::to_value(execution_result) {
+            Ok(value) => match bson::Bson::try_from(value) {
+                Ok(bson) => bson,
+                Err(e) => {
+                    error!("转换执行结果为Bson失败: {}", e);
+                    bson::Bson::Null
+                }
+            },
+            Err(e) => {
+                error!("序列化执行结果失败: {}", e);
+                bson::Bson::Null
+            }
+        };
+
+        // 更新任务实例状态
+        let update_status = bson::doc! {
+            "$set": {
+                "status": status_str(&task_status),
+                "end_time": end_time,
+                "result": result_bson
+            }
+        };
+        if let Err(e) = self
+            .db
+            .update_task_instance(instance_id, update_status)
+            .await
+        {
+            error!("更新任务实例状态失败: {}", e);
+        }
+
+        // 创建执行日志
+        let execution_log = ExecutionLog {
+            id: None,
+            task_id: task_msg.task_id,
+            task_name: task_name.clone(),
+            instance_id,
+            scheduled_time,
+            start_time: Some(start_time),
+            start_latency_ms: Some(start_latency_ms),
+            end_time,
+            status: task_status.clone(),
+            duration_ms,
+            output_summary,
+            error_message,
+            triggered_by: task_msg.triggered_by.clone(),
+        };
+        if let Err(e) = self.db.create_execution_log(execution_log).await {
+            error!("创建执行日志失败: {}", e);
+        }
+
+        info!(
+            "任务 {} 执行{:?}，实例ID: {}",
+            task_name, task_status, instance_id
+        );
+        ProcessResult::Processed
+    }
+
+    /// 将本执行器仍在执行的实例标记为已中断并记录执行日志，返回中断的实例数
+    ///
+    /// 执行器停止时调用，此时不应再有正在执行的任务写回结果
+    pub async fn interrupt_running(&self) -> anyhow::Result<u64> {
+        let filter = bson::doc! {
+            "executor_id": self.executor_id.clone(),
+            "status": "running"
+        };
+        let instances = self.db.find_task_instances(Some(filter), None).await?;
+        let end_time = self.clock.now();
+        let mut interrupted = 0;
+        for instance in instances {
+            let Some(instance_id) = instance.id else {
+                continue;
+            };
+            // 实例可能恰好执行完，只中断仍处于执行中的实例
+            if !self
+                .db
+                .update_task_instance_if_status(
+                    instance_id,
+                    "running",
+                    interrupted_update(end_time)?,
+                )
+                .await?
+            {
+                continue;
+            }
+            interrupted += 1;
+
+            let task_name = match self.db.get_task(instance.task_id).await {
+                Ok(Some(task)) => task.name,
+                _ => String::new(),
+            };
+            let execution_log = interrupted_log(&instance, instance_id, task_name, end_time);
+            if let Err(e) = self.db.create_execution_log(execution_log).await {
+                error!("创建执行日志失败: {}", e);
+            }
+        }
+        Ok(interrupted)
+    }
+
+    /// 解密任务载荷引用的密钥
+    async fn load_secrets(&self, task: &Task) -> Result<BTreeMap<String, String>, String> {
+        let names = secret_references(&task.payload)?;
+        if names.is_empty() {
+            return Ok(BTreeMap::new());
+        }
+        let cipher = self
+            .secret_cipher
+            .as_ref()
+            .ok_or_else(|| "执行器未配置主密钥，无法解密密钥".to_string())?;
+        let mut secrets = BTreeMap::new();
+        for name in names {
+            let secret = self
+                .db
+                .get_secret_by_name(&name)
+                .await
+                .map_err(|e| format!("读取密钥 {} 失败: {}", name, e))?
+                .ok_or_else(|| format!("密钥不存在: {}", name))?;
+            let value = cipher.decrypt(
+                &name,
+                &EncryptedValue {
+                    nonce: secret.nonce,
+                    ciphertext: secret.ciphertext,
+                },
+            )?;
+            secrets.insert(name, value);
+        }
+        Ok(secrets)
+    }
+}
+
+/// 执行器停止时中断实例记录的错误信息
+const INTERRUPTED_MESSAGE: &str = "执行器停止，执行被中断";
+
+/// 将实例标记为已中断的更新
+fn interrupted_update(end_time: DateTime<Utc>) -> anyhow::Result<bson::Document> {
+    let result = ExecutionResult {
+        output: None,
+        error: Some(INTERRUPTED_MESSAGE.to_string()),
+        exit_code: None,
+        failed_assertion: None,
+    };
+    Ok(bson::doc! {
+        "$set": {
+            "status": status_str(&TaskStatus::Interrupted),
+            "end_time": end_time,
+            "result": bson::to_bson(&result)?
+        }
+    })
+}
+
+/// 被中断实例的执行日志，开始延迟与执行时长按实例的开始时间计算
+fn interrupted_log(
+    instance: &TaskInstance,
+    instance_id: bson::oid::ObjectId,
+    task_name: String,
+    end_time: DateTime<Utc>,
+) -> ExecutionLog {
+    let start_time = instance.start_time;
+    ExecutionLog {
+        id: None,
+        task_id: instance.task_id,
+        task_name,
+        instance_id,
+        scheduled_time: instance.scheduled_time,
+        start_time,
+        start_latency_ms: start_time
+            .map(|start| timer::start_latency_ms(instance.scheduled_time, start)),
+        end_time,
+        status: TaskStatus::Interrupted,
+        duration_ms: start_time
+            .map(|start| (end_time - start).num_milliseconds())
+            .unwrap_or(0),
+        output_summary: Some("执行被中断".to_string()),
+        error_message: Some(INTERRUPTED_MESSAGE.to_string()),
+        triggered_by: instance.triggered_by.clone(),
+    }
+}
+
+fn status_str(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Pending => "pending",
+        TaskStatus::Running => "running",
+        TaskStatus::Success => "success",
+        TaskStatus::Failed => "failed",
+        TaskStatus::Cancelled => "cancelled",
+        TaskStatus::Expired => "expired",
+        TaskStatus::TimedOut => "timed_out",
+        TaskStatus::Interrupted => "interrupted",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DatabaseConfig;
+    use crate::types::TriggeredBy;
+    use bson::oid::ObjectId;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    /// 连接不可达的 MongoDB，数据库操作在 100 毫秒后失败
+    async fn unreachable_worker(limiter: TaskLimiter) -> Worker {
+        let db = MongoDataSource::new(&DatabaseConfig {
+            uri: "mongodb://127.0.0.1:1/?serverSelectionTimeoutMS=100".to_string(),
+            database_name: "rapidcron_test".to_string(),
+            username: String::new(),
+            password: String::new(),
+        })
+        .await
+        .unwrap();
+        Worker::new(
+            "executor-test".to_string(),
+            Arc::new(db),
+            RunnerRegistry::new(),
+        )
+        .with_limiter(limiter)
+    }
+
+    fn message() -> TaskMessage {
+        TaskMessage {
+            instance_id: ObjectId::new(),
+            task_id: ObjectId::new(),
+            task_name: "drain".to_string(),
+            scheduled_time: Utc::now().timestamp(),
+            retry_count: 0,
+            triggered_by: TriggeredBy::Scheduler,
+            priority: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_until_returns_stopped_before_permit() {
+        let limiter = TaskLimiter::new(1, &HashMap::new());
+        let worker = unreachable_worker(limiter.clone()).await;
+        let _busy = limiter.acquire(None).await;
+
+        // 名额已满时停止信号先完成，消息未开始执行，应退回队列
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            worker.process_until(&message(), async {}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result, ProcessResult::Stopped);
+        assert_eq!(limiter.active(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_interrupt_running_reports_database_errors() {
+        let worker = unreachable_worker(TaskLimiter::default()).await;
+        // 查询执行中的实例失败时返回错误，而不是报告没有被中断的实例
+        assert!(worker.interrupt_running().await.is_err());
+    }
+
+    #[test]
+    fn test_interrupted_log_and_update() {
+        let scheduled_time = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let end_time = scheduled_time + chrono::Duration::seconds(12);
+        let instance_id = ObjectId::new();
+        let mut instance = TaskInstance {
+            id: Some(instance_id),
+            task_id: ObjectId::new(),
+            scheduled_time,
+            status: TaskStatus::Running,
+            executor_id: Some("executor-test".to_string()),
+            start_time: Some(scheduled_time + chrono::Duration::seconds(2)),
+            end_time: None,
+            retry_count: 0,
+            result: None,
+            triggered_by: TriggeredBy::Manual,
+            priority_score: None,
+            sla_flags: Vec::new(),
+            trigger_payload: None,
+            parameters: None,
+            created_at: scheduled_time,
+        };
+
+        let log = interrupted_log(&instance, instance_id, "drain".to_string(), end_time);
+        assert_eq!(log.status, TaskStatus::Interrupted);
+        assert_eq!(log.start_latency_ms, Some(2000));
+        assert_eq!(log.duration_ms, 10_000);
+        assert_eq!(log.error_message.as_deref(), Some(INTERRUPTED_MESSAGE));
+        assert_eq!(log.triggered_by, TriggeredBy::Manual);
+
+        instance.start_time = None;
+        let log = interrupted_log(&instance, instance_id, String::new(), end_time);
+        assert_eq!(log.start_latency_ms, None);
+        assert_eq!(log.duration_ms, 0);
+
+        let update = interrupted_update(end_time).unwrap();
+        let set = update.get_document("$set").unwrap();
+        assert_eq!(set.get_str("status").unwrap(), "interrupted");
+        assert_eq!(
+            set.get_document("result")
+                .unwrap()
+                .get_str("error")
+                .unwrap(),
+            INTERRUPTED_MESSAGE
+        );
+    }
+}
@@ -1,9 +1,11 @@
 pub mod api;
+pub mod clock;
 pub mod config;
 pub mod coord;
 pub mod error;
 pub mod executor;
 pub mod logging;
 pub mod scheduler;
+pub mod secrets;
 pub mod storage;
 pub mod types;
@@ -1,17 +1,9 @@
-mod api;
-mod config;
-mod coord;
-mod error;
-mod executor;
-mod logging;
-mod scheduler;
-mod storage;
-mod types;
-
 use anyhow::Result;
 use axum::Router;
-use coord::ServiceInfo;
-use executor::TaskQueue;
+use rapidcron::clock::{SharedClock, SystemClock};
+use rapidcron::coord::ServiceInfo;
+use rapidcron::executor::TaskQueue;
+use rapidcron::{api, config, coord, executor, logging, scheduler, secrets, storage};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::interval;
@@ -24,6 +16,12 @@ async fn main() -> Result<()> {
     logging::init(&cfg.logging)?;
     info!("[Main] configuration loaded");
 
+    let secret_cipher =
+        secrets::SecretCipher::from_config(&cfg.secrets).map_err(|e| anyhow::anyhow!(e))?;
+    if secret_cipher.is_none() {
+        info!("[Main] master key not configured, secret values cannot be written");
+    }
+
     let db = Arc::new(storage::mongo::MongoDataSource::new(&cfg.database).await?);
     info!("[Main] mongodb connection established");
 
@@ -45,6 +43,7 @@ async fn main() -> Result<()> {
         metadata: Some(cfg.service.metadata.clone()),
         started_at: now,
         last_heartbeat: now,
+        labels: Default::default(),
     };
 
     let lease_ttl_secs = cfg.etcd.dead_threshold_secs as i64;
@@ -63,19 +62,28 @@ async fn main() -> Result<()> {
     let task_queue = Arc::new(TaskQueue::new(&amqp_url, cfg.rabbitmq.queue_name.clone()).await?);
     info!("[Main] rabbitmq task queue initialized");
 
+    // 分发、重试、SLA 检查与回填共用同一个时钟
+    let clock: SharedClock = Arc::new(SystemClock);
+
     let dispatcher = scheduler::dispatcher::Dispatcher::new(
         Arc::clone(&db),
         Arc::clone(&task_queue),
         cfg.dispatcher.scan_interval_secs,
         cfg.dispatcher.log_retention_days,
         cfg.dispatcher.scheduling.clone(),
-    );
+        scheduler::dispatcher::DispatchLimits {
+            max_concurrent_tasks: cfg.dispatcher.max_concurrent_tasks,
+            max_defer_secs: cfg.dispatcher.max_defer_secs,
+        },
+    )
+    .with_clock(Arc::clone(&clock));
     dispatcher.start().await?;
     info!("[Main] task dispatcher started");
 
     let retry_config = cfg.retry.clone();
     let retry_manager =
-        executor::RetryManager::new(Arc::clone(&db), Arc::clone(&task_queue), retry_config);
+        executor::RetryManager::new(Arc::clone(&db), Arc::clone(&task_queue), retry_config)
+            .with_clock(Arc::clone(&clock));
     info!("[Main] retry manager initialized");
 
     tokio::spawn(async move {
@@ -99,11 +107,31 @@ async fn main() -> Result<()> {
     });
     info!("[Main] retry scheduler started");
 
+    let sla_checker = scheduler::sla::SlaChecker::new(Arc::clone(&db), cfg.sla.clone())
+        .with_clock(Arc::clone(&clock));
+    tokio::spawn(async move {
+        let mut timer = interval(Duration::from_secs(cfg.sla.check_interval_secs.max(1)));
+        loop {
+            timer.tick().await;
+            if let Err(e) = sla_checker.check().await {
+                error!("[SlaChecker] SLA 检查失败: {}", e);
+            }
+        }
+    });
+    info!("[Main] sla checker started");
+
+    match scheduler::backfill::resume_backfill_jobs(&db, &task_queue, &clock).await {
+        Ok(count) => info!("[Main] resumed {} backfill jobs", count),
+        Err(e) => error!("[Main] 恢复回填作业失败: {}", e),
+    }
+
     let api_router = api::create_router_with_etcd(
         (*db).clone(),
         etcd_manager.clone(),
         task_queue.clone(),
         cfg.auth,
+        cfg.backfill,
+        secret_cipher,
     );
 
     let app = Router::new()
@@ -0,0 +1,353 @@
+//! 历史调度回填
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::error::{Error, Result};
+use crate::executor::{TaskMessage, TaskQueue};
+use crate::scheduler::cron_parser::CronParser;
+use crate::storage::mongo::MongoDataSource;
+use crate::types::{BackfillJob, BackfillStatus, Task, TaskInstance, TaskStatus, TriggeredBy};
+use chrono::{DateTime, Utc};
+use mongodb::bson::{doc, oid::ObjectId};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// 回填执行器：按速率逐个创建并发布回填实例
+pub struct BackfillRunner {
+    db: MongoDataSource,
+    task_queue: Arc<TaskQueue>,
+    job_id: ObjectId,
+    task: Task,
+    rate_per_sec: u32,
+    clock: SharedClock,
+}
+
+impl BackfillRunner {
+    pub fn new(
+        db: MongoDataSource,
+        task_queue: Arc<TaskQueue>,
+        job_id: ObjectId,
+        task: Task,
+        rate_per_sec: u32,
+    ) -> Self {
+        Self {
+            db,
+            task_queue,
+            job_id,
+            task,
+            rate_per_sec,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// 替换回填使用的时钟
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 执行回填，结束后写入最终状态
+    pub async fn run(self, slots: Vec<DateTime<Utc>>) {
+        let job_id = self.job_id;
+        let final_update = match self.dispatch_slots(slots).await {
+            Ok(true) => {
+                info!("[Backfill] job {} completed", job_id);
+                doc! { "$set": { "status": "completed", "finished_at": self.clock.now() } }
+            }
+            Ok(false) => {
+                info!("[Backfill] job {} cancelled", job_id);
+                return;
+            }
+            Err(e) => {
+                error!("[Backfill] job {} failed: {}", job_id, e);
+                doc! { "$set": {
+                    "status": "failed",
+                    "error_message": e.to_string(),
+                    "finished_at": self.clock.now(),
+                } }
+            }
+        };
+
+        if let Err(e) = self
+            .db
+            .update_running_backfill_job(job_id, final_update)
+            .await
+        {
+            error!("[Backfill] failed to update job {}: {}", job_id, e);
+        }
+    }
+
+    /// 逐个分发调度时间，返回 `false` 表示作业已被取消
+    async fn dispatch_slots(&self, slots: Vec<DateTime<Utc>>) -> Result<bool> {
+        let task_id = self.task.id.expect("回填任务必须包含 ID");
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs_f64(1.0 / self.rate_per_sec as f64));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        for scheduled_time in slots {
+            ticker.tick().await;
+
+            if !self.is_running().await? {
+                return Ok(false);
+            }
+
+            let now = self.clock.now();
+            let instance = TaskInstance {
+                id: None,
+                task_id,
+                scheduled_time,
+                status: TaskStatus::Pending,
+                executor_id: None,
+                start_time: None,
+                end_time: None,
+                retry_count: 0,
+                result: None,
+                triggered_by: TriggeredBy::Backfill,
+                priority_score: None,
+                sla_flags: Vec::new(),
+                trigger_payload: None,
+                parameters: None,
+                created_at: now,
+            };
+            let instance_id = self.db.create_task_instance(&instance).await?;
+
+            let published = self
+                .task_queue
+                .publish_task(
+                    TaskMessage {
+                        instance_id,
+                        task_id,
+                        task_name: self.task.name.clone(),
+                        scheduled_time: scheduled_time.timestamp(),
+                        retry_count: 0,
+                        triggered_by: TriggeredBy::Backfill,
+                        priority: self.task.effective_priority() as u8,
+                    },
+                    now,
+                )
+                .await;
+            // 发布失败时删除刚创建的实例，避免恢复作业时把没有消息的实例当作已分发
+            if let Err(e) = published {
+                if let Err(delete_err) = self.db.delete_task_instance(instance_id).await {
+                    error!(
+                        "[Backfill] failed to delete unpublished instance {}: {}",
+                        instance_id, delete_err
+                    );
+                }
+                return Err(Error::MessageQueue(format!("发布任务到队列失败: {}", e)));
+            }
+
+            self.db
+                .update_running_backfill_job(
+                    self.job_id,
+                    doc! { "$inc": { "dispatched_instances": 1 } },
+                )
+                .await?;
+        }
+
+        Ok(true)
+    }
+
+    async fn is_running(&self) -> Result<bool> {
+        let job = self.db.get_backfill_job(self.job_id).await?;
+        Ok(job.is_some_and(|job| job.status == BackfillStatus::Running))
+    }
+}
+
+/// 过滤掉已有成功或进行中实例的调度时间
+pub fn pending_slots(
+    slots: Vec<DateTime<Utc>>,
+    existing: &[TaskInstance],
+) -> (Vec<DateTime<Utc>>, usize) {
+    let covered: HashSet<i64> = existing
+        .iter()
+        .filter(|instance| {
+            matches!(
+                instance.status,
+                TaskStatus::Success | TaskStatus::Pending | TaskStatus::Running
+            )
+        })
+        .map(|instance| instance.scheduled_time.timestamp())
+        .collect();
+
+    let total = slots.len();
+    let pending: Vec<_> = slots
+        .into_iter()
+        .filter(|slot| !covered.contains(&slot.timestamp()))
+        .collect();
+    let skipped = total - pending.len();
+
+    (pending, skipped)
+}
+
+/// 恢复调度器重启前仍处于 running 状态的回填作业
+///
+/// 回填在调度器进程内执行，进程退出后作业不会自行结束。启动时重新计算剩余的调度时间并按原速率继续分发；
+/// 任务已删除或调度表达式无法解析时将作业标记为失败。返回恢复的作业数。
+pub async fn resume_backfill_jobs(
+    db: &MongoDataSource,
+    task_queue: &Arc<TaskQueue>,
+    clock: &SharedClock,
+) -> Result<usize> {
+    let jobs = db
+        .find_backfill_jobs(Some(doc! { "status": "running" }))
+        .await?;
+
+    let mut resumed = 0;
+    for job in jobs {
+        let Some(job_id) = job.id else {
+            continue;
+        };
+        let (task, slots) = match remaining_job_slots(db, &job).await {
+            Ok(remaining) => remaining,
+            Err(e) => {
+                warn!("[Backfill] job {} cannot be resumed: {}", job_id, e);
+                let update = doc! { "$set": {
+                    "status": "failed",
+                    "error_message": format!("调度器重启后无法恢复: {}", e),
+                    "finished_at": clock.now(),
+                } };
+                db.update_running_backfill_job(job_id, update).await?;
+                continue;
+            }
+        };
+
+        if slots.is_empty() {
+            let update = doc! { "$set": { "status": "completed", "finished_at": clock.now() } };
+            db.update_running_backfill_job(job_id, update).await?;
+            continue;
+        }
+
+        info!(
+            "[Backfill] resuming job {} with {} remaining slots",
+            job_id,
+            slots.len()
+        );
+        let runner = BackfillRunner::new(
+            db.clone(),
+            Arc::clone(task_queue),
+            job_id,
+            task,
+            job.rate_per_sec.max(1),
+        )
+        .with_clock(Arc::clone(clock));
+        tokio::spawn(runner.run(slots));
+        resumed += 1;
+    }
+
+    Ok(resumed)
+}
+
+/// 读取作业对应的任务并计算尚未分发的调度时间
+async fn remaining_job_slots(
+    db: &MongoDataSource,
+    job: &BackfillJob,
+) -> Result<(Task, Vec<DateTime<Utc>>)> {
+    let task = db
+        .get_task(job.task_id)
+        .await?
+        .filter(|task| task.deleted_at.is_none())
+        .ok_or_else(|| Error::Execution("任务不存在".to_string()))?;
+    let slots = CronParser::new(&task.schedule)?.triggers_between(
+        job.range_start,
+        job.range_end,
+        job.total_slots.max(0) as usize,
+    );
+    let existing = db
+        .find_task_instances(
+            Some(doc! {
+                "task_id": job.task_id,
+                "scheduled_time": { "$gte": job.range_start, "$lt": job.range_end },
+            }),
+            None,
+        )
+        .await?;
+    Ok((task, remaining_slots(slots, &existing, job.created_at)))
+}
+
+/// 过滤掉作业创建后已分发的回填实例（无论状态）以及已有成功或进行中实例的调度时间
+pub fn remaining_slots(
+    slots: Vec<DateTime<Utc>>,
+    existing: &[TaskInstance],
+    job_created_at: DateTime<Utc>,
+) -> Vec<DateTime<Utc>> {
+    let dispatched: HashSet<i64> = existing
+        .iter()
+        .filter(|instance| {
+            instance.triggered_by == TriggeredBy::Backfill && instance.created_at >= job_created_at
+        })
+        .map(|instance| instance.scheduled_time.timestamp())
+        .collect();
+    let (pending, _) = pending_slots(slots, existing);
+    pending
+        .into_iter()
+        .filter(|slot| !dispatched.contains(&slot.timestamp()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn make_instance(scheduled_time: DateTime<Utc>, status: TaskStatus) -> TaskInstance {
+        TaskInstance {
+            id: None,
+            task_id: ObjectId::new(),
+            scheduled_time,
+            status,
+            executor_id: None,
+            start_time: None,
+            end_time: None,
+            retry_count: 0,
+            result: None,
+            triggered_by: TriggeredBy::Scheduler,
+            priority_score: None,
+            sla_flags: Vec::new(),
+            trigger_payload: None,
+            parameters: None,
+            created_at: scheduled_time,
+        }
+    }
+
+    #[test]
+    fn test_pending_slots_skips_successful_and_in_flight() {
+        let base = Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap();
+        let slots: Vec<_> = (0..4).map(|i| base + chrono::Duration::hours(i)).collect();
+        let existing = vec![
+            make_instance(slots[0], TaskStatus::Success),
+            make_instance(slots[1], TaskStatus::Failed),
+            make_instance(slots[2], TaskStatus::Running),
+        ];
+
+        let (pending, skipped) = pending_slots(slots.clone(), &existing);
+
+        assert_eq!(skipped, 2);
+        assert_eq!(pending, vec![slots[1], slots[3]]);
+    }
+
+    #[test]
+    fn test_remaining_slots_skips_instances_dispatched_by_job() {
+        let base = Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap();
+        let job_created_at = base + chrono::Duration::days(1);
+        let slots: Vec<_> = (0..4).map(|i| base + chrono::Duration::hours(i)).collect();
+
+        // 本作业已分发但执行失败的实例不再重复分发
+        let mut dispatched = make_instance(slots[0], TaskStatus::Failed);
+        dispatched.triggered_by = TriggeredBy::Backfill;
+        dispatched.created_at = job_created_at + chrono::Duration::seconds(5);
+        // 更早的回填作业失败的实例仍需补跑
+        let mut earlier = make_instance(slots[1], TaskStatus::Failed);
+        earlier.triggered_by = TriggeredBy::Backfill;
+        let existing = vec![
+            dispatched,
+            earlier,
+            make_instance(slots[2], TaskStatus::Success),
+        ];
+
+        let remaining = remaining_slots(slots.clone(), &existing, job_created_at);
+
+        assert_eq!(remaining, vec![slots[1], slots[3]]);
+    }
+}
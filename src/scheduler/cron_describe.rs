@@ -0,0 +1,244 @@
+//! Cron 表达式的自然语言描述与风险提示
+
+use chrono::{DateTime, Utc};
+
+/// Cron 单个字段的形态
+#[derive(Debug, PartialEq)]
+enum Field<'a> {
+    /// `*` 或 `?`
+    Any,
+    /// 单个数值
+    Value(u32),
+    /// `*/n` 或 `a/n`
+    Step { start: u32, step: u32 },
+    /// 列表、范围或名称等其他写法
+    Other(&'a str),
+}
+
+fn classify(field: &str) -> Field<'_> {
+    if field == "*" || field == "?" {
+        return Field::Any;
+    }
+    if let Ok(value) = field.parse::<u32>() {
+        return Field::Value(value);
+    }
+    if let Some((start, step)) = field.split_once('/')
+        && let Ok(step) = step.parse::<u32>()
+    {
+        if start == "*" {
+            return Field::Step { start: 0, step };
+        }
+        if let Ok(start) = start.parse::<u32>() {
+            return Field::Step { start, step };
+        }
+    }
+    Field::Other(field)
+}
+
+/// 星期字段的名称（cron 中 1 = 周日，7 = 周六）
+fn weekday_name(token: &str) -> String {
+    let name = match token.to_lowercase().as_str() {
+        "1" | "sun" | "sunday" => "周日",
+        "2" | "mon" | "monday" => "周一",
+        "3" | "tue" | "tues" | "tuesday" => "周二",
+        "4" | "wed" | "wednesday" => "周三",
+        "5" | "thu" | "thurs" | "thursday" => "周四",
+        "6" | "fri" | "friday" => "周五",
+        "7" | "sat" | "saturday" => "周六",
+        _ => return token.to_string(),
+    };
+    name.to_string()
+}
+
+/// 将列表 / 范围写法转换为可读文本，例如 `0,30` -> `0、30`，`1-5` -> `1 至 5`
+fn readable_list(field: &str, map: &dyn Fn(&str) -> String) -> String {
+    field
+        .split(',')
+        .map(|part| match part.split_once('-') {
+            Some((from, to)) => format!("{} 至 {}", map(from), map(to)),
+            None => map(part),
+        })
+        .collect::<Vec<_>>()
+        .join("、")
+}
+
+/// 通用字段描述，用于无法归入常见模式的表达式
+fn describe_field(field: &str, unit: &str) -> String {
+    match classify(field) {
+        Field::Any => format!("每{}", unit),
+        Field::Value(value) => format!("第 {} {}", value, unit),
+        Field::Step { start: 0, step } => format!("每 {} {}", step, unit),
+        Field::Step { start, step } => format!("从第 {} {}起每 {} {}", start, unit, step, unit),
+        Field::Other(raw) => format!("第 {} {}", readable_list(raw, &|s| s.to_string()), unit),
+    }
+}
+
+/// 描述日期部分（日、月、星期）
+fn describe_date(dom: &str, mon: &str, dow: &str) -> String {
+    let day = match classify(dom) {
+        Field::Any => None,
+        Field::Value(value) => Some(format!("{} 日", value)),
+        _ => Some(format!("{} 日", readable_list(dom, &|s| s.to_string()))),
+    };
+    let week = match classify(dow) {
+        Field::Any => None,
+        _ => Some(readable_list(dow, &weekday_name)),
+    };
+
+    let month = match classify(mon) {
+        Field::Any => None,
+        _ => Some(format!("{} 月", readable_list(mon, &|s| s.to_string()))),
+    };
+
+    match (month, day, week) {
+        (None, None, None) => "每天".to_string(),
+        (None, Some(day), None) => format!("每月 {}", day),
+        (None, None, Some(week)) => format!("每{}", week),
+        (None, Some(day), Some(week)) => format!("每月 {}（且为{}）", day, week),
+        (Some(month), None, None) => format!("每年 {}每天", month),
+        (Some(month), Some(day), None) => format!("每年 {} {}", month, day),
+        (Some(month), None, Some(week)) => format!("每年 {}的每{}", month, week),
+        (Some(month), Some(day), Some(week)) => {
+            format!("每年 {} {}（且为{}）", month, day, week)
+        }
+    }
+}
+
+/// 生成 Cron 表达式（6 字段）的中文描述
+pub fn describe(expr: &str) -> String {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    if fields.len() != 6 {
+        return expr.to_string();
+    }
+    let (sec, min, hour) = (fields[0], fields[1], fields[2]);
+    let date = describe_date(fields[3], fields[4], fields[5]);
+    let prefix = if date == "每天" {
+        String::new()
+    } else {
+        format!("{}，", date)
+    };
+
+    let time = match (classify(sec), classify(min), classify(hour)) {
+        (Field::Any, Field::Any, Field::Any) => "每秒执行一次".to_string(),
+        (Field::Step { start: 0, step }, Field::Any, Field::Any) => {
+            format!("每 {} 秒执行一次", step)
+        }
+        (Field::Value(0), Field::Step { start: 0, step }, Field::Any) => {
+            format!("每 {} 分钟执行一次", step)
+        }
+        (Field::Value(0), Field::Value(0), Field::Step { start: 0, step }) => {
+            format!("每 {} 小时执行一次", step)
+        }
+        (Field::Value(0), Field::Any, Field::Any) => "每分钟执行一次".to_string(),
+        (Field::Value(0), Field::Value(0), Field::Any) => "每小时执行一次".to_string(),
+        (Field::Value(s), Field::Value(m), Field::Any) => {
+            format!("每小时的 {:02}:{:02} 执行", m, s)
+        }
+        (Field::Value(s), Field::Value(m), Field::Value(h)) => {
+            return format!("{} {:02}:{:02}:{:02} 执行", date, h, m, s);
+        }
+        (_, Field::Any, Field::Any) => format!("每分钟的{}执行", describe_field(sec, "秒")),
+        (_, _, Field::Any) => format!(
+            "每小时的{}、{}执行",
+            describe_field(min, "分钟"),
+            describe_field(sec, "秒")
+        ),
+        _ => format!(
+            "{}、{}、{}执行",
+            describe_field(hour, "小时"),
+            describe_field(min, "分钟"),
+            describe_field(sec, "秒")
+        ),
+    };
+
+    format!("{}{}", prefix, time)
+}
+
+/// 根据表达式与采样得到的触发时间给出风险提示
+pub fn schedule_warnings(expr: &str, sample: &[DateTime<Utc>]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+
+    if sample.is_empty() {
+        warnings.push("在可预览范围内没有触发时间，请检查日期字段是否可能成立".to_string());
+    }
+
+    if let Some(min_gap) = sample
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_seconds())
+        .min()
+        && min_gap < 60
+    {
+        warnings.push(format!(
+            "执行频率高于每分钟一次（最小间隔 {} 秒），请确认执行器能够承受",
+            min_gap
+        ));
+    }
+
+    if fields.len() == 6 {
+        let (dom, dow) = (fields[3], fields[5]);
+        if classify(dom) != Field::Any && classify(dow) != Field::Any {
+            warnings.push("同时限定了日期和星期，两者需同时满足才会触发".to_string());
+        }
+
+        let late_days: Vec<u32> = dom
+            .split([',', '-'])
+            .filter_map(|part| part.parse::<u32>().ok())
+            .filter(|day| *day >= 29)
+            .collect();
+        if let Some(day) = late_days.first() {
+            warnings.push(format!("部分月份没有第 {} 日，这些月份不会触发", day));
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_interval_expressions() {
+        assert_eq!(describe("*/20 * * * * *"), "每 20 秒执行一次");
+        assert_eq!(describe("0/5 * * * * *"), "每 5 秒执行一次");
+        assert_eq!(describe("0 */10 * * * *"), "每 10 分钟执行一次");
+        assert_eq!(describe("0 0 */6 * * *"), "每 6 小时执行一次");
+        assert_eq!(describe("0 0 * * * *"), "每小时执行一次");
+        assert_eq!(describe("* * * * * *"), "每秒执行一次");
+    }
+
+    #[test]
+    fn test_describe_calendar_expressions() {
+        assert_eq!(describe("0 0 0 * * *"), "每天 00:00:00 执行");
+        assert_eq!(describe("0 0 0 1 * *"), "每月 1 日 00:00:00 执行");
+        assert_eq!(describe("0 30 9 * * 2"), "每周一 09:30:00 执行");
+        assert_eq!(describe("0 0 8 * * 2-6"), "每周一 至 周五 08:00:00 执行");
+        assert_eq!(describe("0 0 0 1 1 *"), "每年 1 月 1 日 00:00:00 执行");
+    }
+
+    #[test]
+    fn test_describe_list_expressions() {
+        assert_eq!(describe("0,30 * * * * *"), "每分钟的第 0、30 秒执行");
+        assert_eq!(describe("0-10 * * * * *"), "每分钟的第 0 至 10 秒执行");
+    }
+
+    #[test]
+    fn test_schedule_warnings_sub_minute() {
+        let start = Utc::now();
+        let sample = vec![start, start + chrono::Duration::seconds(20)];
+
+        let warnings = schedule_warnings("*/20 * * * * *", &sample);
+
+        assert!(warnings.iter().any(|w| w.contains("最小间隔 20 秒")));
+    }
+
+    #[test]
+    fn test_schedule_warnings_day_conflicts() {
+        let warnings = schedule_warnings("0 0 0 31 * 2", &[]);
+
+        assert!(warnings.iter().any(|w| w.contains("没有触发时间")));
+        assert!(warnings.iter().any(|w| w.contains("同时限定了日期和星期")));
+        assert!(warnings.iter().any(|w| w.contains("第 31 日")));
+    }
+}
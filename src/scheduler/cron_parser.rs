@@ -1,14 +1,20 @@
 //! Cron 定时任务模块
 
 use anyhow::Result;
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, TimeZone};
 use cron::Schedule;
 use std::str::FromStr;
 
+use crate::clock::{Clock, SystemClock};
 use crate::error::Error;
+use crate::scheduler::cron_describe;
+
+/// 计算风险提示时采样的触发次数
+const WARNING_SAMPLE_SIZE: usize = 10;
 
 /// Cron 表达式解析器
 pub struct CronParser {
+    expr: String,
     schedule: Schedule,
 }
 
@@ -25,7 +31,59 @@ impl CronParser {
 
         let schedule = Schedule::from_str(expr).map_err(|e| map_cron_error(expr, e.to_string()))?;
 
-        Ok(Self { schedule })
+        Ok(Self {
+            expr: expr.to_string(),
+            schedule,
+        })
+    }
+
+    /// 获取指定时间之后的接下来 `count` 个触发时间（按传入时间的时区计算）
+    pub fn next_triggers<Tz: TimeZone>(
+        &self,
+        after: &DateTime<Tz>,
+        count: usize,
+    ) -> Vec<DateTime<Tz>> {
+        self.schedule.after(after).take(count).collect()
+    }
+
+    /// 表达式的中文描述
+    pub fn describe(&self) -> String {
+        cron_describe::describe(&self.expr)
+    }
+
+    /// 表达式的风险提示（如执行频率高于每分钟一次）
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings_at(SystemClock.now())
+    }
+
+    /// 以指定时间为起点采样触发时间，计算风险提示
+    pub fn warnings_at(&self, now: DateTime<chrono::Utc>) -> Vec<String> {
+        let now = now.with_timezone(&Local);
+        let sample: Vec<DateTime<chrono::Utc>> = self
+            .next_triggers(&now, WARNING_SAMPLE_SIZE)
+            .into_iter()
+            .map(|t| t.with_timezone(&chrono::Utc))
+            .collect();
+        cron_describe::schedule_warnings(&self.expr, &sample)
+    }
+
+    /// 获取 `[from, to)` 区间内的触发时间（按本地时区计算），最多返回 `limit` 个
+    pub fn triggers_between(
+        &self,
+        from: DateTime<chrono::Utc>,
+        to: DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> Vec<DateTime<chrono::Utc>> {
+        let from_local = from.with_timezone(&Local);
+        let first = self.schedule.includes(from_local).then_some(from_local);
+
+        first
+            .into_iter()
+            .chain(self.schedule.after(&from_local))
+            .map(|t| t.with_timezone(&chrono::Utc))
+            .take_while(|t| *t < to)
+            .take(limit)
+            .collect()
     }
 
     /// 获取在指定时间窗口内的所有触发时间
@@ -34,13 +92,13 @@ impl CronParser {
         start: DateTime<chrono::Utc>,
         end: DateTime<chrono::Utc>,
     ) -> Vec<DateTime<chrono::Utc>> {
-        let local_offset = *Local::now().offset();
-        let mut current = start.with_timezone(&local_offset);
-        let end_fixed = end.with_timezone(&local_offset);
+        // 按本地时区计算，窗口跨越夏令时切换时使用各时刻各自的偏移
+        let mut current = start.with_timezone(&Local);
+        let end_local = end.with_timezone(&Local);
         let mut triggers = Vec::new();
 
         while let Some(next) = self.schedule.after(&current).next() {
-            if next > end_fixed {
+            if next > end_local {
                 break;
             }
 
@@ -145,22 +203,27 @@ mod tests {
         }
     }
 
+    fn minute_start() -> DateTime<chrono::Utc> {
+        chrono::Utc.with_ymd_and_hms(2025, 7, 15, 12, 0, 0).unwrap()
+    }
+
     #[test]
     fn test_next_triggers_in_window_multiple_triggers() {
         let parser = CronParser::new("0/10 * * * * *").unwrap();
-        let start = chrono::Utc::now();
+        let start = minute_start();
         let end = start + chrono::Duration::seconds(60);
 
         let triggers = parser.next_triggers_in_window(start, end);
 
-        assert!(!triggers.is_empty(), "应该有触发时间");
-        assert!(triggers.len() <= 7, "触发时间数量应该在合理范围内");
+        assert_eq!(triggers.len(), 6);
+        assert_eq!(triggers[0], start + chrono::Duration::seconds(10));
+        assert_eq!(triggers[5], end);
     }
 
     #[test]
     fn test_next_triggers_in_window_no_triggers() {
         let parser = CronParser::new("0 0 0 1 1 *").unwrap();
-        let start = chrono::Utc::now();
+        let start = minute_start();
         let end = start + chrono::Duration::seconds(60);
 
         let triggers = parser.next_triggers_in_window(start, end);
@@ -171,36 +234,42 @@ mod tests {
     #[test]
     fn test_next_triggers_in_window_boundary() {
         let parser = CronParser::new("0 * * * * *").unwrap();
-        let start = chrono::Utc::now();
+        let start = minute_start();
         let end = start + chrono::Duration::seconds(59);
 
         let triggers = parser.next_triggers_in_window(start, end);
+        assert!(triggers.is_empty(), "窗口不包含起点");
+
+        let start = start + chrono::Duration::seconds(30);
+        let end = start + chrono::Duration::seconds(59);
 
-        assert!(triggers.len() <= 1, "边界情况下最多一个触发时间");
+        let triggers = parser.next_triggers_in_window(start, end);
+        assert_eq!(
+            triggers,
+            vec![minute_start() + chrono::Duration::minutes(1)]
+        );
     }
 
     #[test]
     fn test_next_triggers_in_window_complex_expression() {
         let parser = CronParser::new("0,30 * * * * *").unwrap();
-        let start = chrono::Utc::now();
+        let start = minute_start();
         let end = start + chrono::Duration::minutes(2);
 
         let triggers = parser.next_triggers_in_window(start, end);
 
-        assert!(!triggers.is_empty(), "复杂表达式应该有触发时间");
-        assert!(triggers.len() <= 5, "触发时间数量应该在合理范围内");
+        assert_eq!(triggers.len(), 4);
     }
 
     #[test]
     fn test_next_triggers_in_window_every_second() {
         let parser = CronParser::new("* * * * * *").unwrap();
-        let start = chrono::Utc::now();
+        let start = minute_start();
         let end = start + chrono::Duration::seconds(10);
 
         let triggers = parser.next_triggers_in_window(start, end);
 
-        assert!(!triggers.is_empty(), "每秒执行应该有触发时间");
-        assert!(triggers.len() <= 11, "触发时间数量应该在合理范围内");
+        assert_eq!(triggers.len(), 10);
     }
 
     #[test]
@@ -1,8 +1,13 @@
+use crate::clock::{SharedClock, SystemClock};
 use crate::error::{Error, Result};
 use crate::executor::TaskQueue;
 use crate::scheduler::cron_parser::CronParser;
+use crate::scheduler::pause::{PauseSet, cancel_queued_instances};
+use crate::scheduler::rate_limit::{DeferReason, DispatchLimiter};
 use crate::storage::mongo::MongoDataSource;
-use crate::types::{DispatchLog, Task, TaskInstance, TaskStatus};
+use crate::types::{
+    DeferredSlot, DispatchLog, DispatchTaskDetail, MAX_TASK_PRIORITY, Task, TaskInstance, TaskStatus,
+};
 use crate::config::SchedulingPolicyConfig;
 use chrono::{DateTime, Utc};
 use mongodb::bson::{doc, oid::ObjectId};
@@ -23,14 +28,36 @@ pub struct Dispatcher {
     scan_interval: Duration,
     log_retention_days: u32,
     scheduling: SchedulingPolicyConfig,
+    limits: DispatchLimits,
+    /// 因限流或消息队列不可用推迟、等待后续扫描重新分发的候选实例
+    deferred: Arc<RwLock<Vec<DispatchCandidate>>>,
+    clock: SharedClock,
+}
+
+/// 分发限流配置
+#[derive(Debug, Clone, Copy)]
+pub struct DispatchLimits {
+    /// 全局在途实例上限，0 表示不限制
+    pub max_concurrent_tasks: usize,
+    /// 被推迟的候选实例最长保留时间（秒）
+    pub max_defer_secs: u64,
 }
 
 #[derive(Debug, Clone)]
-struct DispatchCandidate {
-    task_id: ObjectId,
-    task_name: String,
-    scheduled_time: DateTime<Utc>,
-    score: f64,
+pub(crate) struct DispatchCandidate {
+    pub(crate) task_id: ObjectId,
+    pub(crate) task_name: String,
+    pub(crate) scheduled_time: DateTime<Utc>,
+    pub(crate) priority: i32,
+    pub(crate) score: f64,
+}
+
+/// 单个任务在一次扫描中收集到的候选实例
+#[derive(Debug, Default)]
+pub(crate) struct TaskCandidates {
+    pub(crate) candidates: Vec<DispatchCandidate>,
+    /// 已存在实例而跳过的调度时间数
+    pub(crate) duplicates: usize,
 }
 
 impl Dispatcher {
@@ -40,18 +67,30 @@ impl Dispatcher {
         scan_interval_secs: u64,
         log_retention_days: u32,
         scheduling: SchedulingPolicyConfig,
+        limits: DispatchLimits,
     ) -> Self {
+        let clock: SharedClock = Arc::new(SystemClock);
         Self {
             db,
             task_queue,
             running: Arc::new(RwLock::new(false)),
-            last_scan_end_time: Arc::new(RwLock::new(Utc::now())),
+            last_scan_end_time: Arc::new(RwLock::new(clock.now())),
             scan_interval: Duration::from_secs(scan_interval_secs),
             log_retention_days,
             scheduling,
+            limits,
+            deferred: Arc::new(RwLock::new(Vec::new())),
+            clock,
         }
     }
 
+    /// 使用指定的时钟（默认为系统时钟）
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.last_scan_end_time = Arc::new(RwLock::new(clock.now()));
+        self.clock = clock;
+        self
+    }
+
     /// 启动分发器
     pub async fn start(&self) -> Result<()> {
         let mut running = self.running.write().await;
@@ -78,6 +117,25 @@ impl Dispatcher {
                 "[Dispatcher] 从数据库恢复上次扫描结束时间: {}",
                 last_log.scan_window_end.format("%H:%M:%S")
             );
+
+            // 恢复上次推迟的候选实例，优先级与评分在下次扫描时重新计算
+            if !last_log.deferred_slots.is_empty() {
+                info!(
+                    "[Dispatcher] 从数据库恢复 {} 个推迟的候选实例",
+                    last_log.deferred_slots.len()
+                );
+                *self.deferred.write().await = last_log
+                    .deferred_slots
+                    .into_iter()
+                    .map(|slot| DispatchCandidate {
+                        task_id: slot.task_id,
+                        task_name: slot.task_name,
+                        scheduled_time: slot.scheduled_time,
+                        priority: 0,
+                        score: 0.0,
+                    })
+                    .collect();
+            }
         }
 
         // 启动时做一次全局去重，与上次扫描时间无关
@@ -95,6 +153,10 @@ impl Dispatcher {
         let log_retention_days = self.log_retention_days;
         let last_scan_end_time = Arc::clone(&self.last_scan_end_time);
         let scheduling = self.scheduling.clone();
+        let limits = self.limits;
+        let deferred = Arc::clone(&self.deferred);
+        let clock = Arc::clone(&self.clock);
+        let clock_cleanup = Arc::clone(&self.clock);
 
         tokio::spawn(async move {
             let mut timer = tokio::time::interval(interval);
@@ -102,12 +164,18 @@ impl Dispatcher {
             while *running_flag.read().await {
                 timer.tick().await;
 
+                let now = clock.now();
+                let scan_window =
+                    Self::calculate_scan_window(now, scan_interval_secs, &last_scan_end_time).await;
+
                 match Self::scan_and_dispatch(
                     &db,
                     &task_queue,
-                    scan_interval_secs,
-                    &last_scan_end_time,
+                    now,
+                    scan_window,
                     &scheduling,
+                    limits,
+                    &deferred,
                 )
                 .await
                 {
@@ -128,7 +196,10 @@ impl Dispatcher {
             while *running_flag_cleanup.read().await {
                 cleanup_timer.tick().await;
 
-                if let Err(e) = Self::cleanup_old_logs(&db_cleanup, log_retention_days).await {
+                if let Err(e) =
+                    Self::cleanup_old_logs(&db_cleanup, log_retention_days, clock_cleanup.now())
+                        .await
+                {
                     error!("[Dispatcher] 清理旧日志失败: {}", e);
                 }
             }
@@ -166,14 +237,14 @@ impl Dispatcher {
     async fn scan_and_dispatch(
         db: &Arc<MongoDataSource>,
         task_queue: &Arc<TaskQueue>,
-        scan_interval_secs: u64,
-        last_scan_end_time: &Arc<RwLock<DateTime<Utc>>>,
+        now: DateTime<Utc>,
+        (scan_window_start, scan_window_end): (DateTime<Utc>, DateTime<Utc>),
         scheduling: &SchedulingPolicyConfig,
+        limits: DispatchLimits,
+        deferred: &Arc<RwLock<Vec<DispatchCandidate>>>,
     ) -> Result<usize> {
-        let now = Utc::now();
-
-        let (scan_window_start, scan_window_end) =
-            Self::calculate_scan_window(now, scan_interval_secs, last_scan_end_time).await;
+        // 取出上次扫描推迟的候选实例，任务已停用或暂停的不再保留
+        let carried = std::mem::take(&mut *deferred.write().await);
 
         info!(
             "[Dispatcher] 开始扫描任务，窗口: {} 到 {}",
@@ -194,6 +265,30 @@ impl Dispatcher {
             .map_err(|e| Error::Database(format!("查询任务失败: {}", e)))?;
         let total_tasks = enabled_tasks.len() as i32;
 
+        // 处理生效中的暂停：按配置取消排队中的实例，并跳过暂停范围内的任务
+        let active_pauses = db
+            .find_active_pauses(now)
+            .await
+            .map_err(|e| Error::Database(format!("查询暂停失败: {}", e)))?;
+        for pause in &active_pauses {
+            if let Err(e) = cancel_queued_instances(db, pause, now).await {
+                error!("[Dispatcher] 处理暂停的排队实例失败: {}", e);
+            }
+        }
+        let pause_set = PauseSet::from_pauses(&active_pauses);
+        let (paused_tasks, enabled_tasks): (Vec<Task>, Vec<Task>) = enabled_tasks
+            .into_iter()
+            .partition(|task| pause_set.is_paused(task));
+        if !paused_tasks.is_empty() {
+            info!(
+                "[Dispatcher] {} 个任务处于暂停中，本次不分发",
+                paused_tasks.len()
+            );
+        }
+
+        let paused_count = paused_tasks.len() as i32;
+        let enabled_count = enabled_tasks.len() as i32;
+
         if enabled_tasks.is_empty() {
             debug!("[Dispatcher] 没有启用的任务");
 
@@ -202,10 +297,15 @@ impl Dispatcher {
                 scan_time: now,
                 scan_window_start,
                 scan_window_end,
-                total_tasks: 0,
+                total_tasks,
                 enabled_tasks: 0,
+                paused_tasks: paused_count,
                 dispatched_instances: 0,
+                failed_tasks: 0,
+                deferred_instances: 0,
                 error_message: None,
+                task_details: Vec::new(),
+                deferred_slots: Vec::new(),
             };
 
             if let Err(e) = db.create_dispatch_log(&dispatch_log).await {
@@ -242,95 +342,207 @@ impl Dispatcher {
                 .insert(instance.scheduled_time.timestamp());
         }
 
+        let mut carried_map = Self::group_carried_candidates(carried, &now, limits.max_defer_secs);
+
         let mut all_candidates: Vec<DispatchCandidate> = Vec::new();
+        let mut task_details: Vec<DispatchTaskDetail> = Vec::new();
+        let mut detail_index: HashMap<ObjectId, usize> = HashMap::new();
 
         for task in &enabled_tasks {
             if let Some(task_id) = task.id {
+                let mut detail = DispatchTaskDetail {
+                    task_id,
+                    task_name: task.name.clone(),
+                    candidates: 0,
+                    duplicates: 0,
+                    dispatched: 0,
+                    failed: 0,
+                    deferred: 0,
+                    defer_reason: None,
+                    scores: Vec::new(),
+                    error: None,
+                };
                 match Self::collect_task_candidates(
-                    &task,
+                    task,
                     &now,
                     &scan_window_end,
                     existing_instances_map.get(&task_id),
                     scheduling,
-                )
-                .await
-                {
-                    Ok(candidates) => {
-                        if !candidates.is_empty() {
+                ) {
+                    Ok(collected) => {
+                        if !collected.candidates.is_empty() {
                             info!(
                                 "[Dispatcher] 任务 {} 生成 {} 个候选实例（待优先级排序）",
                                 task.name,
-                                candidates.len()
+                                collected.candidates.len()
                             );
                         }
-                        all_candidates.extend(candidates);
+                        detail.candidates = collected.candidates.len() as i32;
+                        detail.duplicates = collected.duplicates as i32;
+                        detail.scores = collected.candidates.iter().map(|c| c.score).collect();
+                        all_candidates.extend(collected.candidates);
                     }
                     Err(e) => {
                         error!("[Dispatcher] 处理任务 {} 失败: {}", task.name, e);
+                        detail.error = Some(e.to_string());
                     }
                 }
+                if let Some(carried) = carried_map.remove(&task_id) {
+                    let collected: HashSet<i64> = all_candidates
+                        .iter()
+                        .filter(|candidate| candidate.task_id == task_id)
+                        .map(|candidate| candidate.scheduled_time.timestamp())
+                        .collect();
+                    let existing = existing_instances_map.get(&task_id);
+                    for mut candidate in carried {
+                        let scheduled_ts = candidate.scheduled_time.timestamp();
+                        if collected.contains(&scheduled_ts)
+                            || existing.is_some_and(|times| times.contains(&scheduled_ts))
+                        {
+                            continue;
+                        }
+                        candidate.priority = task.effective_priority();
+                        candidate.score = Self::calculate_priority_score(
+                            task,
+                            &now,
+                            &candidate.scheduled_time,
+                            scheduling,
+                        );
+                        detail.candidates += 1;
+                        detail.scores.push(candidate.score);
+                        all_candidates.push(candidate);
+                    }
+                }
+                if detail.candidates > 0 || detail.duplicates > 0 || detail.error.is_some() {
+                    detail_index.insert(task_id, task_details.len());
+                    task_details.push(detail);
+                }
             }
         }
 
-        // I-EDF: 先按分数降序，再按调度时间升序，最后按任务名稳定排序
-        all_candidates.sort_by(|a, b| {
-            b.score
-                .total_cmp(&a.score)
-                .then_with(|| a.scheduled_time.cmp(&b.scheduled_time))
-                .then_with(|| a.task_name.cmp(&b.task_name))
-        });
-
-        let mut dispatched_count = 0;
-        for candidate in all_candidates {
-            let instance = TaskInstance {
-                id: None,
-                task_id: candidate.task_id,
-                scheduled_time: candidate.scheduled_time,
-                status: TaskStatus::Pending,
-                executor_id: None,
-                start_time: None,
-                end_time: None,
-                retry_count: 0,
-                result: None,
-                triggered_by: crate::types::TriggeredBy::Scheduler,
-                created_at: now,
-            };
+        let dropped: usize = carried_map.values().map(Vec::len).sum();
+        if dropped > 0 {
+            info!(
+                "[Dispatcher] {} 个推迟的候选实例所属任务已停用或暂停，不再分发",
+                dropped
+            );
+        }
 
-            let instance_id = db
-                .create_task_instance(&instance)
-                .await
-                .map_err(|e| Error::Database(format!("创建任务实例失败: {}", e)))?;
+        Self::sort_candidates(&mut all_candidates);
 
-            let task_msg = crate::executor::TaskMessage {
-                instance_id,
-                task_id: candidate.task_id,
-                task_name: candidate.task_name.clone(),
-                scheduled_time: candidate.scheduled_time.timestamp(),
-                retry_count: 0,
-                triggered_by: crate::types::TriggeredBy::Scheduler,
-            };
+        let mut limiter = if all_candidates.is_empty() {
+            DispatchLimiter::default()
+        } else {
+            DispatchLimiter::load(db, limits.max_concurrent_tasks, &enabled_tasks, now).await?
+        };
 
-            task_queue
-                .publish_task(task_msg)
-                .await
-                .map_err(|e| Error::MessageQueue(format!("发布任务到队列失败: {}", e)))?;
+        let mut dispatched_count = 0;
+        let mut deferred_candidates = Vec::new();
+        let mut queue_error = None;
+        let mut remaining = all_candidates.into_iter();
+        for candidate in remaining.by_ref() {
+            // 按策略顺序处理，超出限制的候选实例推迟到后续扫描
+            if let Some(reason) = limiter.check(&candidate) {
+                debug!(
+                    "[Dispatcher] 推迟任务 {} 在 {} 的实例: {}",
+                    candidate.task_name, candidate.scheduled_time, reason
+                );
+                Self::defer_candidate(
+                    &mut task_details,
+                    &detail_index,
+                    &mut deferred_candidates,
+                    candidate,
+                    reason,
+                );
+                continue;
+            }
+            let detail = detail_index
+                .get(&candidate.task_id)
+                .map(|&index| &mut task_details[index]);
+            match Self::dispatch_candidate(db, task_queue, &candidate, &now).await {
+                Ok(()) => {
+                    limiter.record_dispatched(&candidate);
+                    if let Some(detail) = detail {
+                        detail.dispatched += 1;
+                    }
+                    dispatched_count += 1;
+                }
+                Err(e @ Error::MessageQueue(_)) => {
+                    // 队列不可用时停止本次分发，当前及剩余候选实例推迟到下次扫描
+                    error!(
+                        "[Dispatcher] 发布任务 {} 在 {} 的实例失败，停止本次分发: {}",
+                        candidate.task_name, candidate.scheduled_time, e
+                    );
+                    queue_error = Some(e.to_string());
+                    Self::defer_candidate(
+                        &mut task_details,
+                        &detail_index,
+                        &mut deferred_candidates,
+                        candidate,
+                        DeferReason::QueueUnavailable,
+                    );
+                    break;
+                }
+                Err(e) => {
+                    error!(
+                        "[Dispatcher] 分发任务 {} 在 {} 的实例失败: {}",
+                        candidate.task_name, candidate.scheduled_time, e
+                    );
+                    if let Some(detail) = detail {
+                        detail.failed += 1;
+                        detail.error = Some(e.to_string());
+                    }
+                }
+            }
+        }
 
-            debug!(
-                "分发任务 {} 实例 {}，计划执行时间: {}，score={:.4}",
-                candidate.task_name, instance_id, candidate.scheduled_time, candidate.score
+        // 仅在队列不可用中断分发时有剩余候选实例
+        for candidate in remaining {
+            Self::defer_candidate(
+                &mut task_details,
+                &detail_index,
+                &mut deferred_candidates,
+                candidate,
+                DeferReason::QueueUnavailable,
             );
-            dispatched_count += 1;
         }
-
+        let deferred_count = deferred_candidates.len();
+        let deferred_slots = deferred_candidates
+            .iter()
+            .map(|candidate| DeferredSlot {
+                task_id: candidate.task_id,
+                task_name: candidate.task_name.clone(),
+                scheduled_time: candidate.scheduled_time,
+            })
+            .collect();
+        *deferred.write().await = deferred_candidates;
+
+        let failed_tasks = task_details
+            .iter()
+            .filter(|detail| detail.error.is_some())
+            .count();
+        // 队列错误不计入失败任务，但仍写入错误消息
+        let error_message = match (queue_error, Self::summarize_failures(&task_details)) {
+            (Some(queue), Some(failures)) => {
+                Some(format!("消息队列不可用: {}; {}", queue, failures))
+            }
+            (Some(queue), None) => Some(format!("消息队列不可用: {}", queue)),
+            (None, failures) => failures,
+        };
         let dispatch_log = DispatchLog {
             id: None,
             scan_time: now,
             scan_window_start,
             scan_window_end,
             total_tasks,
-            enabled_tasks: total_tasks,
+            enabled_tasks: enabled_count,
+            paused_tasks: paused_count,
             dispatched_instances: dispatched_count as i32,
-            error_message: None,
+            failed_tasks: failed_tasks as i32,
+            deferred_instances: deferred_count as i32,
+            error_message,
+            task_details,
+            deferred_slots,
         };
 
         if let Err(e) = db.create_dispatch_log(&dispatch_log).await {
@@ -338,13 +550,145 @@ impl Dispatcher {
         }
 
         info!(
-            "[Dispatcher] 扫描完成 - 总任务: {}, 启用: {}, 分发实例: {}",
-            total_tasks, total_tasks, dispatched_count
+            "[Dispatcher] 扫描完成 - 总任务: {}, 启用: {}, 暂停: {}, 分发实例: {}, 推迟实例: {}, 失败任务: {}",
+            total_tasks,
+            enabled_count,
+            paused_count,
+            dispatched_count,
+            deferred_count,
+            failed_tasks
         );
 
         Ok(dispatched_count)
     }
 
+    /// 为候选实例创建任务实例并发布到队列，发布失败时删除刚创建的实例
+    async fn dispatch_candidate(
+        db: &Arc<MongoDataSource>,
+        task_queue: &Arc<TaskQueue>,
+        candidate: &DispatchCandidate,
+        now: &DateTime<Utc>,
+    ) -> Result<()> {
+        let instance = TaskInstance {
+            id: None,
+            task_id: candidate.task_id,
+            scheduled_time: candidate.scheduled_time,
+            status: TaskStatus::Pending,
+            executor_id: None,
+            start_time: None,
+            end_time: None,
+            retry_count: 0,
+            result: None,
+            triggered_by: crate::types::TriggeredBy::Scheduler,
+            priority_score: Some(candidate.score),
+            sla_flags: Vec::new(),
+            trigger_payload: None,
+            parameters: None,
+            created_at: *now,
+        };
+
+        let instance_id = db
+            .create_task_instance(&instance)
+            .await
+            .map_err(|e| Error::Database(format!("创建任务实例失败: {}", e)))?;
+
+        let task_msg = crate::executor::TaskMessage {
+            instance_id,
+            task_id: candidate.task_id,
+            task_name: candidate.task_name.clone(),
+            scheduled_time: candidate.scheduled_time.timestamp(),
+            retry_count: 0,
+            triggered_by: crate::types::TriggeredBy::Scheduler,
+            priority: candidate.priority as u8,
+        };
+
+        if let Err(e) = task_queue.publish_task(task_msg, *now).await {
+            if let Err(delete_err) = db.delete_task_instance(instance_id).await {
+                error!(
+                    "[Dispatcher] 删除未发布的任务实例 {} 失败: {}",
+                    instance_id, delete_err
+                );
+            }
+            return Err(Error::MessageQueue(format!("发布任务到队列失败: {}", e)));
+        }
+
+        debug!(
+            "分发任务 {} 实例 {}，计划执行时间: {}，priority={}，score={:.4}",
+            candidate.task_name,
+            instance_id,
+            candidate.scheduled_time,
+            candidate.priority,
+            candidate.score
+        );
+
+        Ok(())
+    }
+
+    /// 按任务分组上次推迟的候选实例，丢弃推迟时间超过 `max_defer_secs` 的
+    fn group_carried_candidates(
+        carried: Vec<DispatchCandidate>,
+        now: &DateTime<Utc>,
+        max_defer_secs: u64,
+    ) -> HashMap<ObjectId, Vec<DispatchCandidate>> {
+        let cutoff = *now - chrono::Duration::seconds(max_defer_secs as i64);
+        let mut grouped: HashMap<ObjectId, Vec<DispatchCandidate>> = HashMap::new();
+        let mut expired = 0;
+        for candidate in carried {
+            if candidate.scheduled_time < cutoff {
+                expired += 1;
+                continue;
+            }
+            grouped
+                .entry(candidate.task_id)
+                .or_default()
+                .push(candidate);
+        }
+        if expired > 0 {
+            warn!(
+                "[Dispatcher] {} 个候选实例推迟超过 {} 秒，放弃本次触发",
+                expired, max_defer_secs
+            );
+        }
+        grouped
+    }
+
+    /// 将候选实例推迟到后续扫描，并在所属任务的明细中记录推迟数与原因
+    fn defer_candidate(
+        task_details: &mut [DispatchTaskDetail],
+        detail_index: &HashMap<ObjectId, usize>,
+        deferred_candidates: &mut Vec<DispatchCandidate>,
+        candidate: DispatchCandidate,
+        reason: DeferReason,
+    ) {
+        if let Some(&index) = detail_index.get(&candidate.task_id) {
+            let detail = &mut task_details[index];
+            detail.deferred += 1;
+            detail.defer_reason = Some(reason.to_string());
+        }
+        deferred_candidates.push(candidate);
+    }
+
+    /// 汇总本次扫描中失败的任务，全部成功时返回 `None`
+    pub(crate) fn summarize_failures(details: &[DispatchTaskDetail]) -> Option<String> {
+        let failures: Vec<String> = details
+            .iter()
+            .filter_map(|detail| {
+                detail
+                    .error
+                    .as_ref()
+                    .map(|error| format!("{}: {}", detail.task_name, error))
+            })
+            .collect();
+        if failures.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "{} 个任务处理失败 - {}",
+            failures.len(),
+            failures.join("; ")
+        ))
+    }
+
     /// 启动时对所有待执行实例做一次去重（无关上次扫描时间）
     async fn check_and_dedup_instances(db: &Arc<MongoDataSource>) -> Result<()> {
         let all_existing_instances = db
@@ -395,14 +739,24 @@ impl Dispatcher {
         Ok(())
     }
 
+    /// I-EDF: 先按分数降序，再按调度时间升序，最后按任务名稳定排序
+    pub(crate) fn sort_candidates(candidates: &mut [DispatchCandidate]) {
+        candidates.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.scheduled_time.cmp(&b.scheduled_time))
+                .then_with(|| a.task_name.cmp(&b.task_name))
+        });
+    }
+
     /// 为任务收集候选实例，不直接分发（用于全局优先级排序）
-    async fn collect_task_candidates(
+    pub(crate) fn collect_task_candidates(
         task: &Task,
         now: &DateTime<Utc>,
         scan_window_end: &DateTime<Utc>,
         existing_instances: Option<&std::collections::HashSet<i64>>,
         scheduling: &SchedulingPolicyConfig,
-    ) -> Result<Vec<DispatchCandidate>> {
+    ) -> Result<TaskCandidates> {
         let task_id = task
             .id
             .ok_or_else(|| Error::Validation("任务 ID 不能为空".to_string()))?;
@@ -412,14 +766,14 @@ impl Dispatcher {
         let next_triggers = cron_parser.next_triggers_in_window(*now, *scan_window_end);
 
         if next_triggers.is_empty() {
-            return Ok(Vec::new());
+            return Ok(TaskCandidates::default());
         }
 
         // 使用传入的已存在实例集合，避免重复查询数据库
         let empty_set = std::collections::HashSet::new();
         let existing_scheduled_times = existing_instances.unwrap_or(&empty_set);
 
-        let mut candidates = Vec::new();
+        let mut collected = TaskCandidates::default();
 
         for scheduled_time in next_triggers {
             let scheduled_timestamp = scheduled_time.timestamp();
@@ -429,29 +783,40 @@ impl Dispatcher {
                     "任务 {} 在 {} 的实例已存在，跳过",
                     task.name, scheduled_time
                 );
+                collected.duplicates += 1;
                 continue;
             }
             let score = Self::calculate_priority_score(task, now, &scheduled_time, scheduling);
-            candidates.push(DispatchCandidate {
+            collected.candidates.push(DispatchCandidate {
                 task_id,
                 task_name: task.name.clone(),
                 scheduled_time,
+                priority: task.effective_priority(),
                 score,
             });
         }
 
-        Ok(candidates)
+        Ok(collected)
     }
 
-    /// 改进 EDF 分数：Urgency + Aging - RetryPenalty
+    /// 改进 EDF 分数：Urgency + Priority + Aging - RetryPenalty
+    ///
+    /// - Urgency：距截止期限（`scheduled_time + deadline_seconds`）越近分数越高
+    /// - Priority：任务业务优先级归一化到 [0, 1]
+    /// - Aging：任务存在越久分数越高，避免低优先级任务长期饥饿
+    /// - RetryPenalty：`max_retries` 越大扣分越多，权重默认为 0
     fn calculate_priority_score(
         task: &Task,
         now: &DateTime<Utc>,
         scheduled_time: &DateTime<Utc>,
         scheduling: &SchedulingPolicyConfig,
     ) -> f64 {
-        let deadline_secs = (*scheduled_time - *now).num_seconds().max(0) as f64;
-        let urgency = 1.0 / (1.0 + deadline_secs);
+        let deadline = *scheduled_time
+            + chrono::Duration::seconds(task.deadline_seconds.unwrap_or(0).max(0) as i64);
+        let slack_secs = (deadline - *now).num_seconds().max(0) as f64;
+        let urgency = 1.0 / (1.0 + slack_secs);
+
+        let priority = task.effective_priority() as f64 / MAX_TASK_PRIORITY as f64;
 
         let age_secs = (*now - task.created_at).num_seconds().max(0) as f64;
         let aging_window = scheduling.aging_window_secs.max(1) as f64;
@@ -459,13 +824,19 @@ impl Dispatcher {
 
         let retry_penalty = task.max_retries.unwrap_or(0).max(0) as f64 / 10.0;
 
-        scheduling.urgency_weight * urgency + scheduling.aging_weight * aging
+        scheduling.urgency_weight * urgency
+            + scheduling.priority_weight * priority
+            + scheduling.aging_weight * aging
             - scheduling.retry_penalty_weight * retry_penalty
     }
 
     /// 清理旧日志
-    async fn cleanup_old_logs(db: &Arc<MongoDataSource>, retention_days: u32) -> Result<()> {
-        let cutoff_time = Utc::now() - chrono::Duration::days(retention_days as i64);
+    async fn cleanup_old_logs(
+        db: &Arc<MongoDataSource>,
+        retention_days: u32,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let cutoff_time = now - chrono::Duration::days(retention_days as i64);
 
         debug!("开始清理 {} 天前的调度日志", retention_days);
 
@@ -481,3 +852,281 @@ impl Dispatcher {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use chrono::TimeZone;
+
+    fn fixed_now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 7, 15, 12, 0, 0).unwrap()
+    }
+
+    fn make_task(priority: Option<i32>, deadline_seconds: Option<i32>) -> Task {
+        Task {
+            priority,
+            deadline_seconds,
+            created_at: fixed_now(),
+            updated_at: fixed_now(),
+            ..Task::for_test("score-task")
+        }
+    }
+
+    #[test]
+    fn test_priority_score_prefers_higher_priority() {
+        let scheduling = SchedulingPolicyConfig::default();
+        let now = fixed_now();
+        let scheduled_time = now + chrono::Duration::seconds(10);
+
+        let low = Dispatcher::calculate_priority_score(
+            &make_task(Some(1), None),
+            &now,
+            &scheduled_time,
+            &scheduling,
+        );
+        let high = Dispatcher::calculate_priority_score(
+            &make_task(Some(9), None),
+            &now,
+            &scheduled_time,
+            &scheduling,
+        );
+
+        assert!(high > low, "高优先级任务分数应更高");
+    }
+
+    #[test]
+    fn test_priority_score_prefers_tighter_deadline() {
+        let scheduling = SchedulingPolicyConfig::default();
+        let now = fixed_now();
+        let scheduled_time = now + chrono::Duration::seconds(10);
+
+        let relaxed = Dispatcher::calculate_priority_score(
+            &make_task(Some(5), Some(600)),
+            &now,
+            &scheduled_time,
+            &scheduling,
+        );
+        let tight = Dispatcher::calculate_priority_score(
+            &make_task(Some(5), Some(5)),
+            &now,
+            &scheduled_time,
+            &scheduling,
+        );
+
+        assert!(tight > relaxed, "截止期限越近分数应越高");
+    }
+
+    #[test]
+    fn test_retry_penalty_weight() {
+        let now = fixed_now();
+        let scheduled_time = now + chrono::Duration::seconds(10);
+        let mut task = make_task(Some(5), None);
+        task.max_retries = Some(5);
+
+        let default = SchedulingPolicyConfig::default();
+        let penalized = SchedulingPolicyConfig {
+            retry_penalty_weight: 0.05,
+            ..SchedulingPolicyConfig::default()
+        };
+        let without_penalty =
+            Dispatcher::calculate_priority_score(&task, &now, &scheduled_time, &default);
+        let with_penalty =
+            Dispatcher::calculate_priority_score(&task, &now, &scheduled_time, &penalized);
+
+        assert!((without_penalty - with_penalty - 0.025).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_collect_task_candidates_counts_duplicates() {
+        let scheduling = SchedulingPolicyConfig::default();
+        let mut task = make_task(None, None);
+        task.schedule = "*/10 * * * * *".to_string();
+        let now = fixed_now();
+        let window_end = now + chrono::Duration::seconds(60);
+
+        let all = Dispatcher::collect_task_candidates(&task, &now, &window_end, None, &scheduling)
+            .unwrap();
+        let existing: HashSet<i64> = all
+            .candidates
+            .iter()
+            .take(2)
+            .map(|candidate| candidate.scheduled_time.timestamp())
+            .collect();
+        let collected = Dispatcher::collect_task_candidates(
+            &task,
+            &now,
+            &window_end,
+            Some(&existing),
+            &scheduling,
+        )
+        .unwrap();
+
+        assert_eq!(all.candidates.len(), 6);
+        assert_eq!(collected.duplicates, 2);
+        assert_eq!(collected.candidates.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_scan_window_follows_clock() {
+        let clock = ManualClock::new(fixed_now());
+        let last_scan_end_time = Arc::new(RwLock::new(clock.now()));
+
+        let first = Dispatcher::calculate_scan_window(clock.now(), 30, &last_scan_end_time).await;
+        assert_eq!(
+            first,
+            (fixed_now(), fixed_now() + chrono::Duration::seconds(30))
+        );
+
+        // 扫描延迟时，下一个窗口从上次窗口终点开始，终点随时钟推进
+        clock.advance(chrono::Duration::seconds(45));
+        let second = Dispatcher::calculate_scan_window(clock.now(), 30, &last_scan_end_time).await;
+        assert_eq!(
+            second,
+            (
+                fixed_now() + chrono::Duration::seconds(30),
+                fixed_now() + chrono::Duration::seconds(75)
+            )
+        );
+    }
+
+    #[test]
+    fn test_candidate_aging_follows_clock() {
+        let scheduling = SchedulingPolicyConfig {
+            urgency_weight: 0.0,
+            priority_weight: 0.0,
+            aging_weight: 1.0,
+            ..SchedulingPolicyConfig::default()
+        };
+        let task = make_task(None, None);
+        let clock = ManualClock::new(fixed_now());
+        let first_score = |clock: &ManualClock| {
+            let now = clock.now();
+            let window_end = now + chrono::Duration::seconds(60);
+            Dispatcher::collect_task_candidates(&task, &now, &window_end, None, &scheduling)
+                .unwrap()
+                .candidates[0]
+                .score
+        };
+
+        assert_eq!(first_score(&clock), 0.0);
+        clock.advance(chrono::Duration::minutes(30));
+        assert!((first_score(&clock) - 0.5).abs() < 1e-9);
+        // 超过 aging_window_secs 后不再增加
+        clock.advance(chrono::Duration::hours(2));
+        assert!((first_score(&clock) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_group_carried_candidates_drops_expired() {
+        let task = make_task(None, None);
+        let now = fixed_now();
+        let candidate = |secs_ago: i64| DispatchCandidate {
+            task_id: task.id.unwrap(),
+            task_name: task.name.clone(),
+            scheduled_time: now - chrono::Duration::seconds(secs_ago),
+            priority: 0,
+            score: 0.0,
+        };
+
+        let grouped =
+            Dispatcher::group_carried_candidates(vec![candidate(10), candidate(7200)], &now, 3600);
+
+        let kept = &grouped[&task.id.unwrap()];
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].scheduled_time, now - chrono::Duration::seconds(10));
+    }
+
+    #[test]
+    fn test_summarize_failures() {
+        let detail = |name: &str, error: Option<&str>| DispatchTaskDetail {
+            task_id: ObjectId::new(),
+            task_name: name.to_string(),
+            candidates: 1,
+            duplicates: 0,
+            dispatched: 0,
+            failed: 0,
+            deferred: 0,
+            defer_reason: None,
+            scores: vec![0.5],
+            error: error.map(str::to_string),
+        };
+
+        assert_eq!(Dispatcher::summarize_failures(&[detail("ok", None)]), None);
+
+        let message = Dispatcher::summarize_failures(&[
+            detail("ok", None),
+            detail("bad-cron", Some("解析 Cron 表达式失败")),
+        ])
+        .unwrap();
+        assert!(message.starts_with("1 个任务处理失败"));
+        assert!(message.contains("bad-cron: 解析 Cron 表达式失败"));
+    }
+
+    #[test]
+    fn test_queue_unavailable_defers_without_failing() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 11, 7, 0, 0).unwrap();
+        let tasks = [Task::for_test("a"), Task::for_test("b")];
+        let mut task_details: Vec<DispatchTaskDetail> = tasks
+            .iter()
+            .map(|task| DispatchTaskDetail {
+                task_id: task.id.unwrap(),
+                task_name: task.name.clone(),
+                candidates: 2,
+                duplicates: 0,
+                dispatched: 0,
+                failed: 0,
+                deferred: 0,
+                defer_reason: None,
+                scores: Vec::new(),
+                error: None,
+            })
+            .collect();
+        let detail_index: HashMap<ObjectId, usize> = tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| (task.id.unwrap(), index))
+            .collect();
+        let candidate = |task: &Task, secs: i64| DispatchCandidate {
+            task_id: task.id.unwrap(),
+            task_name: task.name.clone(),
+            scheduled_time: now + chrono::Duration::seconds(secs),
+            priority: 0,
+            score: 0.0,
+        };
+
+        // a 的首个实例被限流推迟，b 的首个实例发布时队列不可用，其余实例随之推迟
+        let mut deferred = Vec::new();
+        Dispatcher::defer_candidate(
+            &mut task_details,
+            &detail_index,
+            &mut deferred,
+            candidate(&tasks[0], 0),
+            DeferReason::RateLimit,
+        );
+        for candidate in [
+            candidate(&tasks[1], 0),
+            candidate(&tasks[0], 60),
+            candidate(&tasks[1], 60),
+        ] {
+            Dispatcher::defer_candidate(
+                &mut task_details,
+                &detail_index,
+                &mut deferred,
+                candidate,
+                DeferReason::QueueUnavailable,
+            );
+        }
+
+        let deferred_total: i32 = task_details.iter().map(|detail| detail.deferred).sum();
+        assert_eq!(deferred_total as usize, deferred.len());
+        assert!(task_details.iter().all(|detail| detail.failed == 0));
+        let reason = DeferReason::QueueUnavailable.to_string();
+        assert!(
+            task_details
+                .iter()
+                .all(|detail| detail.defer_reason.as_deref() == Some(reason.as_str()))
+        );
+        assert_eq!(Dispatcher::summarize_failures(&task_details), None);
+    }
+}
@@ -1,2 +1,8 @@
+pub mod backfill;
+pub mod cron_describe;
 pub mod cron_parser;
 pub mod dispatcher;
+pub mod pause;
+pub mod rate_limit;
+pub mod simulator;
+pub mod sla;
@@ -0,0 +1,249 @@
+//! 分发限流：全局在途实例上限、任务在途实例上限与任务触发频率限制
+//!
+//! 在途实例指状态为待执行或执行中的实例。超出限制的候选实例不会创建，
+//! 由分发器推迟到后续扫描。
+
+use crate::error::{Error, Result};
+use crate::scheduler::dispatcher::DispatchCandidate;
+use crate::storage::mongo::MongoDataSource;
+use crate::types::{Task, TaskInstance, TaskRateLimit};
+use chrono::{DateTime, Utc};
+use mongodb::bson::{doc, oid::ObjectId};
+use std::collections::HashMap;
+
+/// 候选实例被推迟的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DeferReason {
+    GlobalConcurrency,
+    TaskConcurrency,
+    RateLimit,
+    /// 消息队列不可用，本次扫描停止分发
+    QueueUnavailable,
+}
+
+impl std::fmt::Display for DeferReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            DeferReason::GlobalConcurrency => "达到全局并发上限",
+            DeferReason::TaskConcurrency => "达到任务并发实例上限",
+            DeferReason::RateLimit => "超过任务触发频率限制",
+            DeferReason::QueueUnavailable => "消息队列不可用",
+        };
+        f.write_str(reason)
+    }
+}
+
+#[derive(Debug, Default)]
+struct TaskLimitState {
+    max_in_flight: Option<usize>,
+    in_flight: usize,
+    rate_limit: Option<TaskRateLimit>,
+    /// 已触发实例的计划执行时间（时间戳）
+    firings: Vec<i64>,
+}
+
+/// 一次扫描内的限流状态，分发成功的实例会计入后续判断
+#[derive(Debug, Default)]
+pub(crate) struct DispatchLimiter {
+    max_in_flight: Option<usize>,
+    in_flight: usize,
+    tasks: HashMap<ObjectId, TaskLimitState>,
+}
+
+impl DispatchLimiter {
+    /// `max_concurrent_tasks` 为 0 表示不限制全局在途实例数
+    pub(crate) fn new(max_concurrent_tasks: usize, tasks: &[Task]) -> Self {
+        let tasks = tasks
+            .iter()
+            .filter(|task| task.max_concurrent_instances.is_some() || task.rate_limit.is_some())
+            .filter_map(|task| {
+                let state = TaskLimitState {
+                    max_in_flight: task.max_concurrent_instances.map(|max| max.max(0) as usize),
+                    rate_limit: task.rate_limit,
+                    ..Default::default()
+                };
+                task.id.map(|id| (id, state))
+            })
+            .collect();
+        Self {
+            max_in_flight: (max_concurrent_tasks > 0).then_some(max_concurrent_tasks),
+            in_flight: 0,
+            tasks,
+        }
+    }
+
+    /// 从数据库加载在途实例与频率窗口内已触发的实例
+    pub(crate) async fn load(
+        db: &MongoDataSource,
+        max_concurrent_tasks: usize,
+        tasks: &[Task],
+        now: DateTime<Utc>,
+    ) -> Result<Self> {
+        let mut limiter = Self::new(max_concurrent_tasks, tasks);
+
+        let in_flight = db
+            .find_task_instances(
+                Some(doc! { "status": { "$in": ["pending", "running"] } }),
+                None,
+            )
+            .await
+            .map_err(|e| Error::Database(format!("查询在途实例失败: {}", e)))?;
+        for instance in &in_flight {
+            limiter.record_in_flight(instance.task_id);
+        }
+
+        let rate_limited: Vec<(ObjectId, i32)> = limiter
+            .tasks
+            .iter()
+            .filter_map(|(id, state)| state.rate_limit.map(|limit| (*id, limit.window_seconds)))
+            .collect();
+        if let Some(max_window) = rate_limited.iter().map(|(_, window)| *window).max() {
+            let task_ids: Vec<ObjectId> = rate_limited.iter().map(|(id, _)| *id).collect();
+            let recent = db
+                .find_task_instances(
+                    Some(doc! {
+                        "task_id": { "$in": task_ids },
+                        "status": { "$nin": ["cancelled", "expired"] },
+                        "scheduled_time": { "$gt": now - chrono::Duration::seconds(max_window as i64) },
+                    }),
+                    None,
+                )
+                .await
+                .map_err(|e| Error::Database(format!("查询近期实例失败: {}", e)))?;
+            for instance in &recent {
+                limiter.record_firing(instance);
+            }
+        }
+
+        Ok(limiter)
+    }
+
+    fn record_in_flight(&mut self, task_id: ObjectId) {
+        self.in_flight += 1;
+        if let Some(state) = self.tasks.get_mut(&task_id) {
+            state.in_flight += 1;
+        }
+    }
+
+    fn record_firing(&mut self, instance: &TaskInstance) {
+        if let Some(state) = self.tasks.get_mut(&instance.task_id) {
+            state.firings.push(instance.scheduled_time.timestamp());
+        }
+    }
+
+    /// 判断候选实例是否需要推迟
+    pub(crate) fn check(&self, candidate: &DispatchCandidate) -> Option<DeferReason> {
+        if self.max_in_flight.is_some_and(|max| self.in_flight >= max) {
+            return Some(DeferReason::GlobalConcurrency);
+        }
+        let state = self.tasks.get(&candidate.task_id)?;
+        if state
+            .max_in_flight
+            .is_some_and(|max| state.in_flight >= max)
+        {
+            return Some(DeferReason::TaskConcurrency);
+        }
+        if let Some(limit) = &state.rate_limit
+            && exceeds_rate_limit(&state.firings, candidate.scheduled_time.timestamp(), limit)
+        {
+            return Some(DeferReason::RateLimit);
+        }
+        None
+    }
+
+    /// 记录已分发的候选实例
+    pub(crate) fn record_dispatched(&mut self, candidate: &DispatchCandidate) {
+        self.record_in_flight(candidate.task_id);
+        if let Some(state) = self.tasks.get_mut(&candidate.task_id) {
+            state.firings.push(candidate.scheduled_time.timestamp());
+        }
+    }
+}
+
+/// 在 `scheduled_at` 触发是否会使某个包含它的窗口内触发次数超过上限
+///
+/// 窗口计数只在窗口右端越过某次触发时增加，因此只需检查最左侧的窗口
+/// 以及右端恰好包含 `scheduled_at` 之后各次触发的窗口
+fn exceeds_rate_limit(firings: &[i64], scheduled_at: i64, limit: &TaskRateLimit) -> bool {
+    let window = limit.window_seconds.max(1) as i64;
+    let max_runs = limit.max_runs.max(0) as usize;
+    std::iter::once(scheduled_at - window + 1)
+        .chain(
+            firings
+                .iter()
+                .filter(|&&firing| firing >= scheduled_at && firing < scheduled_at + window)
+                .map(|&firing| firing - window + 1),
+        )
+        .any(|start| {
+            firings
+                .iter()
+                .filter(|&&firing| firing >= start && firing < start + window)
+                .count()
+                >= max_runs
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_task(max_concurrent_instances: Option<i32>, rate_limit: Option<TaskRateLimit>) -> Task {
+        Task {
+            schedule: "* * * * * *".to_string(),
+            max_concurrent_instances,
+            rate_limit,
+            ..Task::for_test("limited-task")
+        }
+    }
+
+    fn candidate(task: &Task, scheduled_time: DateTime<Utc>) -> DispatchCandidate {
+        DispatchCandidate {
+            task_id: task.id.unwrap(),
+            task_name: task.name.clone(),
+            scheduled_time,
+            priority: 0,
+            score: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_concurrency_limits() {
+        let capped = make_task(Some(1), None);
+        let free = make_task(None, None);
+        let now = Utc::now();
+        let mut limiter = DispatchLimiter::new(2, &[capped.clone(), free.clone()]);
+
+        let first = candidate(&capped, now);
+        assert_eq!(limiter.check(&first), None);
+        limiter.record_dispatched(&first);
+        assert_eq!(
+            limiter.check(&candidate(&capped, now + chrono::Duration::seconds(1))),
+            Some(DeferReason::TaskConcurrency)
+        );
+
+        let other = candidate(&free, now);
+        assert_eq!(limiter.check(&other), None);
+        limiter.record_dispatched(&other);
+        assert_eq!(
+            limiter.check(&candidate(&free, now + chrono::Duration::seconds(1))),
+            Some(DeferReason::GlobalConcurrency)
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_sliding_window() {
+        let limit = TaskRateLimit {
+            max_runs: 2,
+            window_seconds: 60,
+        };
+
+        assert!(!exceeds_rate_limit(&[], 100, &limit));
+        assert!(!exceeds_rate_limit(&[0], 30, &limit));
+        assert!(exceeds_rate_limit(&[0, 10], 30, &limit));
+        assert!(exceeds_rate_limit(&[0, 10], 59, &limit));
+        assert!(!exceeds_rate_limit(&[0, 10], 69, &limit));
+        // 之后的触发同样计入包含当前时间的窗口
+        assert!(exceeds_rate_limit(&[40, 50], 30, &limit));
+        assert!(!exceeds_rate_limit(&[100], 30, &limit));
+    }
+}
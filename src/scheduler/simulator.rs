@@ -0,0 +1,336 @@
+//! 分发模拟器：在模拟时钟上重放分发器的候选生成与调度策略，用于容量规划
+
+use crate::config::SchedulingPolicyConfig;
+use crate::scheduler::dispatcher::{DispatchCandidate, Dispatcher};
+use crate::types::Task;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+
+/// 报告中列出的峰值分钟数量
+const PEAK_MINUTES: usize = 5;
+
+/// 模拟参数
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// 模拟开始时间
+    pub start: DateTime<Utc>,
+    /// 模拟时长（秒）
+    pub horizon_secs: i64,
+    /// 分发器扫描间隔（秒）
+    pub scan_interval_secs: u64,
+    /// 假定的执行器数量
+    pub executor_count: usize,
+    /// 假定的单个实例执行时长（秒）
+    pub run_duration_secs: i64,
+    pub scheduling: SchedulingPolicyConfig,
+}
+
+/// 某一分钟内计划执行的实例数量
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MinuteLoad {
+    pub minute: DateTime<Utc>,
+    pub instances: usize,
+}
+
+/// 模拟结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    pub start: DateTime<Utc>,
+    pub horizon_secs: i64,
+    pub task_count: usize,
+    pub executor_count: usize,
+    pub run_duration_secs: i64,
+    /// 模拟区间内生成的实例总数
+    pub total_instances: usize,
+    /// 平均每秒实例数
+    pub avg_instances_per_sec: f64,
+    /// 单秒内计划执行实例数的峰值
+    pub peak_instances_per_sec: usize,
+    /// 计划执行实例最多的分钟（降序）
+    pub peak_minutes: Vec<MinuteLoad>,
+    /// 已发布但未到调度时间、由执行器持有的消息数峰值
+    pub max_held_messages: usize,
+    /// 已到调度时间但尚未开始执行的实例数峰值
+    pub max_backlog: usize,
+    /// 队列积压的平均值（按秒采样）
+    pub avg_backlog: f64,
+    /// 模拟结束时仍在队列中的实例数
+    pub final_backlog: usize,
+    /// 实例从调度时间到开始执行的最长等待时间（秒）
+    pub max_queue_wait_secs: i64,
+    /// 实例从调度时间到开始执行的平均等待时间（秒）
+    pub avg_queue_wait_secs: f64,
+    /// 执行器利用率（0-1）
+    pub executor_utilization: f64,
+}
+
+/// 已到期的消息，按消息优先级降序、入队顺序升序出队（与 RabbitMQ 优先级队列一致）
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct QueuedMessage {
+    priority: i32,
+    seq: Reverse<u64>,
+    due_at: i64,
+}
+
+/// 分发模拟器
+pub struct DispatchSimulator {
+    tasks: Vec<Task>,
+    config: SimulationConfig,
+}
+
+impl DispatchSimulator {
+    pub fn new(tasks: Vec<Task>, config: SimulationConfig) -> Self {
+        Self { tasks, config }
+    }
+
+    /// 逐秒推进模拟时钟，在每个扫描点生成候选实例并交给模拟执行器消费
+    ///
+    /// 与执行器一致，消息在调度时间之前由执行器持有，到期后才参与执行名额的竞争
+    pub fn run(&self) -> SimulationReport {
+        let config = &self.config;
+        let start_ts = config.start.timestamp();
+        let end = config.start + chrono::Duration::seconds(config.horizon_secs);
+        let scan_interval = config.scan_interval_secs.max(1) as i64;
+        let executor_count = config.executor_count.max(1);
+        let run_duration = config.run_duration_secs.max(1);
+
+        let mut generated: HashMap<ObjectId, HashSet<i64>> = HashMap::new();
+        let mut per_second: BTreeMap<i64, usize> = BTreeMap::new();
+        let mut held: BinaryHeap<Reverse<(i64, u64, i32)>> = BinaryHeap::new();
+        let mut queue: BinaryHeap<QueuedMessage> = BinaryHeap::new();
+        let mut executors_free_at: Vec<i64> = vec![start_ts; executor_count];
+        let mut seq = 0u64;
+
+        let mut total_instances = 0usize;
+        let mut backlog_sum = 0usize;
+        let mut max_backlog = 0usize;
+        let mut max_held = 0usize;
+        let mut started = 0usize;
+        let mut wait_sum = 0i64;
+        let mut max_wait = 0i64;
+        let mut busy_secs = 0i64;
+
+        for offset in 0..config.horizon_secs.max(0) {
+            let now_ts = start_ts + offset;
+
+            if offset % scan_interval == 0 {
+                let now = config.start + chrono::Duration::seconds(offset);
+                let candidates = self.scan(&now, &end, scan_interval, &mut generated);
+                for candidate in candidates {
+                    *per_second
+                        .entry(candidate.scheduled_time.timestamp())
+                        .or_default() += 1;
+                    total_instances += 1;
+                    seq += 1;
+                    held.push(Reverse((
+                        candidate.scheduled_time.timestamp().max(now_ts),
+                        seq,
+                        candidate.priority,
+                    )));
+                }
+            }
+
+            while let Some(Reverse((due_at, seq, priority))) = held
+                .peek()
+                .copied()
+                .filter(|Reverse((due_at, ..))| *due_at <= now_ts)
+            {
+                held.pop();
+                queue.push(QueuedMessage {
+                    priority,
+                    seq: Reverse(seq),
+                    due_at,
+                });
+            }
+            max_held = max_held.max(held.len());
+
+            for free_at in executors_free_at.iter_mut() {
+                if *free_at > now_ts {
+                    continue;
+                }
+                let Some(message) = queue.pop() else {
+                    break;
+                };
+                let wait = now_ts - message.due_at;
+                wait_sum += wait;
+                max_wait = max_wait.max(wait);
+                started += 1;
+                *free_at = now_ts + run_duration;
+                busy_secs += run_duration.min(config.horizon_secs - offset);
+            }
+
+            backlog_sum += queue.len();
+            max_backlog = max_backlog.max(queue.len());
+        }
+
+        let horizon = config.horizon_secs.max(1) as f64;
+        SimulationReport {
+            start: config.start,
+            horizon_secs: config.horizon_secs,
+            task_count: self.tasks.len(),
+            executor_count,
+            run_duration_secs: run_duration,
+            total_instances,
+            avg_instances_per_sec: total_instances as f64 / horizon,
+            peak_instances_per_sec: per_second.values().copied().max().unwrap_or(0),
+            peak_minutes: peak_minutes(&per_second, PEAK_MINUTES),
+            max_held_messages: max_held,
+            max_backlog,
+            avg_backlog: backlog_sum as f64 / horizon,
+            final_backlog: queue.len(),
+            max_queue_wait_secs: max_wait,
+            avg_queue_wait_secs: if started == 0 {
+                0.0
+            } else {
+                wait_sum as f64 / started as f64
+            },
+            executor_utilization: busy_secs as f64 / (horizon * executor_count as f64),
+        }
+    }
+
+    /// 模拟一次分发器扫描，返回按调度策略排序后的候选实例
+    fn scan(
+        &self,
+        now: &DateTime<Utc>,
+        end: &DateTime<Utc>,
+        scan_interval: i64,
+        generated: &mut HashMap<ObjectId, HashSet<i64>>,
+    ) -> Vec<DispatchCandidate> {
+        let scan_window_end = (*now + chrono::Duration::seconds(scan_interval)).min(*end);
+        let mut candidates = Vec::new();
+
+        for task in self.tasks.iter().filter(|task| task.enabled) {
+            let Some(task_id) = task.id else {
+                continue;
+            };
+            let Ok(task_candidates) = Dispatcher::collect_task_candidates(
+                task,
+                now,
+                &scan_window_end,
+                generated.get(&task_id),
+                &self.config.scheduling,
+            ) else {
+                continue;
+            };
+            let existing = generated.entry(task_id).or_default();
+            for candidate in task_candidates.candidates {
+                if candidate.scheduled_time < *end {
+                    existing.insert(candidate.scheduled_time.timestamp());
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        Dispatcher::sort_candidates(&mut candidates);
+        candidates
+    }
+}
+
+/// 按分钟汇总每秒实例数，返回实例最多的 `limit` 分钟
+fn peak_minutes(per_second: &BTreeMap<i64, usize>, limit: usize) -> Vec<MinuteLoad> {
+    let mut per_minute: BTreeMap<i64, usize> = BTreeMap::new();
+    for (ts, count) in per_second {
+        *per_minute.entry(ts - ts.rem_euclid(60)).or_default() += count;
+    }
+
+    let mut minutes: Vec<MinuteLoad> = per_minute
+        .into_iter()
+        .filter_map(|(ts, instances)| {
+            DateTime::from_timestamp(ts, 0).map(|minute| MinuteLoad { minute, instances })
+        })
+        .collect();
+    minutes.sort_by(|a, b| b.instances.cmp(&a.instances).then(a.minute.cmp(&b.minute)));
+    minutes.truncate(limit);
+    minutes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn make_task(name: &str, schedule: &str, priority: Option<i32>) -> Task {
+        let created_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        Task {
+            schedule: schedule.to_string(),
+            priority,
+            created_at,
+            updated_at: created_at,
+            ..Task::for_test(name)
+        }
+    }
+
+    fn make_config(executor_count: usize, run_duration_secs: i64) -> SimulationConfig {
+        SimulationConfig {
+            start: Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap(),
+            horizon_secs: 600,
+            scan_interval_secs: 10,
+            executor_count,
+            run_duration_secs,
+            scheduling: SchedulingPolicyConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_simulator_counts_instances() {
+        let tasks = vec![
+            make_task("every-10s", "*/10 * * * * *", None),
+            make_task("every-minute", "30 * * * * *", None),
+        ];
+
+        let report = DispatchSimulator::new(tasks, make_config(2, 1)).run();
+
+        // 10 分钟内：每 10 秒一次 59 个（不含起点与终点），每分钟一次 10 个
+        assert_eq!(report.total_instances, 69);
+        assert_eq!(report.peak_instances_per_sec, 2);
+        assert_eq!(report.max_backlog, 0);
+        assert_eq!(report.final_backlog, 0);
+        assert!(!report.peak_minutes.is_empty());
+    }
+
+    #[test]
+    fn test_simulator_holds_messages_until_scheduled_time() {
+        let tasks = vec![make_task("every-second", "* * * * * *", None)];
+
+        let report = DispatchSimulator::new(tasks, make_config(1, 1)).run();
+
+        // 每次扫描发布下一个间隔内的 10 个实例，执行器按调度时间逐个执行，不会提前消费
+        assert_eq!(report.max_held_messages, 10);
+        assert_eq!(report.max_backlog, 0);
+        assert_eq!(report.max_queue_wait_secs, 0);
+        assert!(report.executor_utilization > 0.95);
+    }
+
+    #[test]
+    fn test_simulator_reports_backlog_when_under_provisioned() {
+        let tasks = (0..5)
+            .map(|i| make_task(&format!("task-{}", i), "* * * * * *", Some(i)))
+            .collect();
+
+        let report = DispatchSimulator::new(tasks, make_config(1, 2)).run();
+
+        assert!(report.max_backlog > 0, "执行器不足时应出现积压");
+        assert!(report.final_backlog > 0);
+        assert!(report.max_queue_wait_secs > 0);
+        assert!(report.executor_utilization > 0.99);
+    }
+
+    #[test]
+    fn test_peak_minutes_sorted_by_load() {
+        let base = Utc
+            .with_ymd_and_hms(2025, 3, 12, 0, 0, 0)
+            .unwrap()
+            .timestamp();
+        let per_second =
+            BTreeMap::from([(base, 1), (base + 60, 3), (base + 61, 2), (base + 120, 4)]);
+
+        let peaks = peak_minutes(&per_second, 2);
+
+        assert_eq!(peaks.len(), 2);
+        assert_eq!(peaks[0].instances, 5);
+        assert_eq!(peaks[1].instances, 4);
+    }
+}
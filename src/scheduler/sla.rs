@@ -0,0 +1,406 @@
+//! SLA 检查：标记未按时开始、执行超时的实例，并使长时间未开始的实例过期
+
+use crate::clock::{SharedClock, SystemClock};
+use crate::config::SlaConfig;
+use crate::error::{Error, Result};
+use crate::storage::mongo::MongoDataSource;
+use crate::types::{SlaEvent, SlaEventKind, Task, TaskInstance, TaskStatus};
+use chrono::{DateTime, Utc};
+use mongodb::bson::{doc, oid::ObjectId};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// 一次 SLA 检查的结果
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SlaCheckSummary {
+    pub late: usize,
+    pub overrun: usize,
+    pub expired: usize,
+}
+
+/// SLA 检查器
+pub struct SlaChecker {
+    db: Arc<MongoDataSource>,
+    config: SlaConfig,
+    clock: SharedClock,
+}
+
+impl SlaChecker {
+    pub fn new(db: Arc<MongoDataSource>, config: SlaConfig) -> Self {
+        Self {
+            db,
+            config,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// 替换检查使用的时钟
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// 执行一次完整检查
+    pub async fn check(&self) -> Result<SlaCheckSummary> {
+        let now = self.clock.now();
+        let mut summary = SlaCheckSummary {
+            expired: self.expire_stale_instances(&now).await?,
+            ..Default::default()
+        };
+
+        let tasks = self
+            .db
+            .find_tasks(
+                Some(doc! {
+                    "deleted_at": null,
+                    "$or": [
+                        { "start_sla_seconds": { "$ne": null } },
+                        { "run_sla_seconds": { "$ne": null } },
+                    ]
+                }),
+                None,
+            )
+            .await
+            .map_err(|e| Error::Database(format!("查询任务失败: {}", e)))?;
+
+        // 只检查过期时限内的实例，更早的实例要么已结束，要么已过期
+        let lookback = now - chrono::Duration::seconds(self.config.expire_after_secs as i64);
+
+        for task in &tasks {
+            let Some(task_id) = task.id else {
+                continue;
+            };
+            let instances = self
+                .db
+                .find_task_instances(
+                    Some(doc! {
+                        "task_id": task_id,
+                        "scheduled_time": { "$gte": lookback },
+                    }),
+                    None,
+                )
+                .await
+                .map_err(|e| Error::Database(format!("查询任务实例失败: {}", e)))?;
+
+            for instance in &instances {
+                if let Some(sla) = task.start_sla_seconds
+                    && let Some(overdue) = start_overdue_seconds(instance, sla, &now)
+                    && self
+                        .record(task, instance, SlaEventKind::StartLate, sla, overdue, &now)
+                        .await?
+                {
+                    summary.late += 1;
+                }
+                if let Some(sla) = task.run_sla_seconds
+                    && let Some(overdue) = run_overdue_seconds(instance, sla, &now)
+                    && self
+                        .record(task, instance, SlaEventKind::RunOverrun, sla, overdue, &now)
+                        .await?
+                {
+                    summary.overrun += 1;
+                }
+            }
+        }
+
+        if summary != SlaCheckSummary::default() {
+            info!(
+                "[SlaChecker] 未按时开始: {}，执行超时: {}，过期: {}",
+                summary.late, summary.overrun, summary.expired
+            );
+        } else {
+            debug!("[SlaChecker] 未发现 SLA 违规");
+        }
+
+        Ok(summary)
+    }
+
+    /// 将超过过期时限仍未开始的实例置为过期，执行器领取时会跳过这些实例
+    ///
+    /// 回填与指定历史时间的手动触发实例的计划时间早于创建时间，等待时长从两者中较晚的时间算起
+    async fn expire_stale_instances(&self, now: &DateTime<Utc>) -> Result<usize> {
+        let horizon = self.config.expire_after_secs as i64;
+        let cutoff = *now - chrono::Duration::seconds(horizon);
+
+        let stale = self
+            .db
+            .find_task_instances(Some(stale_instances_filter(cutoff)), None)
+            .await
+            .map_err(|e| Error::Database(format!("查询待执行实例失败: {}", e)))?;
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        let task_ids: Vec<ObjectId> = stale.iter().map(|instance| instance.task_id).collect();
+        let task_names: HashMap<ObjectId, String> = self
+            .db
+            .find_tasks(Some(doc! { "_id": { "$in": task_ids } }), None)
+            .await
+            .map_err(|e| Error::Database(format!("查询任务失败: {}", e)))?
+            .into_iter()
+            .filter_map(|task| task.id.map(|id| (id, task.name)))
+            .collect();
+
+        let mut expired = 0;
+        for instance in &stale {
+            let Some(instance_id) = instance.id else {
+                continue;
+            };
+            let update = doc! {
+                "$set": {
+                    "status": "expired",
+                    "end_time": now,
+                    "result": { "error": format!("超过 {} 秒仍未开始执行，已过期", horizon) },
+                },
+                "$addToSet": { "sla_flags": SlaEventKind::Expired.as_str() },
+            };
+            let updated = self
+                .db
+                .update_task_instance_if_status(instance_id, "pending", update)
+                .await
+                .map_err(|e| Error::Database(format!("更新任务实例失败: {}", e)))?;
+            if !updated {
+                continue;
+            }
+
+            let event = SlaEvent {
+                id: None,
+                task_id: instance.task_id,
+                task_name: task_names
+                    .get(&instance.task_id)
+                    .cloned()
+                    .unwrap_or_default(),
+                instance_id,
+                kind: SlaEventKind::Expired,
+                scheduled_time: instance.scheduled_time,
+                threshold_seconds: horizon,
+                overdue_seconds: expired_overdue_seconds(instance, horizon, now).unwrap_or(0),
+                detected_at: *now,
+            };
+            self.db
+                .create_sla_event(&event)
+                .await
+                .map_err(|e| Error::Database(format!("创建 SLA 事件失败: {}", e)))?;
+            expired += 1;
+        }
+
+        Ok(expired)
+    }
+
+    /// 为实例打上 SLA 标记并记录事件，已标记过的实例返回 `false`
+    async fn record(
+        &self,
+        task: &Task,
+        instance: &TaskInstance,
+        kind: SlaEventKind,
+        threshold_seconds: i32,
+        overdue_seconds: i64,
+        now: &DateTime<Utc>,
+    ) -> Result<bool> {
+        let Some(instance_id) = instance.id else {
+            return Ok(false);
+        };
+        if instance.sla_flags.contains(&kind) {
+            return Ok(false);
+        }
+
+        let flagged = self
+            .db
+            .update_task_instance(
+                instance_id,
+                doc! { "$addToSet": { "sla_flags": kind.as_str() } },
+            )
+            .await
+            .map_err(|e| Error::Database(format!("更新任务实例失败: {}", e)))?;
+        if !flagged {
+            return Ok(false);
+        }
+
+        let event = SlaEvent {
+            id: None,
+            task_id: instance.task_id,
+            task_name: task.name.clone(),
+            instance_id,
+            kind,
+            scheduled_time: instance.scheduled_time,
+            threshold_seconds: threshold_seconds as i64,
+            overdue_seconds,
+            detected_at: *now,
+        };
+        self.db
+            .create_sla_event(&event)
+            .await
+            .map_err(|e| Error::Database(format!("创建 SLA 事件失败: {}", e)))?;
+
+        Ok(true)
+    }
+}
+
+/// 待执行且计划时间与创建时间都早于 `cutoff` 的实例
+fn stale_instances_filter(cutoff: DateTime<Utc>) -> mongodb::bson::Document {
+    doc! {
+        "status": "pending",
+        "scheduled_time": { "$lt": cutoff },
+        "created_at": { "$lt": cutoff },
+    }
+}
+
+/// 待执行实例超出过期时限的秒数，从计划时间与创建时间中较晚者算起，未超出时返回 `None`
+pub fn expired_overdue_seconds(
+    instance: &TaskInstance,
+    expire_after_secs: i64,
+    now: &DateTime<Utc>,
+) -> Option<i64> {
+    if instance.status != TaskStatus::Pending {
+        return None;
+    }
+    let waiting_since = instance.scheduled_time.max(instance.created_at);
+    let overdue = (*now - waiting_since).num_seconds() - expire_after_secs;
+    (overdue > 0).then_some(overdue)
+}
+
+/// 实例开始执行超出 `start_sla_seconds` 的秒数，未超出时返回 `None`
+///
+/// 已开始的实例按实际开始时间计算，未开始的待执行实例按当前时间计算
+pub fn start_overdue_seconds(
+    instance: &TaskInstance,
+    start_sla_seconds: i32,
+    now: &DateTime<Utc>,
+) -> Option<i64> {
+    let started_at = match (&instance.status, instance.start_time) {
+        (_, Some(start_time)) => start_time,
+        (TaskStatus::Pending, None) => *now,
+        _ => return None,
+    };
+    let overdue = (started_at - instance.scheduled_time).num_seconds() - start_sla_seconds as i64;
+    (overdue > 0).then_some(overdue)
+}
+
+/// 实例执行时长超出 `run_sla_seconds` 的秒数，未开始或未超出时返回 `None`
+pub fn run_overdue_seconds(
+    instance: &TaskInstance,
+    run_sla_seconds: i32,
+    now: &DateTime<Utc>,
+) -> Option<i64> {
+    let start_time = instance.start_time?;
+    let finished_at = match instance.status {
+        TaskStatus::Running => *now,
+        _ => instance.end_time?,
+    };
+    let overdue = (finished_at - start_time).num_seconds() - run_sla_seconds as i64;
+    (overdue > 0).then_some(overdue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TriggeredBy;
+    use chrono::Duration;
+
+    fn make_instance(
+        status: TaskStatus,
+        scheduled_time: DateTime<Utc>,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> TaskInstance {
+        TaskInstance {
+            id: Some(ObjectId::new()),
+            task_id: ObjectId::new(),
+            scheduled_time,
+            status,
+            executor_id: None,
+            start_time,
+            end_time,
+            retry_count: 0,
+            result: None,
+            triggered_by: TriggeredBy::Scheduler,
+            priority_score: None,
+            sla_flags: Vec::new(),
+            trigger_payload: None,
+            parameters: None,
+            created_at: scheduled_time,
+        }
+    }
+
+    #[test]
+    fn test_start_overdue_for_pending_instance() {
+        let now = Utc::now();
+        let pending = make_instance(TaskStatus::Pending, now - Duration::seconds(90), None, None);
+
+        assert_eq!(start_overdue_seconds(&pending, 60, &now), Some(30));
+        assert_eq!(start_overdue_seconds(&pending, 120, &now), None);
+    }
+
+    #[test]
+    fn test_start_overdue_uses_actual_start_time() {
+        let now = Utc::now();
+        let scheduled = now - Duration::seconds(600);
+        let started_late = make_instance(
+            TaskStatus::Success,
+            scheduled,
+            Some(scheduled + Duration::seconds(100)),
+            Some(scheduled + Duration::seconds(110)),
+        );
+        let started_on_time = make_instance(
+            TaskStatus::Running,
+            scheduled,
+            Some(scheduled + Duration::seconds(5)),
+            None,
+        );
+
+        assert_eq!(start_overdue_seconds(&started_late, 60, &now), Some(40));
+        assert_eq!(start_overdue_seconds(&started_on_time, 60, &now), None);
+    }
+
+    #[test]
+    fn test_fresh_backfill_instance_is_not_expired() {
+        let now = Utc::now();
+        let last_week = now - Duration::days(7);
+        let mut backfill = make_instance(TaskStatus::Pending, last_week, None, None);
+        backfill.triggered_by = TriggeredBy::Backfill;
+        backfill.created_at = now - Duration::seconds(10);
+
+        assert_eq!(expired_overdue_seconds(&backfill, 3600, &now), None);
+        // 查询条件同时要求创建时间早于截止时间，刚创建的回填实例不会被查出
+        let cutoff = now - Duration::seconds(3600);
+        let filter = stale_instances_filter(cutoff);
+        assert_eq!(
+            filter.get_document("created_at").unwrap(),
+            &doc! { "$lt": cutoff }
+        );
+
+        // 同一实例等待超过过期时限后照常过期
+        backfill.created_at = now - Duration::seconds(3700);
+        assert_eq!(expired_overdue_seconds(&backfill, 3600, &now), Some(100));
+        let stale = make_instance(
+            TaskStatus::Pending,
+            now - Duration::seconds(3900),
+            None,
+            None,
+        );
+        assert_eq!(expired_overdue_seconds(&stale, 3600, &now), Some(300));
+    }
+
+    #[test]
+    fn test_run_overdue() {
+        let now = Utc::now();
+        let scheduled = now - Duration::seconds(600);
+        let running = make_instance(
+            TaskStatus::Running,
+            scheduled,
+            Some(now - Duration::seconds(300)),
+            None,
+        );
+        let finished = make_instance(
+            TaskStatus::Success,
+            scheduled,
+            Some(scheduled),
+            Some(scheduled + Duration::seconds(30)),
+        );
+        let pending = make_instance(TaskStatus::Pending, scheduled, None, None);
+
+        assert_eq!(run_overdue_seconds(&running, 120, &now), Some(180));
+        assert_eq!(run_overdue_seconds(&finished, 60, &now), None);
+        assert_eq!(run_overdue_seconds(&pending, 60, &now), None);
+    }
+}
@@ -0,0 +1,214 @@
+//! 密钥存储：密钥值以 AES-256-GCM 加密后保存在 `secrets` 集合中
+//!
+//! 任务载荷通过 `${secret:名称}` 引用密钥（见 [`crate::executor::template`]），只在执行器中解密并替换；
+//! 执行结果与执行日志中出现的明文会被替换为 [`MASK`]。
+
+use crate::config::SecretsConfig;
+use crate::types::ExecutionResult;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+/// 覆盖配置文件中主密钥的环境变量
+pub const MASTER_KEY_ENV: &str = "RAPIDCRON_MASTER_KEY";
+
+/// 替换密钥明文的占位符
+pub const MASK: &str = "******";
+
+/// 密钥值的最大字节数
+pub const MAX_SECRET_VALUE_BYTES: usize = 64 * 1024;
+
+/// 加密后的密钥值
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncryptedValue {
+    /// 12 字节随机数（十六进制）
+    pub nonce: String,
+    /// 密文与认证标签（十六进制）
+    pub ciphertext: String,
+}
+
+/// 使用主密钥加解密密钥值
+#[derive(Clone)]
+pub struct SecretCipher {
+    cipher: Aes256Gcm,
+}
+
+impl SecretCipher {
+    /// 由 64 个十六进制字符（32 字节）的主密钥创建
+    pub fn from_hex(master_key: &str) -> Result<Self, String> {
+        let bytes = hex::decode(master_key.trim()).map_err(|_| "主密钥必须是十六进制字符串")?;
+        if bytes.len() != 32 {
+            return Err("主密钥必须为 32 字节（64 个十六进制字符）".to_string());
+        }
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)),
+        })
+    }
+
+    /// 按配置创建，环境变量优先；都未设置时返回 None
+    pub fn from_config(config: &SecretsConfig) -> Result<Option<Self>, String> {
+        config
+            .master_key()
+            .map(|master_key| Self::from_hex(&master_key))
+            .transpose()
+    }
+
+    /// 加密密钥值，密钥名称作为附加认证数据，密文不能挪用到其他名称下
+    pub fn encrypt(&self, name: &str, value: &str) -> Result<EncryptedValue, String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| "加密密钥失败".to_string())?;
+        Ok(EncryptedValue {
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// 解密密钥值
+    pub fn decrypt(&self, name: &str, value: &EncryptedValue) -> Result<String, String> {
+        let nonce = hex::decode(&value.nonce)
+            .ok()
+            .filter(|nonce| nonce.len() == 12)
+            .ok_or_else(|| format!("密钥 {} 的随机数无效", name))?;
+        let ciphertext =
+            hex::decode(&value.ciphertext).map_err(|_| format!("密钥 {} 的密文无效", name))?;
+        let plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| format!("解密密钥 {} 失败，主密钥可能已更换", name))?;
+        String::from_utf8(plaintext).map_err(|_| format!("密钥 {} 不是有效的 UTF-8", name))
+    }
+}
+
+/// 校验密钥名称：1-64 个字母、数字、`_`、`-` 或 `.`
+pub fn validate_secret_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 64 {
+        return Err("密钥名称长度必须在1到64个字符之间".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(format!("无效的密钥名称: {}", name));
+    }
+    Ok(())
+}
+
+/// 校验密钥值
+pub fn validate_secret_value(value: &str) -> Result<(), String> {
+    if value.is_empty() {
+        return Err("密钥值不能为空".to_string());
+    }
+    if value.len() > MAX_SECRET_VALUE_BYTES {
+        return Err(format!("密钥值不能超过{}字节", MAX_SECRET_VALUE_BYTES));
+    }
+    Ok(())
+}
+
+/// 在输出中遮盖密钥明文
+#[derive(Debug, Clone, Default)]
+pub struct SecretMask {
+    /// 按长度从长到短排列，避免较短的值先替换后留下较长值的片段
+    values: Vec<String>,
+}
+
+impl SecretMask {
+    pub fn new<'a>(values: impl IntoIterator<Item = &'a String>) -> Self {
+        let mut values: Vec<String> = values
+            .into_iter()
+            .filter(|value| !value.is_empty())
+            .cloned()
+            .collect();
+        values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+        values.dedup();
+        Self { values }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn mask(&self, text: &str) -> String {
+        self.values.iter().fold(text.to_string(), |text, value| {
+            text.replace(value.as_str(), MASK)
+        })
+    }
+
+    pub fn mask_option(&self, text: Option<String>) -> Option<String> {
+        match text {
+            Some(text) if !self.is_empty() => Some(self.mask(&text)),
+            text => text,
+        }
+    }
+
+    /// 遮盖执行结果中的输出、错误信息与断言说明
+    pub fn mask_result(&self, mut result: ExecutionResult) -> ExecutionResult {
+        if self.is_empty() {
+            return result;
+        }
+        result.output = self.mask_option(result.output);
+        result.error = self.mask_option(result.error);
+        if let Some(failure) = &mut result.failed_assertion {
+            failure.message = self.mask(&failure.message);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASTER_KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let cipher = SecretCipher::from_hex(MASTER_KEY).unwrap();
+        let encrypted = cipher.encrypt("api-token", "s3cr3t").unwrap();
+        assert!(!encrypted.ciphertext.contains(&hex::encode("s3cr3t")));
+        assert_eq!(cipher.decrypt("api-token", &encrypted).unwrap(), "s3cr3t");
+
+        // 随机数每次不同
+        assert_ne!(cipher.encrypt("api-token", "s3cr3t").unwrap(), encrypted);
+        // 密文与名称绑定
+        assert!(cipher.decrypt("other", &encrypted).is_err());
+        // 主密钥不同无法解密
+        let other = SecretCipher::from_hex(&"ff".repeat(32)).unwrap();
+        assert!(other.decrypt("api-token", &encrypted).is_err());
+
+        assert!(SecretCipher::from_hex("abcd").is_err());
+        assert!(SecretCipher::from_hex(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_mask() {
+        let values = ["abc".to_string(), "abcdef".to_string(), String::new()];
+        let mask = SecretMask::new(&values);
+        assert_eq!(
+            mask.mask("token=abcdef, short=abc"),
+            "token=******, short=******"
+        );
+
+        let result = mask.mask_result(ExecutionResult {
+            output: Some("echo abcdef".to_string()),
+            error: None,
+            exit_code: Some(0),
+            failed_assertion: None,
+        });
+        assert_eq!(result.output.as_deref(), Some("echo ******"));
+        assert!(SecretMask::default().is_empty());
+    }
+}
@@ -52,6 +52,34 @@ impl MongoDataSource {
     fn dispatch_logs(&self) -> Collection<DispatchLog> {
         self.database.collection("dispatch_logs")
     }
+
+    fn backfill_jobs(&self) -> Collection<BackfillJob> {
+        self.database.collection("backfill_jobs")
+    }
+
+    fn sla_events(&self) -> Collection<SlaEvent> {
+        self.database.collection("sla_events")
+    }
+
+    fn pauses(&self) -> Collection<Pause> {
+        self.database.collection("pauses")
+    }
+
+    fn webhooks(&self) -> Collection<Webhook> {
+        self.database.collection("webhooks")
+    }
+
+    fn secrets(&self) -> Collection<Secret> {
+        self.database.collection("secrets")
+    }
+
+    fn wasm_modules(&self) -> Collection<WasmModule> {
+        self.database.collection("wasm_modules")
+    }
+
+    fn grpc_descriptor_sets(&self) -> Collection<GrpcDescriptorSet> {
+        self.database.collection("grpc_descriptor_sets")
+    }
 }
 impl MongoDataSource {
     pub async fn create_task(&self, task: &Task) -> Result<ObjectId> {
@@ -110,6 +138,27 @@ impl MongoDataSource {
         Ok(result.modified_count > 0)
     }
 
+    /// 仅当实例处于指定状态时才更新，用于避免状态竞争（如执行器领取与过期检查）
+    pub async fn update_task_instance_if_status(
+        &self,
+        id: ObjectId,
+        status: &str,
+        update: Document,
+    ) -> Result<bool> {
+        let collection = self.task_instances();
+        let result = collection
+            .update_one(doc! { "_id": id, "status": status }, update)
+            .await?;
+        Ok(result.modified_count > 0)
+    }
+
+    /// 批量更新任务实例，返回更新的数量
+    pub async fn update_task_instances(&self, filter: Document, update: Document) -> Result<u64> {
+        let collection = self.task_instances();
+        let result = collection.update_many(filter, update).await?;
+        Ok(result.modified_count)
+    }
+
     pub async fn delete_task_instance(&self, id: ObjectId) -> Result<bool> {
         let collection = self.task_instances();
         let result = collection.delete_one(doc! { "_id": id }).await?;
@@ -217,10 +266,263 @@ impl MongoDataSource {
         Ok(result.deleted_count)
     }
 
+    pub async fn create_backfill_job(&self, job: &BackfillJob) -> Result<ObjectId> {
+        let collection = self.backfill_jobs();
+        let result = collection.insert_one(job).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
+    pub async fn get_backfill_job(&self, id: ObjectId) -> Result<Option<BackfillJob>> {
+        let collection = self.backfill_jobs();
+        let job = collection.find_one(doc! { "_id": id }).await?;
+        Ok(job)
+    }
+
+    /// 仅更新仍处于运行中的回填作业，避免覆盖已取消或已结束的状态
+    pub async fn update_running_backfill_job(
+        &self,
+        id: ObjectId,
+        update: Document,
+    ) -> Result<bool> {
+        let collection = self.backfill_jobs();
+        let result = collection
+            .update_one(doc! { "_id": id, "status": "running" }, update)
+            .await?;
+        Ok(result.modified_count > 0)
+    }
+
+    pub async fn find_backfill_jobs(&self, filter: Option<Document>) -> Result<Vec<BackfillJob>> {
+        let collection = self.backfill_jobs();
+        let mut cursor = collection
+            .find(filter.unwrap_or_default())
+            .sort(doc! { "created_at": -1 })
+            .await?;
+        let mut jobs = Vec::new();
+        while let Some(job) = cursor.try_next().await? {
+            jobs.push(job);
+        }
+        Ok(jobs)
+    }
+
+    pub async fn create_sla_event(&self, event: &SlaEvent) -> Result<ObjectId> {
+        let collection = self.sla_events();
+        let result = collection.insert_one(event).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
+    pub async fn find_sla_events(&self, filter: Option<Document>) -> Result<Vec<SlaEvent>> {
+        let collection = self.sla_events();
+        let mut cursor = collection
+            .find(filter.unwrap_or_default())
+            .sort(doc! { "detected_at": -1 })
+            .await?;
+        let mut events = Vec::new();
+        while let Some(event) = cursor.try_next().await? {
+            events.push(event);
+        }
+        Ok(events)
+    }
+
+    pub async fn create_pause(&self, pause: &Pause) -> Result<ObjectId> {
+        let collection = self.pauses();
+        let result = collection.insert_one(pause).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
+    pub async fn get_pause(&self, id: ObjectId) -> Result<Option<Pause>> {
+        let collection = self.pauses();
+        let pause = collection.find_one(doc! { "_id": id }).await?;
+        Ok(pause)
+    }
+
+    pub async fn update_pause(&self, id: ObjectId, update: Document) -> Result<bool> {
+        let collection = self.pauses();
+        let result = collection.update_one(doc! { "_id": id }, update).await?;
+        Ok(result.modified_count > 0)
+    }
+
+    pub async fn find_pauses(&self, filter: Option<Document>) -> Result<Vec<Pause>> {
+        let collection = self.pauses();
+        let mut cursor = collection
+            .find(filter.unwrap_or_default())
+            .sort(doc! { "starts_at": -1 })
+            .await?;
+        let mut pauses = Vec::new();
+        while let Some(pause) = cursor.try_next().await? {
+            pauses.push(pause);
+        }
+        Ok(pauses)
+    }
+
+    /// 查询在指定时间生效的暂停：已开始、未手动恢复且未到结束时间
+    pub async fn find_active_pauses(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Pause>> {
+        self.find_pauses(Some(doc! {
+            "starts_at": { "$lte": now },
+            "resumed_at": null,
+            "$or": [
+                { "ends_at": null },
+                { "ends_at": { "$gt": now } },
+            ],
+        }))
+        .await
+    }
+
+    /// 保存任务的 Webhook，已存在时替换（令牌轮换）
+    pub async fn replace_webhook(&self, webhook: &Webhook) -> Result<()> {
+        let collection = self.webhooks();
+        collection
+            .replace_one(doc! { "task_id": webhook.task_id }, webhook)
+            .upsert(true)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_webhook_by_task(&self, task_id: ObjectId) -> Result<Option<Webhook>> {
+        let collection = self.webhooks();
+        let webhook = collection.find_one(doc! { "task_id": task_id }).await?;
+        Ok(webhook)
+    }
+
+    pub async fn get_webhook_by_token(&self, token: &str) -> Result<Option<Webhook>> {
+        let collection = self.webhooks();
+        let webhook = collection.find_one(doc! { "token": token }).await?;
+        Ok(webhook)
+    }
+
+    pub async fn update_webhook(&self, id: ObjectId, update: Document) -> Result<bool> {
+        let collection = self.webhooks();
+        let result = collection.update_one(doc! { "_id": id }, update).await?;
+        Ok(result.modified_count > 0)
+    }
+
+    pub async fn delete_webhook_by_task(&self, task_id: ObjectId) -> Result<bool> {
+        let collection = self.webhooks();
+        let result = collection.delete_one(doc! { "task_id": task_id }).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn create_secret(&self, secret: &Secret) -> Result<ObjectId> {
+        let collection = self.secrets();
+        let result = collection.insert_one(secret).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
+    pub async fn get_secret_by_name(&self, name: &str) -> Result<Option<Secret>> {
+        let collection = self.secrets();
+        let secret = collection.find_one(doc! { "name": name }).await?;
+        Ok(secret)
+    }
+
+    pub async fn find_secrets(&self, filter: Option<Document>) -> Result<Vec<Secret>> {
+        let collection = self.secrets();
+        let mut cursor = collection
+            .find(filter.unwrap_or_default())
+            .sort(doc! { "name": 1 })
+            .await?;
+        let mut secrets = Vec::new();
+        while let Some(secret) = cursor.try_next().await? {
+            secrets.push(secret);
+        }
+        Ok(secrets)
+    }
+
+    pub async fn update_secret_by_name(&self, name: &str, update: Document) -> Result<bool> {
+        let collection = self.secrets();
+        let result = collection.update_one(doc! { "name": name }, update).await?;
+        Ok(result.matched_count > 0)
+    }
+
+    pub async fn delete_secret_by_name(&self, name: &str) -> Result<bool> {
+        let collection = self.secrets();
+        let result = collection.delete_one(doc! { "name": name }).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn create_wasm_module(&self, module: &WasmModule) -> Result<ObjectId> {
+        let collection = self.wasm_modules();
+        let result = collection.insert_one(module).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
+    pub async fn get_wasm_module_by_name(&self, name: &str) -> Result<Option<WasmModule>> {
+        let collection = self.wasm_modules();
+        let module = collection.find_one(doc! { "name": name }).await?;
+        Ok(module)
+    }
+
+    /// 查询模块列表，不读取模块内容
+    pub async fn find_wasm_modules(&self) -> Result<Vec<WasmModuleResponse>> {
+        let collection = self.wasm_modules().clone_with_type::<WasmModuleResponse>();
+        let mut cursor = collection
+            .find(doc! {})
+            .projection(doc! { "data": 0 })
+            .sort(doc! { "name": 1 })
+            .await?;
+        let mut modules = Vec::new();
+        while let Some(module) = cursor.try_next().await? {
+            modules.push(module);
+        }
+        Ok(modules)
+    }
+
+    pub async fn delete_wasm_module_by_name(&self, name: &str) -> Result<bool> {
+        let collection = self.wasm_modules();
+        let result = collection.delete_one(doc! { "name": name }).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    pub async fn create_grpc_descriptor_set(&self, set: &GrpcDescriptorSet) -> Result<ObjectId> {
+        let collection = self.grpc_descriptor_sets();
+        let result = collection.insert_one(set).await?;
+        Ok(result.inserted_id.as_object_id().unwrap())
+    }
+
+    pub async fn get_grpc_descriptor_set_by_name(
+        &self,
+        name: &str,
+    ) -> Result<Option<GrpcDescriptorSet>> {
+        let collection = self.grpc_descriptor_sets();
+        let set = collection.find_one(doc! { "name": name }).await?;
+        Ok(set)
+    }
+
+    /// 查询描述符集列表，不读取描述符集内容
+    pub async fn find_grpc_descriptor_sets(&self) -> Result<Vec<GrpcDescriptorSetResponse>> {
+        let collection = self
+            .grpc_descriptor_sets()
+            .clone_with_type::<GrpcDescriptorSetResponse>();
+        let mut cursor = collection
+            .find(doc! {})
+            .projection(doc! { "data": 0 })
+            .sort(doc! { "name": 1 })
+            .await?;
+        let mut sets = Vec::new();
+        while let Some(set) = cursor.try_next().await? {
+            sets.push(set);
+        }
+        Ok(sets)
+    }
+
+    pub async fn delete_grpc_descriptor_set_by_name(&self, name: &str) -> Result<bool> {
+        let collection = self.grpc_descriptor_sets();
+        let result = collection.delete_one(doc! { "name": name }).await?;
+        Ok(result.deleted_count > 0)
+    }
+
     pub async fn clear_all_data(&self) -> Result<()> {
         self.tasks().delete_many(doc! {}).await?;
         self.task_instances().delete_many(doc! {}).await?;
         self.execution_logs().delete_many(doc! {}).await?;
+        self.backfill_jobs().delete_many(doc! {}).await?;
+        self.sla_events().delete_many(doc! {}).await?;
+        self.pauses().delete_many(doc! {}).await?;
+        self.webhooks().delete_many(doc! {}).await?;
+        self.secrets().delete_many(doc! {}).await?;
+        self.wasm_modules().delete_many(doc! {}).await?;
+        self.grpc_descriptor_sets().delete_many(doc! {}).await?;
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -1,13 +1,17 @@
 use chrono::{DateTime, Utc};
 use mongodb::bson::{Bson, oid::ObjectId};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum TaskType {
     Command,
     Http,
+    Script,
+    Wasm,
+    Grpc,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -18,6 +22,35 @@ pub enum TaskStatus {
     Success,
     Failed,
     Cancelled,
+    /// 超过过期时限仍未开始执行，已被取消
+    Expired,
+    /// 执行超过超时时间被终止
+    #[serde(rename = "timed_out")]
+    TimedOut,
+    /// 执行器停止时仍未执行完，已被中断
+    Interrupted,
+}
+
+/// SLA 事件类型
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaEventKind {
+    /// 未在 `start_sla_seconds` 内开始执行
+    StartLate,
+    /// 执行时长超过 `run_sla_seconds`
+    RunOverrun,
+    /// 超过过期时限仍未开始执行
+    Expired,
+}
+
+impl SlaEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlaEventKind::StartLate => "start_late",
+            SlaEventKind::RunOverrun => "run_overrun",
+            SlaEventKind::Expired => "expired",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -25,6 +58,8 @@ pub enum TaskStatus {
 pub enum TriggeredBy {
     Scheduler,
     Manual,
+    Backfill,
+    Webhook,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +69,24 @@ pub enum TaskPayload {
         command: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         timeout_seconds: Option<i32>,
+        /// 额外的环境变量，覆盖执行器进程的同名变量
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        env: Option<BTreeMap<String, String>>,
+        /// 工作目录（绝对路径）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        working_dir: Option<String>,
+        /// 以指定的系统用户身份运行
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        run_as_user: Option<String>,
+        /// 写入命令标准输入的内容
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stdin: Option<String>,
+        /// 资源限制
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limits: Option<ResourceLimits>,
+        /// 隔离选项
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sandbox: Option<SandboxOptions>,
     },
     Http {
         url: String,
@@ -45,6 +98,54 @@ pub enum TaskPayload {
         body: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         timeout_seconds: Option<i32>,
+        /// 响应断言，未设置时 2xx 即视为成功
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        assertions: Option<HttpAssertions>,
+    },
+    /// 内联脚本：写入临时文件后由解释器执行，脚本内容不做模板渲染
+    Script {
+        /// 解释器，必须在 [`SCRIPT_INTERPRETERS`] 中
+        interpreter: String,
+        script: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timeout_seconds: Option<i32>,
+        /// 额外的环境变量，覆盖执行器进程的同名变量
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        env: Option<BTreeMap<String, String>>,
+        /// 工作目录（绝对路径）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        working_dir: Option<String>,
+        /// 以指定的系统用户身份运行
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        run_as_user: Option<String>,
+        /// 写入脚本标准输入的内容
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stdin: Option<String>,
+        /// 资源限制
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limits: Option<ResourceLimits>,
+        /// 隔离选项
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sandbox: Option<SandboxOptions>,
+    },
+    /// WASI 模块：在嵌入的 wasm 运行时中执行，只能访问显式授予的目录与环境变量
+    Wasm {
+        wasm: WasmSpec,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timeout_seconds: Option<i32>,
+        /// 模块可见的环境变量（不继承执行器的环境变量）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        env: Option<BTreeMap<String, String>>,
+        /// 写入模块标准输入的内容
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stdin: Option<String>,
+    },
+    /// gRPC 一元调用：按描述符将 JSON 请求编码为 protobuf 后调用
+    Grpc {
+        grpc: GrpcSpec,
+        /// 调用的截止时间（秒），同时作为 `grpc-timeout` 发送给服务端
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timeout_seconds: Option<i32>,
     },
 }
 
@@ -69,6 +170,9 @@ pub struct Task {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub dependency_ids: Vec<ObjectId>,
+    /// 任务标签，可用于按标签暂停
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tags: Vec<String>,
     #[serde(rename = "type")]
     pub task_type: TaskType,
     pub schedule: String,
@@ -78,6 +182,27 @@ pub struct Task {
     pub timeout_seconds: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_retries: Option<i32>,
+    /// 业务优先级（0-9，越大越优先），同时映射为 RabbitMQ 消息优先级
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    /// 相对计划执行时间的截止期限（秒），用于计算紧迫度
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_seconds: Option<i32>,
+    /// 计划执行时间后应开始执行的时限（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_sla_seconds: Option<i32>,
+    /// 开始执行后应完成的时限（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_sla_seconds: Option<i32>,
+    /// 同时处于待执行或执行中的实例上限
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_instances: Option<i32>,
+    /// 时间窗口内的触发次数上限
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<TaskRateLimit>,
+    /// 载荷模板中可引用的参数（`{{ params.<name> }}`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<TaskParameter>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
@@ -90,6 +215,383 @@ pub struct Task {
     pub deleted_at: Option<DateTime<Utc>>,
 }
 
+/// 任务优先级上限，同时作为队列的 `x-max-priority`
+pub const MAX_TASK_PRIORITY: i32 = 9;
+
+/// 任务触发频率限制：任意 `window_seconds` 秒内最多触发 `max_runs` 次
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TaskRateLimit {
+    pub max_runs: i32,
+    pub window_seconds: i32,
+}
+
+/// 命令任务的资源限制，执行器通过 rlimit 施加在命令进程上，未设置的项不限制
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct ResourceLimits {
+    /// CPU 时间（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_seconds: Option<u64>,
+    /// 虚拟地址空间（字节）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_bytes: Option<u64>,
+    /// 打开文件数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_files: Option<u64>,
+    /// 单个输出文件的大小（字节）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_size_bytes: Option<u64>,
+    /// 运行用户的进程数，按 uid 计数，只在设置 `run_as_user` 时生效
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<u64>,
+}
+
+/// 命令任务的隔离选项，通过 Linux 命名空间实现，需要执行器以 root 运行
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct SandboxOptions {
+    /// 在独立的网络命名空间中运行，不能访问网络
+    #[serde(default)]
+    pub no_network: bool,
+    /// 在独立的挂载命名空间中以只读方式挂载根文件系统及所有子挂载点，/tmp 为独立的 tmpfs
+    #[serde(default)]
+    pub read_only_root: bool,
+}
+
+/// wasm 模块来源
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WasmModuleRef {
+    /// 通过 `/api/wasm-modules` 上传、保存在数据库中的模块名称
+    Name(String),
+    /// 执行器主机上的模块文件（绝对路径）
+    Path(String),
+}
+
+/// 授予 wasm 模块访问的主机目录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WasmDirGrant {
+    /// 主机上的目录（绝对路径）
+    pub host_path: String,
+    /// 模块内看到的路径
+    pub guest_path: String,
+    /// 是否允许写入，默认只读
+    #[serde(default)]
+    pub writable: bool,
+}
+
+/// wasm 任务的模块、参数与资源限制
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WasmSpec {
+    pub module: WasmModuleRef,
+    /// 传给模块的参数（不含程序名）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// 授予访问的目录，未授予时模块不能访问文件系统
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dirs: Vec<WasmDirGrant>,
+    /// fuel 上限（约等于执行的指令数），未设置时取执行器默认值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<u64>,
+    /// 线性内存上限（字节），不超过执行器的 `wasm_max_memory_bytes`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_bytes: Option<u64>,
+}
+
+/// gRPC 任务的目标方法、请求与视为成功的状态码
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GrpcSpec {
+    /// 服务地址，如 `http://orders.internal:50051`，`https://` 时使用 TLS
+    pub endpoint: String,
+    /// 完整方法名，如 `orders.v1.OrderService/CloseExpired`
+    pub method: String,
+    /// 编码请求使用的描述符集（通过 `/api/grpc-descriptor-sets` 上传），未设置时通过服务端反射获取
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descriptor_set: Option<String>,
+    /// 请求消息（protobuf 的 JSON 映射），其中的字符串支持模板，未设置时发送空消息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<serde_json::Value>,
+    /// 请求元数据，值支持模板
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, String>>,
+    /// 视为成功的状态码名称（如 `OK`、`NOT_FOUND`），未设置时只有 `OK` 视为成功
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_codes: Option<Vec<String>>,
+}
+
+/// HTTP 任务的响应断言，全部通过时实例才视为成功
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HttpAssertions {
+    /// 允许的状态码，未设置时要求 2xx
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Vec<u16>>,
+    /// 响应体必须包含的子串
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_contains: Option<String>,
+    /// 响应体必须匹配的正则表达式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_regex: Option<String>,
+    /// 对 JSON 响应体的 JSONPath 相等断言
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub json_path: Vec<JsonPathAssertion>,
+    /// 响应中必须存在的响应头（不区分大小写）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers_present: Vec<String>,
+    /// 从发送请求到读完响应体的最长耗时（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_latency_ms: Option<u64>,
+}
+
+/// JSONPath 相等断言：路径必须恰好选中一个值且与期望值相等
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JsonPathAssertion {
+    pub path: String,
+    pub equals: serde_json::Value,
+}
+
+/// 响应断言的种类
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssertionKind {
+    Status,
+    Header,
+    MaxLatency,
+    BodyContains,
+    BodyRegex,
+    JsonPath,
+}
+
+/// 未通过的响应断言
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AssertionFailure {
+    pub kind: AssertionKind,
+    pub message: String,
+}
+
+/// 任务参数类型
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterType {
+    #[default]
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+impl ParameterType {
+    /// 参数值是否符合类型
+    pub fn accepts(&self, value: &serde_json::Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+        }
+    }
+}
+
+/// 任务参数定义
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskParameter {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub param_type: ParameterType,
+    /// 默认值，为空表示触发时必须提供
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 允许的取值
+    #[serde(rename = "enum", default, skip_serializing_if = "Option::is_none")]
+    pub allowed_values: Option<Vec<serde_json::Value>>,
+    /// 取值（字符串形式）须完整匹配的正则表达式
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+impl TaskParameter {
+    /// 是否声明了取值约束（`enum` 或 `pattern`）
+    pub fn is_constrained(&self) -> bool {
+        self.allowed_values.is_some() || self.pattern.is_some()
+    }
+
+    fn pattern_regex(&self) -> Result<Option<regex::Regex>, String> {
+        self.pattern
+            .as_deref()
+            .map(|pattern| {
+                regex::Regex::new(&format!("^(?:{})$", pattern))
+                    .map_err(|e| format!("参数 {} 的 pattern 无效: {}", self.name, e))
+            })
+            .transpose()
+    }
+
+    /// 校验取值的类型与约束，返回用于模板渲染的字符串值
+    fn check_value(&self, value: &serde_json::Value) -> Result<String, String> {
+        if !self.param_type.accepts(value) {
+            return Err(format!(
+                "参数 {} 的类型应为 {:?}",
+                self.name, self.param_type
+            ));
+        }
+        if let Some(allowed) = &self.allowed_values
+            && !allowed.contains(value)
+        {
+            return Err(format!("参数 {} 的取值不在 enum 中", self.name));
+        }
+        let rendered = match value {
+            serde_json::Value::String(value) => value.clone(),
+            other => other.to_string(),
+        };
+        if let Some(regex) = self.pattern_regex()?
+            && !regex.is_match(&rendered)
+        {
+            return Err(format!("参数 {} 的取值不匹配 pattern", self.name));
+        }
+        Ok(rendered)
+    }
+}
+
+/// 验证任务参数定义：名称合法且唯一，默认值符合类型与约束
+pub fn validate_parameters(parameters: &[TaskParameter]) -> Result<(), String> {
+    if parameters.len() > 20 {
+        return Err("任务参数不能超过20个".to_string());
+    }
+    let mut names = std::collections::HashSet::new();
+    for parameter in parameters {
+        let valid_name = parameter.name.len() <= 50
+            && parameter
+                .name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && parameter
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(format!(
+                "无效的参数名: {}（只能包含字母、数字和下划线，且不能以数字开头）",
+                parameter.name
+            ));
+        }
+        if !names.insert(parameter.name.as_str()) {
+            return Err(format!("参数名重复: {}", parameter.name));
+        }
+        if let Some(default) = &parameter.default
+            && !parameter.param_type.accepts(default)
+        {
+            return Err(format!("参数 {} 的默认值类型不匹配", parameter.name));
+        }
+        if let Some(allowed) = &parameter.allowed_values {
+            if allowed.is_empty() || allowed.len() > 100 {
+                return Err(format!("参数 {} 的 enum 须包含1到100个值", parameter.name));
+            }
+            if !allowed
+                .iter()
+                .all(|value| parameter.param_type.accepts(value))
+            {
+                return Err(format!("参数 {} 的 enum 值类型不匹配", parameter.name));
+            }
+        }
+        parameter.pattern_regex()?;
+        if let Some(default) = &parameter.default {
+            parameter.check_value(default)?;
+        }
+    }
+    Ok(())
+}
+
+/// 校验不可信来源（Webhook）的参数覆盖值：命令与脚本任务只允许覆盖声明了 `enum` 或 `pattern` 的参数
+pub fn check_untrusted_overrides(
+    task: &Task,
+    overrides: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> Result<(), String> {
+    if !matches!(task.task_type, TaskType::Command | TaskType::Script) {
+        return Ok(());
+    }
+    for name in overrides.into_iter().flat_map(|overrides| overrides.keys()) {
+        if let Some(parameter) = task.parameters.iter().find(|p| &p.name == name)
+            && !parameter.is_constrained()
+        {
+            return Err(format!(
+                "参数 {} 未声明 enum 或 pattern，不能通过 Webhook 覆盖",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 合并参数默认值与触发时的覆盖值，返回用于模板渲染的字符串值
+pub fn resolve_parameters(
+    parameters: &[TaskParameter],
+    overrides: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> Result<std::collections::BTreeMap<String, String>, String> {
+    if let Some(overrides) = overrides
+        && let Some(unknown) = overrides
+            .keys()
+            .find(|key| !parameters.iter().any(|p| &p.name == *key))
+    {
+        return Err(format!("未定义的参数: {}", unknown));
+    }
+
+    let mut resolved = std::collections::BTreeMap::new();
+    for parameter in parameters {
+        let value = overrides
+            .and_then(|overrides| overrides.get(&parameter.name))
+            .or(parameter.default.as_ref())
+            .ok_or_else(|| format!("缺少参数: {}", parameter.name))?;
+        resolved.insert(parameter.name.clone(), parameter.check_value(value)?);
+    }
+    Ok(resolved)
+}
+
+impl Task {
+    /// 实际生效的优先级（未设置时为 0）
+    pub fn effective_priority(&self) -> i32 {
+        self.priority.unwrap_or(0).clamp(0, MAX_TASK_PRIORITY)
+    }
+}
+
+#[cfg(test)]
+impl Task {
+    /// 单元测试使用的任务：启用、每分钟执行一次 `echo ok`，其余字段为空
+    pub(crate) fn for_test(name: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Some(ObjectId::new()),
+            name: name.to_string(),
+            description: None,
+            dependency_ids: Vec::new(),
+            tags: Vec::new(),
+            task_type: TaskType::Command,
+            schedule: "0 * * * * *".to_string(),
+            enabled: true,
+            payload: TaskPayload::Command {
+                command: "echo ok".to_string(),
+                timeout_seconds: None,
+                env: None,
+                working_dir: None,
+                run_as_user: None,
+                stdin: None,
+                limits: None,
+                sandbox: None,
+            },
+            timeout_seconds: None,
+            max_retries: None,
+            priority: None,
+            deadline_seconds: None,
+            start_sla_seconds: None,
+            run_sla_seconds: None,
+            max_concurrent_instances: None,
+            rate_limit: None,
+            parameters: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionResult {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -98,6 +600,9 @@ pub struct ExecutionResult {
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exit_code: Option<i32>,
+    /// HTTP 任务首个未通过的响应断言
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_assertion: Option<AssertionFailure>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -127,6 +632,18 @@ pub struct TaskInstance {
     pub result: Option<ExecutionResult>,
     #[serde(rename = "triggered_by")]
     pub triggered_by: TriggeredBy,
+    /// 分发时计算出的调度分数（仅调度器生成的实例有值，便于排查）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_score: Option<f64>,
+    /// SLA 检查标记的违规类型
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sla_flags: Vec<SlaEventKind>,
+    /// Webhook 触发时携带的 JSON 数据
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_payload: Option<serde_json::Value>,
+    /// 手动或 Webhook 触发时覆盖的任务参数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: DateTime<Utc>,
 }
@@ -146,6 +663,9 @@ pub struct ExecutionLog {
         with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
     )]
     pub start_time: Option<DateTime<Utc>>,
+    /// 实际开始时间相对计划执行时间的延迟（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_latency_ms: Option<i64>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub end_time: DateTime<Utc>,
     pub status: TaskStatus,
@@ -159,6 +679,31 @@ pub struct ExecutionLog {
     pub triggered_by: TriggeredBy,
 }
 
+/// 单个任务在一次扫描中的分发明细
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DispatchTaskDetail {
+    pub task_id: ObjectId,
+    pub task_name: String,
+    /// 生成的候选实例数
+    pub candidates: i32,
+    /// 已存在实例而跳过的调度时间数
+    pub duplicates: i32,
+    pub dispatched: i32,
+    /// 创建或发布失败的候选实例数
+    pub failed: i32,
+    /// 因并发、频率限制或消息队列不可用推迟到后续扫描的候选实例数
+    #[serde(default)]
+    pub deferred: i32,
+    /// 最后一次推迟的原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defer_reason: Option<String>,
+    /// 候选实例的调度分数，与候选实例按调度时间一一对应
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scores: Vec<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DispatchLog {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
@@ -180,12 +725,180 @@ pub struct DispatchLog {
     pub scan_window_end: DateTime<Utc>,
     #[serde(rename = "total_tasks")]
     pub total_tasks: i32,
+    /// 扣除暂停中任务后参与分发的任务数
     #[serde(rename = "enabled_tasks")]
     pub enabled_tasks: i32,
+    /// 处于生效暂停范围内、本次不分发的任务数
+    #[serde(default)]
+    pub paused_tasks: i32,
     #[serde(rename = "dispatched_instances")]
     pub dispatched_instances: i32,
+    /// 处理失败的任务数（Cron 解析失败或实例创建、发布失败）
+    #[serde(default)]
+    pub failed_tasks: i32,
+    /// 因并发、频率限制或消息队列不可用推迟的候选实例数
+    #[serde(default)]
+    pub deferred_instances: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    /// 按任务的分发明细，仅记录本次扫描有候选实例、重复或失败的任务
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub task_details: Vec<DispatchTaskDetail>,
+    /// 推迟到后续扫描的候选实例，分发器重启时从最近一条调度日志恢复
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deferred_slots: Vec<DeferredSlot>,
+}
+
+/// 推迟分发的调度时间
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeferredSlot {
+    pub task_id: ObjectId,
+    pub task_name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub scheduled_time: DateTime<Utc>,
+}
+
+/// SLA 事件：实例未按时开始、执行超时或过期
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaEvent {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub task_id: ObjectId,
+    pub task_name: String,
+    pub instance_id: ObjectId,
+    pub kind: SlaEventKind,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub scheduled_time: DateTime<Utc>,
+    /// 触发该事件的时限（秒）
+    pub threshold_seconds: i64,
+    /// 检测时已超出的时长（秒）
+    pub overdue_seconds: i64,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub detected_at: DateTime<Utc>,
+}
+
+/// 任务的入站 Webhook 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub task_id: ObjectId,
+    /// 出现在 Webhook 地址中的随机令牌
+    pub token: String,
+    /// HMAC-SHA256 签名密钥，为空表示不校验签名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub last_triggered_at: Option<DateTime<Utc>>,
+}
+
+/// 暂停范围
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PauseScope {
+    /// 暂停所有任务
+    Global,
+    /// 暂停带有指定标签的任务
+    Tag { tag: String },
+    /// 暂停指定的任务
+    Tasks { task_ids: Vec<ObjectId> },
+}
+
+/// 暂停生效时对已在队列中的实例的处理方式
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum QueuedAction {
+    /// 继续执行已入队的实例
+    #[default]
+    Run,
+    /// 取消暂停开始前已入队、仍待执行的实例
+    Cancel,
+}
+
+/// 暂停（维护模式），生效期间分发器与重试管理器不再为范围内的任务产生新实例
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pause {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub scope: PauseScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub queued_action: QueuedAction,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub starts_at: DateTime<Utc>,
+    /// 自动结束时间，为空表示需要手动恢复
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub ends_at: Option<DateTime<Utc>>,
+    /// 手动恢复时间
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub resumed_at: Option<DateTime<Utc>>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl Pause {
+    /// 暂停在指定时间是否生效
+    pub fn is_active_at(&self, now: &DateTime<Utc>) -> bool {
+        self.starts_at <= *now
+            && self.resumed_at.is_none()
+            && self.ends_at.is_none_or(|ends_at| ends_at > *now)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackfillStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// 回填作业：对 `[range_start, range_end)` 内错过的调度时间补发实例
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillJob {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub task_id: ObjectId,
+    pub task_name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub range_start: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub range_end: DateTime<Utc>,
+    pub status: BackfillStatus,
+    /// 发布速率（个/秒）
+    pub rate_per_sec: u32,
+    /// 区间内的调度时间总数
+    pub total_slots: i32,
+    /// 已有成功或进行中实例而跳过的数量
+    pub skipped_slots: i32,
+    /// 已创建并发布的回填实例数量
+    pub dispatched_instances: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub finished_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -195,16 +908,409 @@ pub struct CreateTaskRequest {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub dependency_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tags: Vec<String>,
     pub task_type: Option<String>,
     pub schedule: String,
     #[serde(default)]
     pub enabled: bool,
     pub command: Option<String>,
     pub url: Option<String>,
+    /// 脚本任务的解释器
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<String>,
+    /// 脚本任务的脚本内容
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    /// wasm 任务的模块、参数与资源限制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wasm: Option<WasmSpec>,
+    /// gRPC 任务的目标方法与请求
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grpc: Option<GrpcSpec>,
+    /// 额外的环境变量，覆盖执行器进程的同名变量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    /// 工作目录（绝对路径）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// 以指定的系统用户身份运行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_as_user: Option<String>,
+    /// 写入命令标准输入的内容
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    /// 命令任务的资源限制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ResourceLimits>,
+    /// 命令任务的隔离选项
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxOptions>,
+    /// HTTP 任务的响应断言
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assertions: Option<HttpAssertions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout_seconds: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_retries: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_seconds: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_sla_seconds: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_sla_seconds: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_instances: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<TaskRateLimit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<TaskParameter>,
+}
+
+/// 验证任务优先级
+pub fn validate_priority(priority: i32) -> Result<(), String> {
+    if !(0..=MAX_TASK_PRIORITY).contains(&priority) {
+        return Err(format!("优先级必须在0到{}之间", MAX_TASK_PRIORITY));
+    }
+    Ok(())
+}
+
+/// 验证截止期限
+pub fn validate_deadline_seconds(deadline_seconds: i32) -> Result<(), String> {
+    if deadline_seconds <= 0 {
+        return Err("截止期限必须大于0".to_string());
+    }
+    if deadline_seconds > 86400 {
+        return Err("截止期限不能超过86400秒".to_string());
+    }
+    Ok(())
+}
+
+/// 验证任务标签
+pub fn validate_tags(tags: &[String]) -> Result<(), String> {
+    if tags.len() > 20 {
+        return Err("任务标签不能超过20个".to_string());
+    }
+    if tags
+        .iter()
+        .any(|tag| tag.trim().is_empty() || tag.len() > 50)
+    {
+        return Err("任务标签不能为空且长度不能超过50个字符".to_string());
+    }
+    Ok(())
+}
+
+/// 验证 SLA 时限
+pub fn validate_sla_seconds(sla_seconds: i32) -> Result<(), String> {
+    if sla_seconds <= 0 {
+        return Err("SLA 时限必须大于0".to_string());
+    }
+    if sla_seconds > 86400 {
+        return Err("SLA 时限不能超过86400秒".to_string());
+    }
+    Ok(())
+}
+
+/// 验证任务并发实例上限
+pub fn validate_max_concurrent_instances(max_concurrent_instances: i32) -> Result<(), String> {
+    if max_concurrent_instances <= 0 {
+        return Err("并发实例上限必须大于0".to_string());
+    }
+    if max_concurrent_instances > 1000 {
+        return Err("并发实例上限不能超过1000".to_string());
+    }
+    Ok(())
+}
+
+/// 验证任务触发频率限制
+pub fn validate_rate_limit(rate_limit: &TaskRateLimit) -> Result<(), String> {
+    if rate_limit.max_runs <= 0 {
+        return Err("频率限制的触发次数必须大于0".to_string());
+    }
+    if rate_limit.window_seconds <= 0 {
+        return Err("频率限制的时间窗口必须大于0".to_string());
+    }
+    if rate_limit.window_seconds > 86400 {
+        return Err("频率限制的时间窗口不能超过86400秒".to_string());
+    }
+    Ok(())
+}
+
+/// 环境变量数量上限
+pub const MAX_COMMAND_ENV_VARS: usize = 50;
+/// 标准输入内容的字节数上限
+pub const MAX_COMMAND_STDIN_BYTES: usize = 1024 * 1024;
+
+/// 脚本任务允许使用的解释器，执行器按 PATH 查找
+pub const SCRIPT_INTERPRETERS: [&str; 6] = ["sh", "bash", "python3", "node", "perl", "ruby"];
+/// 脚本内容的字节数上限
+pub const MAX_SCRIPT_BYTES: usize = 256 * 1024;
+
+/// 验证脚本任务的解释器与脚本内容
+pub fn validate_script(interpreter: &str, script: &str) -> Result<(), String> {
+    if !SCRIPT_INTERPRETERS.contains(&interpreter) {
+        return Err(format!(
+            "不支持的解释器: {}，可选: {}",
+            interpreter,
+            SCRIPT_INTERPRETERS.join(", ")
+        ));
+    }
+    if script.trim().is_empty() {
+        return Err("脚本内容不能为空".to_string());
+    }
+    if script.len() > MAX_SCRIPT_BYTES {
+        return Err(format!("脚本内容不能超过{}字节", MAX_SCRIPT_BYTES));
+    }
+    if script.contains('\0') {
+        return Err("脚本内容不能包含空字符".to_string());
+    }
+    Ok(())
+}
+
+/// wasm 模块的字节数上限
+pub const MAX_WASM_MODULE_BYTES: usize = 10 * 1024 * 1024;
+/// wasm 模块内存上限的下限（一个 wasm 页）
+pub const MIN_WASM_MEMORY_BYTES: u64 = 64 * 1024;
+
+/// 验证 wasm 模块名称：1-64 个字母、数字、`_`、`-` 或 `.`
+pub fn validate_wasm_module_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 64 {
+        return Err("模块名称长度必须在1到64个字符之间".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(format!("无效的模块名称: {}", name));
+    }
+    Ok(())
+}
+
+/// 验证 wasm 任务的模块来源、参数、目录授权与资源限制
+pub fn validate_wasm_spec(spec: &WasmSpec) -> Result<(), String> {
+    match &spec.module {
+        WasmModuleRef::Name(name) => validate_wasm_module_name(name)?,
+        WasmModuleRef::Path(path) => {
+            if !path.starts_with('/') || path.contains('\0') {
+                return Err("模块路径必须是绝对路径".to_string());
+            }
+        }
+    }
+    if spec.args.len() > 64 {
+        return Err("模块参数不能超过64个".to_string());
+    }
+    if spec.args.iter().any(|arg| arg.contains('\0')) {
+        return Err("模块参数不能包含空字符".to_string());
+    }
+    if spec.dirs.len() > 16 {
+        return Err("授权目录不能超过16个".to_string());
+    }
+    for dir in &spec.dirs {
+        if !dir.host_path.starts_with('/') || dir.host_path.contains('\0') {
+            return Err(format!("授权目录必须是绝对路径: {}", dir.host_path));
+        }
+        if dir.guest_path.is_empty() || dir.guest_path.contains('\0') {
+            return Err("模块内路径不能为空".to_string());
+        }
+    }
+    if spec.fuel == Some(0) {
+        return Err("fuel 必须大于0".to_string());
+    }
+    if let Some(memory_bytes) = spec.memory_bytes
+        && memory_bytes < MIN_WASM_MEMORY_BYTES
+    {
+        return Err(format!("内存上限不能小于{}字节", MIN_WASM_MEMORY_BYTES));
+    }
+    Ok(())
+}
+
+/// gRPC 状态码名称，下标即状态码
+pub const GRPC_STATUS_CODES: [&str; 17] = [
+    "OK",
+    "CANCELLED",
+    "UNKNOWN",
+    "INVALID_ARGUMENT",
+    "DEADLINE_EXCEEDED",
+    "NOT_FOUND",
+    "ALREADY_EXISTS",
+    "PERMISSION_DENIED",
+    "RESOURCE_EXHAUSTED",
+    "FAILED_PRECONDITION",
+    "ABORTED",
+    "OUT_OF_RANGE",
+    "UNIMPLEMENTED",
+    "INTERNAL",
+    "UNAVAILABLE",
+    "DATA_LOSS",
+    "UNAUTHENTICATED",
+];
+
+/// gRPC 描述符集的字节数上限
+pub const MAX_GRPC_DESCRIPTOR_SET_BYTES: usize = 4 * 1024 * 1024;
+/// gRPC 请求元数据的条数上限
+pub const MAX_GRPC_METADATA: usize = 50;
+
+/// 验证 gRPC 描述符集名称：1-64 个字母、数字、`_`、`-` 或 `.`
+pub fn validate_grpc_descriptor_set_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 64 {
+        return Err("描述符集名称长度必须在1到64个字符之间".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(format!("无效的描述符集名称: {}", name));
+    }
+    Ok(())
+}
+
+/// 拆分完整方法名 `package.Service/Method`，允许以 `/` 开头
+pub fn split_grpc_method(method: &str) -> Result<(&str, &str), String> {
+    let invalid = || format!("方法名必须为 package.Service/Method 格式: {}", method);
+    let (service, name) = method
+        .strip_prefix('/')
+        .unwrap_or(method)
+        .split_once('/')
+        .ok_or_else(invalid)?;
+    let is_identifier = |part: &str| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    if !service.split('.').all(is_identifier) || !is_identifier(name) {
+        return Err(invalid());
+    }
+    Ok((service, name))
+}
+
+/// 验证 gRPC 任务的地址、方法、描述符集、元数据与状态码
+pub fn validate_grpc_spec(spec: &GrpcSpec) -> Result<(), String> {
+    let host = spec
+        .endpoint
+        .strip_prefix("http://")
+        .or_else(|| spec.endpoint.strip_prefix("https://"))
+        .ok_or_else(|| "gRPC 地址必须以 http:// 或 https:// 开头".to_string())?;
+    if host.is_empty() || host.starts_with('/') || host.chars().any(char::is_whitespace) {
+        return Err(format!("无效的 gRPC 地址: {}", spec.endpoint));
+    }
+    split_grpc_method(&spec.method)?;
+    if let Some(name) = &spec.descriptor_set {
+        validate_grpc_descriptor_set_name(name)?;
+    }
+    if spec
+        .request
+        .as_ref()
+        .is_some_and(|request| !request.is_object())
+    {
+        return Err("gRPC 请求必须是 JSON 对象".to_string());
+    }
+    if let Some(metadata) = &spec.metadata {
+        if metadata.len() > MAX_GRPC_METADATA {
+            return Err(format!("元数据不能超过{}条", MAX_GRPC_METADATA));
+        }
+        for (key, value) in metadata {
+            let valid_key = !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.".contains(c));
+            // grpc- 前缀为协议保留，二进制元数据（-bin 后缀）不支持
+            if !valid_key || key.starts_with("grpc-") || key.ends_with("-bin") {
+                return Err(format!("无效的元数据名称: {}", key));
+            }
+            if value.chars().any(|c| c.is_control()) {
+                return Err(format!("元数据 {} 的值不能包含控制字符", key));
+            }
+        }
+    }
+    if let Some(codes) = &spec.expected_codes {
+        if codes.is_empty() {
+            return Err("expected_codes 不能为空".to_string());
+        }
+        if let Some(code) = codes
+            .iter()
+            .find(|code| !GRPC_STATUS_CODES.contains(&code.as_str()))
+        {
+            return Err(format!("未知的 gRPC 状态码: {}", code));
+        }
+    }
+    Ok(())
+}
+
+/// 验证命令任务的环境变量、工作目录、运行用户与标准输入
+pub fn validate_command_options(
+    env: Option<&BTreeMap<String, String>>,
+    working_dir: Option<&str>,
+    run_as_user: Option<&str>,
+    stdin: Option<&str>,
+) -> Result<(), String> {
+    if let Some(env) = env {
+        if env.len() > MAX_COMMAND_ENV_VARS {
+            return Err(format!("环境变量不能超过{}个", MAX_COMMAND_ENV_VARS));
+        }
+        for (key, value) in env {
+            let mut chars = key.chars();
+            let valid_key = key.len() <= 128
+                && chars
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_key {
+                return Err(format!("无效的环境变量名: {}", key));
+            }
+            if value.contains('\0') {
+                return Err(format!("环境变量 {} 的值不能包含空字符", key));
+            }
+        }
+    }
+    if let Some(working_dir) = working_dir
+        && (!working_dir.starts_with('/') || working_dir.contains('\0'))
+    {
+        return Err("工作目录必须是绝对路径".to_string());
+    }
+    if let Some(user) = run_as_user {
+        let mut chars = user.chars();
+        let valid_user = user.len() <= 32
+            && chars
+                .next()
+                .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        if !valid_user {
+            return Err(format!("无效的运行用户: {}", user));
+        }
+    }
+    if let Some(stdin) = stdin
+        && stdin.len() > MAX_COMMAND_STDIN_BYTES
+    {
+        return Err(format!(
+            "标准输入内容不能超过{}字节",
+            MAX_COMMAND_STDIN_BYTES
+        ));
+    }
+    Ok(())
+}
+
+/// 内存限制下限，过小的地址空间无法启动 shell
+pub const MIN_COMMAND_MEMORY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// 验证命令任务的资源限制
+pub fn validate_resource_limits(limits: &ResourceLimits) -> Result<(), String> {
+    let fields = [
+        ("cpu_seconds", limits.cpu_seconds),
+        ("memory_bytes", limits.memory_bytes),
+        ("open_files", limits.open_files),
+        ("file_size_bytes", limits.file_size_bytes),
+        ("max_processes", limits.max_processes),
+    ];
+    if let Some((name, _)) = fields.iter().find(|(_, value)| *value == Some(0)) {
+        return Err(format!("资源限制 {} 必须大于0", name));
+    }
+    if limits
+        .memory_bytes
+        .is_some_and(|bytes| bytes < MIN_COMMAND_MEMORY_BYTES)
+    {
+        return Err(format!("内存限制不能小于{}字节", MIN_COMMAND_MEMORY_BYTES));
+    }
+    Ok(())
 }
 
 impl CreateTaskRequest {
@@ -249,6 +1355,32 @@ impl CreateTaskRequest {
             }
         }
 
+        // 验证优先级与截止期限
+        if let Some(priority) = self.priority {
+            validate_priority(priority)?;
+        }
+        if let Some(deadline_seconds) = self.deadline_seconds {
+            validate_deadline_seconds(deadline_seconds)?;
+        }
+
+        validate_tags(&self.tags)?;
+
+        // 验证 SLA 时限
+        if let Some(start_sla_seconds) = self.start_sla_seconds {
+            validate_sla_seconds(start_sla_seconds)?;
+        }
+        if let Some(run_sla_seconds) = self.run_sla_seconds {
+            validate_sla_seconds(run_sla_seconds)?;
+        }
+
+        // 验证并发与频率限制
+        if let Some(max_concurrent_instances) = self.max_concurrent_instances {
+            validate_max_concurrent_instances(max_concurrent_instances)?;
+        }
+        if let Some(rate_limit) = &self.rate_limit {
+            validate_rate_limit(rate_limit)?;
+        }
+
         // 验证任务类型
         let task_type = match self.task_type.as_deref() {
             Some("http") => {
@@ -263,6 +1395,27 @@ impl CreateTaskRequest {
                 }
                 TaskType::Command
             }
+            Some("wasm") => {
+                let Some(wasm) = &self.wasm else {
+                    return Err("wasm 任务必须提供模块".to_string());
+                };
+                validate_wasm_spec(wasm)?;
+                TaskType::Wasm
+            }
+            Some("grpc") => {
+                let Some(grpc) = &self.grpc else {
+                    return Err("gRPC 任务必须提供 grpc".to_string());
+                };
+                validate_grpc_spec(grpc)?;
+                TaskType::Grpc
+            }
+            Some("script") => {
+                let (Some(interpreter), Some(script)) = (&self.interpreter, &self.script) else {
+                    return Err("脚本任务必须提供解释器与脚本内容".to_string());
+                };
+                validate_script(interpreter, script)?;
+                TaskType::Script
+            }
             _ => {
                 // 默认为命令类型
                 if self.command.is_none() || self.command.as_ref().unwrap().is_empty() {
@@ -278,32 +1431,115 @@ impl CreateTaskRequest {
             .filter_map(|id| ObjectId::parse_str(id).ok())
             .collect();
 
-        let payload = if task_type == TaskType::Http {
-            TaskPayload::Http {
+        let has_command_options = self.env.is_some()
+            || self.working_dir.is_some()
+            || self.run_as_user.is_some()
+            || self.stdin.is_some()
+            || self.limits.is_some()
+            || self.sandbox.is_some();
+        if matches!(task_type, TaskType::Http | TaskType::Grpc) && has_command_options {
+            return Err(
+                "env、working_dir、run_as_user、stdin、limits 与 sandbox 仅适用于命令与脚本任务"
+                    .to_string(),
+            );
+        }
+        if task_type == TaskType::Wasm
+            && (self.working_dir.is_some()
+                || self.run_as_user.is_some()
+                || self.limits.is_some()
+                || self.sandbox.is_some())
+        {
+            return Err(
+                "wasm 任务不支持 working_dir、run_as_user、limits 与 sandbox，请使用 wasm 中的目录授权与资源限制"
+                    .to_string(),
+            );
+        }
+        if task_type != TaskType::Wasm && self.wasm.is_some() {
+            return Err("wasm 仅适用于 wasm 任务".to_string());
+        }
+        if task_type != TaskType::Grpc && self.grpc.is_some() {
+            return Err("grpc 仅适用于 gRPC 任务".to_string());
+        }
+        validate_command_options(
+            self.env.as_ref(),
+            self.working_dir.as_deref(),
+            self.run_as_user.as_deref(),
+            self.stdin.as_deref(),
+        )?;
+        if let Some(limits) = &self.limits {
+            validate_resource_limits(limits)?;
+        }
+        if let Some(assertions) = &self.assertions {
+            if task_type != TaskType::Http {
+                return Err("assertions 仅适用于 HTTP 任务".to_string());
+            }
+            crate::executor::assertion::validate_assertions(assertions)?;
+        }
+
+        let payload = match task_type {
+            TaskType::Http => TaskPayload::Http {
                 url: self.url.clone().unwrap_or_default(),
                 method: None,
                 headers: None,
                 body: None,
                 timeout_seconds: self.timeout_seconds,
-            }
-        } else {
-            TaskPayload::Command {
+                assertions: self.assertions.clone(),
+            },
+            TaskType::Command => TaskPayload::Command {
                 command: self.command.clone().unwrap_or_default(),
                 timeout_seconds: self.timeout_seconds,
-            }
+                env: self.env.clone(),
+                working_dir: self.working_dir.clone(),
+                run_as_user: self.run_as_user.clone(),
+                stdin: self.stdin.clone(),
+                limits: self.limits,
+                sandbox: self.sandbox,
+            },
+            TaskType::Wasm => TaskPayload::Wasm {
+                wasm: self.wasm.clone().unwrap(),
+                timeout_seconds: self.timeout_seconds,
+                env: self.env.clone(),
+                stdin: self.stdin.clone(),
+            },
+            TaskType::Grpc => TaskPayload::Grpc {
+                grpc: self.grpc.clone().unwrap(),
+                timeout_seconds: self.timeout_seconds,
+            },
+            TaskType::Script => TaskPayload::Script {
+                interpreter: self.interpreter.clone().unwrap_or_default(),
+                script: self.script.clone().unwrap_or_default(),
+                timeout_seconds: self.timeout_seconds,
+                env: self.env.clone(),
+                working_dir: self.working_dir.clone(),
+                run_as_user: self.run_as_user.clone(),
+                stdin: self.stdin.clone(),
+                limits: self.limits,
+                sandbox: self.sandbox,
+            },
         };
 
+        validate_parameters(&self.parameters)?;
+        crate::executor::template::validate_payload(&payload, &self.parameters)?;
+
         Ok(Task {
             id: None,
             name: self.name.clone(),
             description: self.description.clone(),
             dependency_ids,
+            tags: self.tags.clone(),
             task_type,
             schedule: self.schedule.clone(),
             enabled: self.enabled,
             payload,
             timeout_seconds: self.timeout_seconds,
             max_retries: self.max_retries,
+            priority: self.priority,
+            deadline_seconds: self.deadline_seconds,
+            start_sla_seconds: self.start_sla_seconds,
+            run_sla_seconds: self.run_sla_seconds,
+            max_concurrent_instances: self.max_concurrent_instances,
+            rate_limit: self.rate_limit,
+            parameters: self.parameters.clone(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
             deleted_at: None,
@@ -319,6 +1555,8 @@ pub struct UpdateTaskRequest {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dependency_ids: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schedule: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -329,16 +1567,328 @@ pub struct UpdateTaskRequest {
     pub command: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+    /// 额外的环境变量，覆盖执行器进程的同名变量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    /// 工作目录（绝对路径）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// 以指定的系统用户身份运行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_as_user: Option<String>,
+    /// 写入命令标准输入的内容
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    /// 命令任务的资源限制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ResourceLimits>,
+    /// 命令任务的隔离选项
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<SandboxOptions>,
+    /// HTTP 任务的响应断言
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assertions: Option<HttpAssertions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout_seconds: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_retries: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline_seconds: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_sla_seconds: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_sla_seconds: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_instances: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<TaskRateLimit>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Vec<TaskParameter>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TriggerTaskRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scheduled_time: Option<i64>,
+    /// 覆盖任务参数的默认值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// 回填请求，时间为 Unix 时间戳（秒），区间左闭右开
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillRequest {
+    pub from: i64,
+    pub to: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_per_sec: Option<u32>,
+}
+
+/// 创建（或轮换）Webhook 请求
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateWebhookRequest {
+    /// 是否生成签名密钥并要求请求携带 HMAC 签名
+    #[serde(default)]
+    pub signed: bool,
+}
+
+/// Webhook 详情，密钥只在创建时返回一次
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookResponse {
+    pub task_id: ObjectId,
+    pub token: String,
+    /// 触发地址（相对 API 前缀）
+    pub path: String,
+    pub signed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_triggered_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookResponse {
+    pub fn from_webhook(webhook: Webhook, reveal_secret: bool) -> Self {
+        Self {
+            task_id: webhook.task_id,
+            path: format!("/webhooks/{}", webhook.token),
+            token: webhook.token,
+            signed: webhook.secret.is_some(),
+            secret: webhook.secret.filter(|_| reveal_secret),
+            created_at: webhook.created_at,
+            last_triggered_at: webhook.last_triggered_at,
+        }
+    }
+}
+
+/// 加密保存的密钥，值只在执行器中解密
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secret {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// AES-256-GCM 随机数（十六进制）
+    pub nonce: String,
+    /// 密文（十六进制）
+    pub ciphertext: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 创建密钥请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSecretRequest {
+    pub name: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// 更新密钥请求，未提供的字段保持不变
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateSecretRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// 密钥详情，不包含密钥值
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretResponse {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Secret> for SecretResponse {
+    fn from(secret: Secret) -> Self {
+        Self {
+            name: secret.name,
+            description: secret.description,
+            created_at: secret.created_at,
+            updated_at: secret.updated_at,
+        }
+    }
+}
+
+/// 上传到数据库的 wasm 模块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmModule {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 模块内容的 SHA-256（十六进制），执行器按此缓存编译结果
+    pub sha256: String,
+    pub size_bytes: i64,
+    pub data: bson::Binary,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 上传 wasm 模块请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWasmModuleRequest {
+    pub name: String,
+    /// 模块内容（base64）
+    pub module_base64: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// wasm 模块详情，不包含模块内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmModuleResponse {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub sha256: String,
+    pub size_bytes: i64,
+    #[serde(
+        deserialize_with = "bson::serde_helpers::chrono_datetime_as_bson_datetime::deserialize"
+    )]
+    pub created_at: DateTime<Utc>,
+    #[serde(
+        deserialize_with = "bson::serde_helpers::chrono_datetime_as_bson_datetime::deserialize"
+    )]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<WasmModule> for WasmModuleResponse {
+    fn from(module: WasmModule) -> Self {
+        Self {
+            name: module.name,
+            description: module.description,
+            sha256: module.sha256,
+            size_bytes: module.size_bytes,
+            created_at: module.created_at,
+            updated_at: module.updated_at,
+        }
+    }
+}
+
+/// 上传到数据库的 gRPC 描述符集（`FileDescriptorSet` 的二进制编码）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrpcDescriptorSet {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 描述符集内容的 SHA-256（十六进制），执行器按此缓存解析结果
+    pub sha256: String,
+    pub size_bytes: i64,
+    /// 描述符集中定义的服务（完整名称）
+    #[serde(default)]
+    pub services: Vec<String>,
+    pub data: bson::Binary,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 上传 gRPC 描述符集请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateGrpcDescriptorSetRequest {
+    pub name: String,
+    /// `protoc --include_imports --descriptor_set_out` 生成的文件内容（base64）
+    pub descriptor_set_base64: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// gRPC 描述符集详情，不包含描述符集内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrpcDescriptorSetResponse {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub sha256: String,
+    pub size_bytes: i64,
+    #[serde(default)]
+    pub services: Vec<String>,
+    #[serde(
+        deserialize_with = "bson::serde_helpers::chrono_datetime_as_bson_datetime::deserialize"
+    )]
+    pub created_at: DateTime<Utc>,
+    #[serde(
+        deserialize_with = "bson::serde_helpers::chrono_datetime_as_bson_datetime::deserialize"
+    )]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<GrpcDescriptorSet> for GrpcDescriptorSetResponse {
+    fn from(set: GrpcDescriptorSet) -> Self {
+        Self {
+            name: set.name,
+            description: set.description,
+            sha256: set.sha256,
+            size_bytes: set.size_bytes,
+            services: set.services,
+            created_at: set.created_at,
+            updated_at: set.updated_at,
+        }
+    }
+}
+
+/// 创建暂停请求，时间为 Unix 时间戳（秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePauseRequest {
+    pub scope: PauseScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub queued_action: QueuedAction,
+    /// 开始时间，为空则立即生效
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starts_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ends_at: Option<i64>,
+}
+
+/// 暂停详情，附带当前是否生效
+#[derive(Debug, Clone, Serialize)]
+pub struct PauseResponse {
+    #[serde(flatten)]
+    pub pause: Pause,
+    pub active: bool,
+}
+
+/// 调度预览请求：提供 Cron 表达式或已有任务 ID 之一
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulePreviewRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    /// IANA 时区名称（如 `Asia/Shanghai`），缺省为调度器本地时区
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// 调度预览响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulePreviewResponse {
+    pub schedule: String,
+    pub timezone: String,
+    pub description: String,
+    pub next_triggers: Vec<String>,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -398,6 +1948,14 @@ pub struct StatsResponse {
     pub running_instances: u64,
     pub success_instances: u64,
     pub failed_instances: u64,
+    pub expired_instances: u64,
+    pub timed_out_instances: u64,
+    /// 执行器停止时被中断的实例数
+    pub interrupted_instances: u64,
+    /// 未按时开始执行的实例数
+    pub late_instances: u64,
+    /// 执行超时（超过运行 SLA）的实例数
+    pub overrun_instances: u64,
 }
 
 pub fn parse_object_id(id: &str) -> Result<ObjectId, String> {
@@ -422,6 +1980,9 @@ pub struct ClusterNode {
     pub memory_total: u64,
     pub active_tasks: u64,
     pub metadata: Option<String>,
+    /// 节点注册时上报的标签
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
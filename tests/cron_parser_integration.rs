@@ -1,26 +1,32 @@
 use chrono::{Duration, TimeZone, Timelike, Utc};
 use rapidcron::scheduler::cron_parser::CronParser;
 
+/// 整分钟的固定起点
+fn minute_start() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 7, 15, 12, 0, 0).unwrap()
+}
+
+/// 不落在任何时区整点上的固定起点，避开夏令时切换
+fn offset_start() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 7, 15, 12, 10, 7).unwrap()
+}
+
 #[test]
 fn test_cron_parser_integration_end_to_end() {
     let expr = "0/5 * * * * *";
     let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
 
-    let start = Utc::now();
+    let start = minute_start();
     let end = start + Duration::seconds(30);
 
     let triggers = parser.next_triggers_in_window(start, end);
 
-    assert!(!triggers.is_empty(), "应该有触发时间");
-    assert!(triggers.len() <= 7, "触发时间数量应该在合理范围内");
-
-    for (i, trigger) in triggers.iter().enumerate() {
-        assert!(trigger >= &start, "触发时间应该在开始时间之后");
-        assert!(trigger <= &end, "触发时间应该在结束时间之前");
-        if i > 0 {
-            let diff = trigger.timestamp() - triggers[i - 1].timestamp();
-            assert!(diff >= 4 && diff <= 6, "触发间隔应该在 5 秒左右");
-        }
+    // 窗口不包含起点、包含终点
+    assert_eq!(triggers.len(), 6);
+    assert_eq!(triggers[0], start + Duration::seconds(5));
+    assert_eq!(triggers[5], end);
+    for pair in triggers.windows(2) {
+        assert_eq!(pair[1] - pair[0], Duration::seconds(5), "触发间隔应为 5 秒");
     }
 }
 
@@ -29,13 +35,12 @@ fn test_cron_parser_integration_complex_schedule() {
     let expr = "0,15,30,45 * * * * *";
     let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
 
-    let start = Utc::now();
+    let start = minute_start();
     let end = start + Duration::minutes(2);
 
     let triggers = parser.next_triggers_in_window(start, end);
 
-    assert!(!triggers.is_empty(), "应该有触发时间");
-    assert!(triggers.len() <= 9, "触发时间数量应该在合理范围内");
+    assert_eq!(triggers.len(), 8);
 
     for trigger in &triggers {
         let seconds = trigger.timestamp() % 60;
@@ -51,13 +56,12 @@ fn test_cron_parser_integration_hourly_schedule() {
     let expr = "0 0 * * * *";
     let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
 
-    let start = Utc::now();
+    let start = offset_start();
     let end = start + Duration::hours(25);
 
     let triggers = parser.next_triggers_in_window(start, end);
 
-    assert!(!triggers.is_empty(), "应该有触发时间");
-    assert!(triggers.len() <= 26, "触发时间数量应该在合理范围内");
+    assert_eq!(triggers.len(), 25);
 
     for trigger in &triggers {
         assert!(trigger.timestamp() % 3600 == 0, "触发时间应该在整点");
@@ -69,13 +73,12 @@ fn test_cron_parser_integration_daily_schedule() {
     let expr = "0 0 0 * * *";
     let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
 
-    let start = Utc::now();
+    let start = offset_start();
     let end = start + Duration::days(3);
 
     let triggers = parser.next_triggers_in_window(start, end);
 
-    assert!(!triggers.is_empty(), "应该有触发时间");
-    assert!(triggers.len() <= 4, "触发时间数量应该在合理范围内");
+    assert_eq!(triggers.len(), 3);
 
     for trigger in &triggers {
         let minute = trigger.minute();
@@ -90,13 +93,13 @@ fn test_cron_parser_integration_weekly_schedule() {
     let expr = "0 0 0 * * 1";
     let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
 
-    let start = Utc::now();
+    let start = offset_start();
     let end = start + Duration::weeks(2);
 
     let triggers = parser.next_triggers_in_window(start, end);
 
-    assert!(!triggers.is_empty(), "应该有触发时间");
-    assert!(triggers.len() <= 3, "触发时间数量应该在合理范围内");
+    assert_eq!(triggers.len(), 2);
+    assert_eq!(triggers[1] - triggers[0], Duration::weeks(1));
 
     for trigger in &triggers {
         let minute = trigger.minute();
@@ -111,13 +114,13 @@ fn test_cron_parser_integration_monthly_schedule() {
     let expr = "0 0 0 1 * *";
     let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
 
-    let start = Utc::now();
+    let start = offset_start();
     let end = start + Duration::days(60);
 
     let triggers = parser.next_triggers_in_window(start, end);
 
-    assert!(!triggers.is_empty(), "应该有触发时间");
-    assert!(triggers.len() <= 3, "触发时间数量应该在合理范围内");
+    // 8 月 1 日与 9 月 1 日
+    assert_eq!(triggers.len(), 2);
 
     for trigger in &triggers {
         let minute = trigger.minute();
@@ -137,7 +140,7 @@ fn test_cron_parser_integration_range_expression() {
 
     let triggers = parser.next_triggers_in_window(start, end);
 
-    assert!(!triggers.is_empty(), "应该有触发时间");
+    assert_eq!(triggers.len(), 10, "起点不计入，应触发 1-10 秒");
 
     for trigger in &triggers {
         let seconds = trigger.timestamp() % 60;
@@ -150,12 +153,12 @@ fn test_cron_parser_integration_step_expression() {
     let expr = "0/15 * * * * *";
     let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
 
-    let start = Utc::now();
+    let start = minute_start();
     let end = start + Duration::seconds(60);
 
     let triggers = parser.next_triggers_in_window(start, end);
 
-    assert!(!triggers.is_empty(), "应该有触发时间");
+    assert_eq!(triggers.len(), 4);
 
     for trigger in &triggers {
         let seconds = trigger.timestamp() % 60;
@@ -168,7 +171,7 @@ fn test_cron_parser_integration_empty_window() {
     let expr = "0 0 0 1 1 *";
     let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
 
-    let start = Utc::now();
+    let start = minute_start();
     let end = start + Duration::seconds(60);
 
     let triggers = parser.next_triggers_in_window(start, end);
@@ -181,11 +184,76 @@ fn test_cron_parser_integration_very_long_window() {
     let expr = "* * * * * *";
     let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
 
-    let start = Utc::now();
+    let start = minute_start();
     let end = start + Duration::seconds(100);
 
     let triggers = parser.next_triggers_in_window(start, end);
 
-    assert!(!triggers.is_empty(), "应该有触发时间");
-    assert!(triggers.len() <= 101, "触发时间数量应该在合理范围内");
+    assert_eq!(triggers.len(), 100);
+}
+
+#[test]
+fn test_cron_parser_integration_next_triggers_in_timezone() {
+    let expr = "0 0 9 * * *";
+    let parser = CronParser::new(expr).expect("应该成功解析 Cron 表达式");
+
+    let tz: chrono_tz::Tz = "Asia/Shanghai".parse().unwrap();
+    let start = Utc
+        .with_ymd_and_hms(2025, 3, 12, 0, 0, 0)
+        .unwrap()
+        .with_timezone(&tz);
+
+    let triggers = parser.next_triggers(&start, 3);
+
+    assert_eq!(triggers.len(), 3);
+    assert_eq!(
+        triggers[0].with_timezone(&Utc),
+        Utc.with_ymd_and_hms(2025, 3, 12, 1, 0, 0).unwrap()
+    );
+    for trigger in &triggers {
+        assert_eq!(trigger.hour(), 9, "应按指定时区的 9 点触发");
+    }
+}
+
+#[test]
+fn test_cron_parser_integration_describe() {
+    let parser = CronParser::new("*/20 * * * * *").expect("应该成功解析 Cron 表达式");
+
+    assert_eq!(parser.describe(), "每 20 秒执行一次");
+    assert!(
+        parser
+            .warnings_at(minute_start())
+            .iter()
+            .any(|w| w.contains("每分钟")),
+        "秒级调度应该给出频率提示"
+    );
+}
+
+#[test]
+fn test_cron_parser_integration_triggers_between_half_open() {
+    let parser = CronParser::new("0 * * * * *").expect("应该成功解析 Cron 表达式");
+
+    let from = Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2025, 3, 12, 0, 3, 0).unwrap();
+
+    let triggers = parser.triggers_between(from, to, 100);
+
+    assert_eq!(triggers.len(), 3, "区间应包含起点、不包含终点");
+    assert_eq!(triggers[0], from);
+    assert_eq!(
+        triggers[2],
+        Utc.with_ymd_and_hms(2025, 3, 12, 0, 2, 0).unwrap()
+    );
+}
+
+#[test]
+fn test_cron_parser_integration_triggers_between_limit() {
+    let parser = CronParser::new("* * * * * *").expect("应该成功解析 Cron 表达式");
+
+    let from = Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap();
+    let to = from + Duration::hours(1);
+
+    let triggers = parser.triggers_between(from, to, 10);
+
+    assert_eq!(triggers.len(), 10, "应按上限截断");
 }
@@ -1,4 +1,9 @@
-use rapidcron::executor::retry::retry_logic::{RetryConfig, RetryStrategy};
+use chrono::{Duration, TimeZone, Utc};
+use mongodb::bson::oid::ObjectId;
+use rapidcron::clock::{Clock, ManualClock};
+use rapidcron::executor::retry::retry_logic::{RetryConfig, RetryStrategy, next_retry_time};
+use rapidcron::executor::task_queue::task_queue::delay_bucket;
+use rapidcron::types::{TaskInstance, TaskStatus, TriggeredBy};
 
 #[test]
 fn test_retry_strategy_fixed() {
@@ -207,3 +212,47 @@ fn test_retry_strategy_linear_initial_delay_zero() {
         );
     }
 }
+
+#[test]
+fn test_next_retry_time_follows_manual_clock() {
+    let start = Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+    let clock = ManualClock::new(start);
+    let strategy = RetryStrategy::Exponential {
+        base_delay_seconds: 5,
+        max_delay_seconds: 60,
+    };
+    let mut instance = TaskInstance {
+        id: Some(ObjectId::new()),
+        task_id: ObjectId::new(),
+        scheduled_time: start,
+        status: TaskStatus::Failed,
+        executor_id: None,
+        start_time: None,
+        end_time: None,
+        retry_count: 0,
+        result: None,
+        triggered_by: TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
+        created_at: start,
+    };
+
+    // 每次失败后时钟推进 3 秒，重试时间从失败时刻起按退避延迟计算
+    let mut retry_times = Vec::new();
+    for _ in 0..5 {
+        let retry_time = next_retry_time(&clock, &strategy, &instance);
+        retry_times.push((retry_time - start).num_seconds());
+        clock.set(retry_time + Duration::seconds(3));
+        instance.retry_count += 1;
+    }
+    assert_eq!(retry_times, vec![5, 18, 41, 84, 147]);
+
+    // 重试消息按剩余时间进入延迟队列
+    let retry_time = next_retry_time(&clock, &strategy, &instance);
+    assert_eq!(
+        delay_bucket(retry_time.timestamp() - clock.now().timestamp()),
+        Some(32)
+    );
+}
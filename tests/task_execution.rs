@@ -1,6 +1,9 @@
 use chrono::{Duration, Utc};
 use mongodb::bson::oid::ObjectId;
-use rapidcron::types::{ExecutionLog, ExecutionResult, TaskInstance, TaskStatus, TriggeredBy};
+use rapidcron::types::{
+    AssertionFailure, AssertionKind, ExecutionLog, ExecutionResult, SlaEventKind, TaskInstance,
+    TaskStatus, TriggeredBy,
+};
 
 #[test]
 fn test_task_instance_status_transitions() {
@@ -15,6 +18,10 @@ fn test_task_instance_status_transitions() {
         retry_count: 0,
         result: None,
         triggered_by: TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -36,6 +43,7 @@ fn test_task_instance_status_transitions() {
         output: Some("Success".to_string()),
         error: None,
         exit_code: Some(0),
+        failed_assertion: None,
     });
 
     assert_eq!(instance.status, TaskStatus::Success);
@@ -58,8 +66,13 @@ fn test_task_instance_failed_status() {
             output: None,
             error: Some("Error occurred".to_string()),
             exit_code: Some(1),
+            failed_assertion: None,
         }),
         triggered_by: TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -86,6 +99,10 @@ fn test_task_instance_manual_trigger() {
         retry_count: 0,
         result: None,
         triggered_by: TriggeredBy::Manual,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -98,6 +115,7 @@ fn test_execution_result_success() {
         output: Some("Task completed successfully".to_string()),
         error: None,
         exit_code: Some(0),
+        failed_assertion: None,
     };
 
     assert!(result.output.is_some());
@@ -111,6 +129,7 @@ fn test_execution_result_failure() {
         output: None,
         error: Some("Task failed".to_string()),
         exit_code: Some(1),
+        failed_assertion: None,
     };
 
     assert!(result.output.is_none());
@@ -127,6 +146,7 @@ fn test_execution_log_creation() {
         instance_id: ObjectId::new(),
         scheduled_time: Utc::now(),
         start_time: Some(Utc::now()),
+        start_latency_ms: None,
         end_time: Utc::now(),
         status: TaskStatus::Success,
         duration_ms: 1000,
@@ -150,6 +170,7 @@ fn test_execution_log_failure() {
         instance_id: ObjectId::new(),
         scheduled_time: Utc::now(),
         start_time: Some(Utc::now()),
+        start_latency_ms: None,
         end_time: Utc::now(),
         status: TaskStatus::Failed,
         duration_ms: 500,
@@ -176,6 +197,10 @@ fn test_task_instance_serialization() {
         retry_count: 0,
         result: None,
         triggered_by: TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -195,6 +220,7 @@ fn test_execution_log_serialization() {
         instance_id: ObjectId::new(),
         scheduled_time: Utc::now(),
         start_time: Some(Utc::now()),
+        start_latency_ms: None,
         end_time: Utc::now(),
         status: TaskStatus::Success,
         duration_ms: 1000,
@@ -222,6 +248,101 @@ fn test_task_status_equality() {
     assert_ne!(TaskStatus::Success, TaskStatus::Failed);
 }
 
+#[test]
+fn test_timed_out_status_serialization() {
+    assert_eq!(
+        serde_json::to_value(TaskStatus::TimedOut).unwrap(),
+        "timed_out"
+    );
+    let status: TaskStatus = serde_json::from_str("\"timed_out\"").unwrap();
+    assert_eq!(status, TaskStatus::TimedOut);
+}
+
+#[test]
+fn test_interrupted_status_serialization() {
+    assert_eq!(
+        serde_json::to_value(TaskStatus::Interrupted).unwrap(),
+        "interrupted"
+    );
+    let status: TaskStatus = serde_json::from_str("\"interrupted\"").unwrap();
+    assert_eq!(status, TaskStatus::Interrupted);
+}
+
+#[test]
+fn test_failed_assertion_serialization() {
+    let result = ExecutionResult {
+        output: Some(r#"{"status":"error"}"#.to_string()),
+        error: Some("断言失败: $.status 的值为 \"error\"，期望 \"ok\"".to_string()),
+        exit_code: Some(200),
+        failed_assertion: Some(AssertionFailure {
+            kind: AssertionKind::JsonPath,
+            message: "$.status 的值为 \"error\"，期望 \"ok\"".to_string(),
+        }),
+    };
+    let value = serde_json::to_value(&result).unwrap();
+    assert_eq!(value["failed_assertion"]["kind"], "json_path");
+
+    // 旧记录没有 failed_assertion 字段
+    let result: ExecutionResult = serde_json::from_str(r#"{"exit_code":0}"#).unwrap();
+    assert!(result.failed_assertion.is_none());
+}
+
+#[test]
+fn test_instance_sla_flags_serialization() {
+    let instance = TaskInstance {
+        id: None,
+        task_id: ObjectId::new(),
+        scheduled_time: Utc::now(),
+        status: TaskStatus::Expired,
+        executor_id: None,
+        start_time: None,
+        end_time: None,
+        retry_count: 0,
+        result: None,
+        triggered_by: TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: vec![SlaEventKind::StartLate, SlaEventKind::Expired],
+        trigger_payload: None,
+        parameters: None,
+        created_at: Utc::now(),
+    };
+
+    let value = serde_json::to_value(&instance).unwrap();
+
+    assert_eq!(value["status"], "expired");
+    assert_eq!(value["sla_flags"][0], "start_late");
+    assert_eq!(value["sla_flags"][1], "expired");
+}
+
+#[test]
+fn test_webhook_instance_keeps_trigger_payload() {
+    let instance = TaskInstance {
+        id: None,
+        task_id: ObjectId::new(),
+        scheduled_time: Utc::now(),
+        status: TaskStatus::Pending,
+        executor_id: None,
+        start_time: None,
+        end_time: None,
+        retry_count: 0,
+        result: None,
+        triggered_by: TriggeredBy::Webhook,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: Some(serde_json::json!({ "commit": "a1b2c3d", "branch": "main" })),
+        parameters: None,
+        created_at: Utc::now(),
+    };
+
+    let value = serde_json::to_value(&instance).unwrap();
+    assert_eq!(value["triggered_by"], "webhook");
+    assert_eq!(value["trigger_payload"]["branch"], "main");
+
+    let bson_doc = mongodb::bson::to_document(&instance).unwrap();
+    let restored: TaskInstance = mongodb::bson::from_document(bson_doc).unwrap();
+    assert_eq!(restored.trigger_payload, instance.trigger_payload);
+}
+
 #[test]
 fn test_triggered_by_equality() {
     assert_eq!(TriggeredBy::Scheduler, TriggeredBy::Scheduler);
@@ -246,6 +367,10 @@ fn test_task_instance_duration_calculation() {
         retry_count: 0,
         result: None,
         triggered_by: TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: start_time,
     };
 
@@ -270,8 +395,13 @@ fn test_task_instance_retry_count_increment() {
             output: None,
             error: Some("Error".to_string()),
             exit_code: Some(1),
+            failed_assertion: None,
         }),
         triggered_by: TriggeredBy::Scheduler,
+        priority_score: None,
+        sla_flags: Vec::new(),
+        trigger_payload: None,
+        parameters: None,
         created_at: Utc::now(),
     };
 
@@ -294,6 +424,7 @@ fn test_execution_log_output_summary_truncation() {
         instance_id: ObjectId::new(),
         scheduled_time: Utc::now(),
         start_time: Some(Utc::now()),
+        start_latency_ms: None,
         end_time: Utc::now(),
         status: TaskStatus::Success,
         duration_ms: 1000,
@@ -1,6 +1,10 @@
 use chrono::Utc;
 use mongodb::bson::oid::ObjectId;
-use rapidcron::types::{CreateTaskRequest, Task, TaskPayload, TaskType, UpdateTaskRequest};
+use rapidcron::types::{
+    CreatePauseRequest, CreateTaskRequest, ParameterType, PauseScope, QueuedAction, ResourceLimits,
+    Task, TaskParameter, TaskPayload, TaskRateLimit, TaskType, UpdateTaskRequest, WasmModuleRef,
+    check_untrusted_overrides, resolve_parameters,
+};
 
 #[test]
 fn test_create_task_request_to_task_command() {
@@ -8,13 +12,32 @@ fn test_create_task_request_to_task_command() {
         name: "test-task".to_string(),
         description: Some("Test task".to_string()),
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let task = request.to_task().expect("应该成功创建任务");
@@ -33,13 +56,32 @@ fn test_create_task_request_to_task_http() {
         name: "http-task".to_string(),
         description: Some("HTTP task".to_string()),
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("http".to_string()),
         schedule: "0 * * * * *".to_string(),
         enabled: true,
         command: None,
         url: Some("http://example.com/api".to_string()),
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let task = request.to_task().expect("应该成功创建任务");
@@ -55,13 +97,32 @@ fn test_create_task_request_empty_name() {
         name: "".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let result = request.to_task();
@@ -74,13 +135,32 @@ fn test_create_task_request_name_too_long() {
         name: "a".repeat(101),
         description: None,
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let result = request.to_task();
@@ -93,13 +173,32 @@ fn test_create_task_request_invalid_cron() {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "invalid-cron".to_string(),
         enabled: true,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let result = request.to_task();
@@ -112,13 +211,32 @@ fn test_create_task_request_invalid_timeout() {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(0),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let result = request.to_task();
@@ -131,13 +249,32 @@ fn test_create_task_request_timeout_too_large() {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(3601),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let result = request.to_task();
@@ -150,32 +287,265 @@ fn test_create_task_request_invalid_max_retries() {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(11),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let result = request.to_task();
     assert!(result.is_err(), "最大重试次数过大应该返回错误");
 }
 
+#[test]
+fn test_create_task_request_invalid_priority() {
+    let request = CreateTaskRequest {
+        name: "test-task".to_string(),
+        description: None,
+        dependency_ids: vec![],
+        tags: vec![],
+        task_type: Some("command".to_string()),
+        schedule: "0/5 * * * * *".to_string(),
+        enabled: true,
+        command: Some("echo 'Hello'".to_string()),
+        url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
+        timeout_seconds: Some(30),
+        max_retries: Some(3),
+        priority: Some(10),
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
+    };
+
+    let result = request.to_task();
+    assert!(result.is_err(), "优先级超出范围应该返回错误");
+}
+
+#[test]
+fn test_create_task_request_with_priority_and_deadline() {
+    let request = CreateTaskRequest {
+        name: "test-task".to_string(),
+        description: None,
+        dependency_ids: vec![],
+        tags: vec![],
+        task_type: Some("command".to_string()),
+        schedule: "0/5 * * * * *".to_string(),
+        enabled: true,
+        command: Some("echo 'Hello'".to_string()),
+        url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
+        timeout_seconds: Some(30),
+        max_retries: Some(3),
+        priority: Some(7),
+        deadline_seconds: Some(120),
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
+    };
+
+    let task = request.to_task().expect("应该成功创建任务");
+
+    assert_eq!(task.priority, Some(7));
+    assert_eq!(task.deadline_seconds, Some(120));
+    assert_eq!(task.effective_priority(), 7);
+}
+
+#[test]
+fn test_create_task_request_invalid_deadline() {
+    let request = CreateTaskRequest {
+        name: "test-task".to_string(),
+        description: None,
+        dependency_ids: vec![],
+        tags: vec![],
+        task_type: Some("command".to_string()),
+        schedule: "0/5 * * * * *".to_string(),
+        enabled: true,
+        command: Some("echo 'Hello'".to_string()),
+        url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
+        timeout_seconds: Some(30),
+        max_retries: Some(3),
+        priority: None,
+        deadline_seconds: Some(0),
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
+    };
+
+    let result = request.to_task();
+    assert!(result.is_err(), "截止期限为0应该返回错误");
+}
+
+#[test]
+fn test_create_task_request_with_sla() {
+    let request = CreateTaskRequest {
+        name: "test-task".to_string(),
+        description: None,
+        dependency_ids: vec![],
+        tags: vec![],
+        task_type: Some("command".to_string()),
+        schedule: "0/5 * * * * *".to_string(),
+        enabled: true,
+        command: Some("echo 'Hello'".to_string()),
+        url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
+        timeout_seconds: Some(30),
+        max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: Some(60),
+        run_sla_seconds: Some(300),
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
+    };
+
+    let task = request.to_task().expect("应该成功创建任务");
+
+    assert_eq!(task.start_sla_seconds, Some(60));
+    assert_eq!(task.run_sla_seconds, Some(300));
+}
+
+#[test]
+fn test_create_task_request_invalid_sla() {
+    let request = CreateTaskRequest {
+        name: "test-task".to_string(),
+        description: None,
+        dependency_ids: vec![],
+        tags: vec![],
+        task_type: Some("command".to_string()),
+        schedule: "0/5 * * * * *".to_string(),
+        enabled: true,
+        command: Some("echo 'Hello'".to_string()),
+        url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
+        timeout_seconds: Some(30),
+        max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: Some(-1),
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
+    };
+
+    let result = request.to_task();
+    assert!(result.is_err(), "SLA 时限为负数应该返回错误");
+}
+
 #[test]
 fn test_create_task_request_http_without_url() {
     let request = CreateTaskRequest {
         name: "http-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("http".to_string()),
         schedule: "0 * * * * *".to_string(),
         enabled: true,
         command: None,
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let result = request.to_task();
@@ -188,13 +558,32 @@ fn test_create_task_request_command_without_command() {
         name: "command-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0 * * * * *".to_string(),
         enabled: true,
         command: None,
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let result = request.to_task();
@@ -208,13 +597,32 @@ fn test_create_task_request_with_dependency_ids() {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![dep_id.to_hex()],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let task = request.to_task().expect("应该成功创建任务");
@@ -229,13 +637,32 @@ fn test_create_task_request_with_invalid_dependency_ids() {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec!["invalid-id".to_string()],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let task = request.to_task().expect("应该成功创建任务");
@@ -249,13 +676,32 @@ fn test_create_task_request_default_enabled() {
         name: "test-task".to_string(),
         description: None,
         dependency_ids: vec![],
+        tags: vec![],
         task_type: Some("command".to_string()),
         schedule: "0/5 * * * * *".to_string(),
         enabled: false,
         command: Some("echo 'Hello'".to_string()),
         url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
     };
 
     let task = request.to_task().expect("应该成功创建任务");
@@ -269,13 +715,28 @@ fn test_update_task_request_partial_update() {
         name: Some("updated-task".to_string()),
         description: None,
         dependency_ids: None,
+        tags: None,
         schedule: None,
         enabled: Some(false),
         task_type: None,
         command: None,
         url: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
         timeout_seconds: None,
         max_retries: None,
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: None,
     };
 
     assert_eq!(request.name, Some("updated-task".to_string()));
@@ -329,15 +790,29 @@ fn test_task_serialization() {
         name: "test-task".to_string(),
         description: Some("Test task".to_string()),
         dependency_ids: vec![],
+        tags: Vec::new(),
         task_type: TaskType::Command,
         schedule: "0/5 * * * * *".to_string(),
         enabled: true,
         payload: TaskPayload::Command {
             command: "echo 'Hello'".to_string(),
             timeout_seconds: Some(30),
+            env: None,
+            working_dir: None,
+            run_as_user: None,
+            stdin: None,
+            limits: None,
+            sandbox: None,
         },
         timeout_seconds: Some(30),
         max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: Vec::new(),
         created_at: Utc::now(),
         updated_at: Utc::now(),
         deleted_at: None,
@@ -350,6 +825,306 @@ fn test_task_serialization() {
     assert!(deserialized.is_ok(), "任务应该可以反序列化");
 }
 
+#[test]
+fn test_create_task_request_with_tags() {
+    let mut request = CreateTaskRequest {
+        name: "test-task".to_string(),
+        description: None,
+        dependency_ids: vec![],
+        tags: vec!["reporting".to_string(), "nightly".to_string()],
+        task_type: Some("command".to_string()),
+        schedule: "0/5 * * * * *".to_string(),
+        enabled: true,
+        command: Some("echo 'Hello'".to_string()),
+        url: None,
+        interpreter: None,
+        script: None,
+        wasm: None,
+        grpc: None,
+        env: None,
+        working_dir: None,
+        run_as_user: None,
+        stdin: None,
+        limits: None,
+        sandbox: None,
+        assertions: None,
+        timeout_seconds: Some(30),
+        max_retries: Some(3),
+        priority: None,
+        deadline_seconds: None,
+        start_sla_seconds: None,
+        run_sla_seconds: None,
+        max_concurrent_instances: None,
+        rate_limit: None,
+        parameters: vec![],
+    };
+
+    let task = request.to_task().expect("应该成功创建任务");
+    assert_eq!(task.tags, vec!["reporting", "nightly"]);
+
+    request.tags = vec!["  ".to_string()];
+    assert!(request.to_task().is_err(), "空标签应该返回错误");
+}
+
+#[test]
+fn test_create_task_request_with_parameters() {
+    let mut request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "daily-export",
+            "task_type": "command",
+            "schedule": "0 0 1 * * *",
+            "command": "export --date {{ scheduled_date }} --region {{ params.region }} --limit {{ params.limit }}",
+            "parameters": [
+                {"name": "region", "type": "string", "default": "cn"},
+                {"name": "limit", "type": "integer"}
+            ]
+        }"#,
+    )
+    .expect("应该可以解析带参数的任务请求");
+
+    let task = request.to_task().expect("应该成功创建任务");
+    assert_eq!(task.parameters.len(), 2);
+    assert_eq!(task.parameters[1].param_type, ParameterType::Integer);
+
+    let resolved = resolve_parameters(
+        &task.parameters,
+        serde_json::json!({"limit": 100}).as_object(),
+    )
+    .expect("覆盖值应该通过校验");
+    assert_eq!(resolved["region"], "cn");
+    assert_eq!(resolved["limit"], "100");
+
+    assert!(
+        resolve_parameters(&task.parameters, None).is_err(),
+        "缺少无默认值的参数应该返回错误"
+    );
+    assert!(
+        resolve_parameters(
+            &task.parameters,
+            serde_json::json!({"limit": "many"}).as_object()
+        )
+        .is_err(),
+        "类型不匹配应该返回错误"
+    );
+    assert!(
+        resolve_parameters(
+            &task.parameters,
+            serde_json::json!({"limit": 1, "unknown": 1}).as_object()
+        )
+        .is_err(),
+        "未声明的参数应该返回错误"
+    );
+
+    request.command = Some("export {{ params.missing }}".to_string());
+    assert!(request.to_task().is_err(), "引用未定义参数应该返回错误");
+
+    request.command = Some("export".to_string());
+    request.parameters = vec![TaskParameter {
+        name: "limit".to_string(),
+        param_type: ParameterType::Boolean,
+        default: Some(serde_json::json!(10)),
+        description: None,
+        allowed_values: None,
+        pattern: None,
+    }];
+    assert!(request.to_task().is_err(), "默认值类型不匹配应该返回错误");
+}
+
+#[test]
+fn test_parameter_constraints_and_webhook_overrides() {
+    let request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "deploy",
+            "task_type": "command",
+            "schedule": "0 0 1 * * *",
+            "command": "deploy --env {{ params.env }} --tag {{ params.tag }} --note {{ params.note }}",
+            "parameters": [
+                {"name": "env", "enum": ["staging", "prod"], "default": "staging"},
+                {"name": "tag", "pattern": "v[0-9]+\\.[0-9]+", "default": "v1.0"},
+                {"name": "note", "default": "none"}
+            ]
+        }"#,
+    )
+    .expect("应该可以解析带约束的参数");
+    let task = request.to_task().expect("应该成功创建任务");
+
+    let overrides = serde_json::json!({"env": "prod", "tag": "v2.3"});
+    assert!(resolve_parameters(&task.parameters, overrides.as_object()).is_ok());
+    assert!(check_untrusted_overrides(&task, overrides.as_object()).is_ok());
+
+    for invalid in [
+        serde_json::json!({"env": "dev"}),
+        serde_json::json!({"tag": "v2.3; reboot"}),
+    ] {
+        assert!(
+            resolve_parameters(&task.parameters, invalid.as_object()).is_err(),
+            "不满足 enum 或 pattern 的取值应该返回错误: {}",
+            invalid
+        );
+    }
+
+    // 未声明约束的参数只能由可信来源覆盖
+    let note = serde_json::json!({"note": "$(reboot)"});
+    assert!(resolve_parameters(&task.parameters, note.as_object()).is_ok());
+    assert!(check_untrusted_overrides(&task, note.as_object()).is_err());
+
+    let mut http_task = task.clone();
+    http_task.task_type = TaskType::Http;
+    assert!(check_untrusted_overrides(&http_task, note.as_object()).is_ok());
+
+    let mut request = request;
+    request.parameters[0].default = Some(serde_json::json!("dev"));
+    assert!(request.to_task().is_err(), "默认值不在 enum 中应该返回错误");
+    request.parameters[0].default = None;
+    request.parameters[1].pattern = Some("(".to_string());
+    assert!(request.to_task().is_err(), "无效的 pattern 应该返回错误");
+}
+
+#[test]
+fn test_create_task_request_with_limits() {
+    let mut request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "limited-task",
+            "schedule": "* * * * * *",
+            "command": "echo limited",
+            "max_concurrent_instances": 2,
+            "rate_limit": {"max_runs": 10, "window_seconds": 60}
+        }"#,
+    )
+    .expect("应该可以解析带限流配置的任务请求");
+
+    let task = request.to_task().expect("应该成功创建任务");
+    assert_eq!(task.max_concurrent_instances, Some(2));
+    assert_eq!(
+        task.rate_limit,
+        Some(TaskRateLimit {
+            max_runs: 10,
+            window_seconds: 60
+        })
+    );
+
+    request.max_concurrent_instances = Some(0);
+    assert!(request.to_task().is_err(), "并发实例上限为0应该返回错误");
+
+    request.max_concurrent_instances = None;
+    request.rate_limit = Some(TaskRateLimit {
+        max_runs: 1,
+        window_seconds: 86401,
+    });
+    assert!(request.to_task().is_err(), "时间窗口过大应该返回错误");
+}
+
+#[test]
+fn test_create_task_request_wasm() {
+    let mut request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "wasm-task",
+            "schedule": "* * * * * *",
+            "task_type": "wasm",
+            "wasm": {
+                "module": {"name": "report-v1"},
+                "args": ["--date", "{{ scheduled_date }}"],
+                "dirs": [{"host_path": "/srv/data", "guest_path": "/data"}],
+                "fuel": 1000000
+            },
+            "env": {"MODE": "daily"}
+        }"#,
+    )
+    .expect("应该可以解析 wasm 任务请求");
+
+    let task = request.to_task().expect("应该成功创建 wasm 任务");
+    assert_eq!(task.task_type, TaskType::Wasm);
+    let TaskPayload::Wasm { wasm, env, .. } = &task.payload else {
+        panic!("应为 wasm 载荷");
+    };
+    assert_eq!(wasm.module, WasmModuleRef::Name("report-v1".to_string()));
+    assert!(!wasm.dirs[0].writable, "目录默认只读");
+    assert_eq!(env.as_ref().unwrap()["MODE"], "daily");
+
+    request.run_as_user = Some("nobody".to_string());
+    assert!(request.to_task().is_err(), "wasm 任务不支持 run_as_user");
+    request.run_as_user = None;
+
+    request.wasm.as_mut().unwrap().dirs[0].host_path = "relative".to_string();
+    assert!(request.to_task().is_err(), "授权目录必须为绝对路径");
+
+    request.wasm = None;
+    assert!(request.to_task().is_err(), "wasm 任务必须提供模块");
+}
+
+#[test]
+fn test_create_task_request_grpc() {
+    let mut request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "grpc-task",
+            "schedule": "* * * * * *",
+            "task_type": "grpc",
+            "timeout_seconds": 30,
+            "grpc": {
+                "endpoint": "http://orders.internal:50051",
+                "method": "orders.v1.OrderService/CloseExpired",
+                "request": {"before": "{{ scheduled_time }}", "limit": 100},
+                "metadata": {"authorization": "Bearer ${secret:orders-token}"},
+                "expected_codes": ["OK", "NOT_FOUND"]
+            }
+        }"#,
+    )
+    .expect("应该可以解析 gRPC 任务请求");
+
+    let task = request.to_task().expect("应该成功创建 gRPC 任务");
+    assert_eq!(task.task_type, TaskType::Grpc);
+    let TaskPayload::Grpc {
+        grpc,
+        timeout_seconds,
+    } = &task.payload
+    else {
+        panic!("应为 gRPC 载荷");
+    };
+    assert_eq!(grpc.descriptor_set, None);
+    assert_eq!(grpc.request.as_ref().unwrap()["limit"], 100);
+    assert_eq!(*timeout_seconds, Some(30));
+
+    let valid = request.grpc.clone();
+    request.grpc.as_mut().unwrap().method = "CloseExpired".to_string();
+    assert!(request.to_task().is_err(), "方法名必须包含服务名");
+
+    request.grpc = valid.clone();
+    request.grpc.as_mut().unwrap().expected_codes = Some(vec!["MAYBE".to_string()]);
+    assert!(request.to_task().is_err(), "未知的状态码应该返回错误");
+
+    request.grpc = valid.clone();
+    request.grpc.as_mut().unwrap().endpoint = "orders.internal:50051".to_string();
+    assert!(request.to_task().is_err(), "地址必须包含 scheme");
+
+    request.grpc = valid;
+    request.env = Some([("MODE".to_string(), "daily".to_string())].into());
+    assert!(request.to_task().is_err(), "gRPC 任务不支持 env");
+}
+
+#[test]
+fn test_create_pause_request_deserialization() {
+    let task_id = ObjectId::new();
+    let json = format!(
+        r#"{{"scope": {{"type": "tasks", "task_ids": ["{}"]}}, "queued_action": "cancel", "ends_at": 1700000000}}"#,
+        task_id.to_hex()
+    );
+
+    let request: CreatePauseRequest = serde_json::from_str(&json).expect("应该可以解析暂停请求");
+    assert_eq!(
+        request.scope,
+        PauseScope::Tasks {
+            task_ids: vec![task_id]
+        }
+    );
+    assert_eq!(request.queued_action, QueuedAction::Cancel);
+    assert_eq!(request.starts_at, None);
+
+    let global: CreatePauseRequest =
+        serde_json::from_str(r#"{"scope": {"type": "global"}}"#).expect("应该可以解析全局暂停");
+    assert_eq!(global.scope, PauseScope::Global);
+    assert_eq!(global.queued_action, QueuedAction::Run);
+}
+
 #[test]
 fn test_paginated_response_from_items() {
     let items = vec!["item1", "item2", "item3", "item4", "item5"];
@@ -371,3 +1146,234 @@ fn test_api_response_success() {
     assert!(response.data.is_some());
     assert!(response.message.is_none());
 }
+
+#[test]
+fn test_create_task_request_with_command_options() {
+    let mut request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "nightly-report",
+            "task_type": "command",
+            "schedule": "0 0 2 * * *",
+            "command": "./report.sh",
+            "env": {"REPORT_DATE": "{{ scheduled_date }}"},
+            "working_dir": "/srv/reports",
+            "run_as_user": "reporter",
+            "stdin": "{{ task_name }}"
+        }"#,
+    )
+    .expect("应该可以解析带执行选项的命令任务请求");
+
+    let task = request.to_task().expect("应该成功创建任务");
+    match &task.payload {
+        TaskPayload::Command {
+            env,
+            working_dir,
+            run_as_user,
+            stdin,
+            limits: None,
+            sandbox: None,
+            ..
+        } => {
+            assert_eq!(env.as_ref().unwrap()["REPORT_DATE"], "{{ scheduled_date }}");
+            assert_eq!(working_dir.as_deref(), Some("/srv/reports"));
+            assert_eq!(run_as_user.as_deref(), Some("reporter"));
+            assert_eq!(stdin.as_deref(), Some("{{ task_name }}"));
+        }
+        _ => panic!("应该是命令任务载荷"),
+    }
+
+    request.working_dir = Some("relative/dir".to_string());
+    assert!(request.to_task().is_err(), "工作目录必须是绝对路径");
+    request.working_dir = None;
+
+    request.env = Some([("1BAD".to_string(), "x".to_string())].into());
+    assert!(request.to_task().is_err(), "无效的环境变量名应该返回错误");
+    request.env = None;
+
+    request.run_as_user = Some("Root User".to_string());
+    assert!(request.to_task().is_err(), "无效的运行用户应该返回错误");
+    request.run_as_user = None;
+
+    request.task_type = Some("http".to_string());
+    request.url = Some("http://localhost/health".to_string());
+    request.stdin = Some("payload".to_string());
+    assert!(request.to_task().is_err(), "HTTP 任务不支持命令执行选项");
+}
+
+#[test]
+fn test_create_task_request_with_resource_limits() {
+    let mut request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "sandboxed-job",
+            "task_type": "command",
+            "schedule": "0 0 * * * *",
+            "command": "./job.sh",
+            "limits": {"cpu_seconds": 30, "memory_bytes": 268435456, "open_files": 64},
+            "sandbox": {"no_network": true, "read_only_root": true}
+        }"#,
+    )
+    .expect("应该可以解析带资源限制的命令任务请求");
+
+    let task = request.to_task().expect("应该成功创建任务");
+    match &task.payload {
+        TaskPayload::Command {
+            limits: Some(limits),
+            sandbox: Some(sandbox),
+            ..
+        } => {
+            assert_eq!(limits.cpu_seconds, Some(30));
+            assert_eq!(limits.memory_bytes, Some(268_435_456));
+            assert_eq!(limits.open_files, Some(64));
+            assert_eq!(limits.max_processes, None);
+            assert!(sandbox.no_network);
+            assert!(sandbox.read_only_root);
+        }
+        _ => panic!("应该是带资源限制与隔离的命令任务载荷"),
+    }
+
+    request.limits = Some(ResourceLimits {
+        cpu_seconds: Some(0),
+        ..Default::default()
+    });
+    assert!(request.to_task().is_err(), "资源限制为0应该返回错误");
+
+    request.limits = Some(ResourceLimits {
+        memory_bytes: Some(1024),
+        ..Default::default()
+    });
+    assert!(request.to_task().is_err(), "过小的内存限制应该返回错误");
+    request.limits = None;
+
+    request.task_type = Some("http".to_string());
+    request.url = Some("http://localhost/health".to_string());
+    assert!(request.to_task().is_err(), "HTTP 任务不支持隔离选项");
+}
+
+#[test]
+fn test_create_task_request_with_http_assertions() {
+    let mut request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "health-check",
+            "task_type": "http",
+            "schedule": "0 */5 * * * *",
+            "url": "http://localhost/health",
+            "assertions": {
+                "status": [200],
+                "body_regex": "\"status\":\\s*\"ok\"",
+                "json_path": [{"path": "$.status", "equals": "ok"}],
+                "headers_present": ["content-type"],
+                "max_latency_ms": 2000
+            }
+        }"#,
+    )
+    .expect("应该可以解析带响应断言的 HTTP 任务请求");
+
+    let task = request.to_task().expect("应该成功创建任务");
+    match &task.payload {
+        TaskPayload::Http {
+            assertions: Some(assertions),
+            ..
+        } => {
+            assert_eq!(assertions.status, Some(vec![200]));
+            assert_eq!(assertions.json_path[0].path, "$.status");
+            assert_eq!(assertions.json_path[0].equals, serde_json::json!("ok"));
+            assert_eq!(assertions.headers_present, vec!["content-type"]);
+            assert_eq!(assertions.max_latency_ms, Some(2000));
+        }
+        _ => panic!("应该是带响应断言的 HTTP 载荷"),
+    }
+
+    request.assertions.as_mut().unwrap().body_regex = Some("(".to_string());
+    assert!(request.to_task().is_err(), "无效的正则应该返回错误");
+
+    request.task_type = Some("command".to_string());
+    request.command = Some("echo ok".to_string());
+    request.assertions.as_mut().unwrap().body_regex = None;
+    assert!(request.to_task().is_err(), "命令任务不支持响应断言");
+}
+
+#[test]
+fn test_create_task_request_with_secret_references() {
+    let mut request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "sync-report",
+            "task_type": "command",
+            "schedule": "0 0 3 * * *",
+            "command": "./sync.sh",
+            "env": {"API_TOKEN": "${secret:report-api.token}"}
+        }"#,
+    )
+    .expect("应该可以解析引用密钥的命令任务请求");
+
+    let task = request.to_task().expect("引用密钥的任务应该创建成功");
+    match &task.payload {
+        TaskPayload::Command { env, .. } => {
+            // 载荷中只保存引用，不保存密钥值
+            assert_eq!(
+                env.as_ref().unwrap()["API_TOKEN"],
+                "${secret:report-api.token}"
+            );
+        }
+        _ => panic!("应该是命令任务载荷"),
+    }
+
+    request.env = Some([("API_TOKEN".to_string(), "${secret:bad name}".to_string())].into());
+    assert!(request.to_task().is_err(), "无效的密钥名称应该返回错误");
+
+    request.env = None;
+    request.command = Some("./sync.sh --token ${secret:report-api.token".to_string());
+    assert!(request.to_task().is_err(), "未闭合的密钥引用应该返回错误");
+}
+
+#[test]
+fn test_create_task_request_with_script() {
+    let mut request: CreateTaskRequest = serde_json::from_str(
+        r#"{
+            "name": "cleanup-script",
+            "task_type": "script",
+            "schedule": "0 0 4 * * *",
+            "interpreter": "python3",
+            "script": "import os\nprint(f\"{{0}}\".format(os.environ['RUN_DATE']))\n",
+            "env": {"RUN_DATE": "{{ scheduled_date }}"},
+            "timeout_seconds": 120
+        }"#,
+    )
+    .expect("应该可以解析脚本任务请求");
+
+    let task = request.to_task().expect("应该成功创建脚本任务");
+    assert_eq!(task.task_type, TaskType::Script);
+    match &task.payload {
+        TaskPayload::Script {
+            interpreter,
+            script,
+            timeout_seconds,
+            env,
+            ..
+        } => {
+            assert_eq!(interpreter, "python3");
+            // 脚本内容不做模板校验与渲染
+            assert!(script.contains("{{0}}"));
+            assert_eq!(*timeout_seconds, Some(120));
+            assert_eq!(env.as_ref().unwrap()["RUN_DATE"], "{{ scheduled_date }}");
+        }
+        _ => panic!("应该是脚本任务载荷"),
+    }
+
+    // 载荷可以原样序列化与反序列化
+    let json = serde_json::to_string(&task.payload).unwrap();
+    let payload: TaskPayload = serde_json::from_str(&json).unwrap();
+    assert!(matches!(payload, TaskPayload::Script { .. }));
+
+    request.interpreter = Some("/bin/zsh".to_string());
+    assert!(
+        request.to_task().is_err(),
+        "不在允许列表中的解释器应该返回错误"
+    );
+
+    request.interpreter = Some("bash".to_string());
+    request.script = Some("   \n".to_string());
+    assert!(request.to_task().is_err(), "空脚本应该返回错误");
+
+    request.script = None;
+    assert!(request.to_task().is_err(), "脚本任务必须提供脚本内容");
+}